@@ -0,0 +1,41 @@
+extern crate troll;
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use troll::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+
+const ITERATIONS: u32 = 5;
+
+// Compares parsing with a single thread and with all cores.
+// Set TROLL_BENCH_ELF to a large binary to see the speedup.
+fn main() {
+    let elf_path = env::var("TROLL_BENCH_ELF")
+        .unwrap_or_else(|_| String::from("examples/many-compilation-units"));
+
+    let serial = bench(&elf_path, 1);
+    let parallel = bench(&elf_path, rayon::current_num_threads());
+    println!("{}", elf_path);
+    println!("  1 thread:   {:?}", serial);
+    println!(
+        "  {} threads: {:?} (x{:.2})",
+        rayon::current_num_threads(),
+        parallel,
+        serial.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
+
+fn bench(elf_path: &str, num_threads: usize) -> Duration {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap();
+    pool.install(|| {
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let mut usecase = DumpGlobalVariablesUsecase::new();
+            usecase.dump_global_variables(String::from(elf_path));
+        }
+        start.elapsed() / ITERATIONS
+    })
+}
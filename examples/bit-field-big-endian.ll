@@ -0,0 +1,30 @@
+; struct bit_field of bit-field.c for a big endian target, initialized as
+; { .c = 'x', .a = 5, .b = 85, .d = -2, .e = 2, .f = 0x123456789a }
+target datalayout = "E-m:e-p:32:32-i64:64-n32"
+target triple = "powerpc-unknown-linux-gnu"
+
+@bit_field = dso_local global [16 x i8] c"x\B5@\00\FF\FF\FF\FE\84\8D\15\9E&\80\00\00", align 8, !dbg !0
+
+!llvm.dbg.cu = !{!2}
+!llvm.module.flags = !{!18, !19}
+
+!0 = !DIGlobalVariableExpression(var: !1, expr: !DIExpression())
+!1 = distinct !DIGlobalVariable(name: "bit_field", scope: !2, file: !3, line: 8, type: !5, isLocal: false, isDefinition: true)
+!2 = distinct !DICompileUnit(language: DW_LANG_C99, file: !3, producer: "hand-written", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, globals: !4)
+!3 = !DIFile(filename: "bit-field-big-endian.ll", directory: "examples")
+!4 = !{!0}
+!5 = distinct !DICompositeType(tag: DW_TAG_structure_type, name: "bit_field", file: !3, line: 1, size: 128, elements: !6)
+!6 = !{!7, !9, !11, !12, !14, !16}
+!7 = !DIDerivedType(tag: DW_TAG_member, name: "c", scope: !5, file: !3, line: 2, baseType: !8, size: 8)
+!8 = !DIBasicType(name: "char", size: 8, encoding: DW_ATE_signed_char)
+!9 = !DIDerivedType(tag: DW_TAG_member, name: "a", scope: !5, file: !3, line: 3, baseType: !10, size: 3, offset: 8, flags: DIFlagBitField, extraData: i64 0)
+!10 = !DIBasicType(name: "unsigned int", size: 32, encoding: DW_ATE_unsigned)
+!11 = !DIDerivedType(tag: DW_TAG_member, name: "b", scope: !5, file: !3, line: 4, baseType: !10, size: 7, offset: 11, flags: DIFlagBitField, extraData: i64 0)
+!12 = !DIDerivedType(tag: DW_TAG_member, name: "d", scope: !5, file: !3, line: 5, baseType: !13, size: 32, offset: 32)
+!13 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!14 = !DIDerivedType(tag: DW_TAG_member, name: "e", scope: !5, file: !3, line: 6, baseType: !15, size: 2, offset: 64, flags: DIFlagBitField, extraData: i64 64)
+!15 = !DIBasicType(name: "unsigned char", size: 8, encoding: DW_ATE_unsigned_char)
+!16 = !DIDerivedType(tag: DW_TAG_member, name: "f", scope: !5, file: !3, line: 7, baseType: !17, size: 40, offset: 66, flags: DIFlagBitField, extraData: i64 64)
+!17 = !DIBasicType(name: "long long unsigned int", size: 64, encoding: DW_ATE_unsigned)
+!18 = !{i32 7, !"Dwarf Version", i32 4}
+!19 = !{i32 2, !"Debug Info Version", i32 3}
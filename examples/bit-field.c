@@ -0,0 +1,12 @@
+struct bit_field {
+  char c;
+  unsigned int a: 3;
+  unsigned int b: 7;
+  int d;
+  unsigned char e: 2;
+  unsigned long long f: 40;
+} bit_field;
+
+int main() {
+  return 0;
+}
@@ -0,0 +1,24 @@
+#include <stdio.h>
+#include <unistd.h>
+
+enum state {
+  IDLE,
+  RUNNING,
+  STOPPED,
+} state = IDLE;
+
+struct counter {
+  unsigned int ticks;
+  int delta: 4;
+} counter = { 1, 0 };
+
+int main() {
+  state = RUNNING;
+  counter.ticks = 42;
+  counter.delta = -3;
+  // tells that the values are set
+  puts("ready");
+  fflush(stdout);
+  pause();
+  return 0;
+}
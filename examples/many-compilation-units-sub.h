@@ -2,6 +2,6 @@
 #define __MANY_COMPILATION_UNITS_SUB_H__
 
 int sub1(int);
-int c;
+extern int c;
 
 #endif
@@ -0,0 +1,7 @@
+static volatile int counter = 1;
+
+int *sub_counter(void);
+
+int main() {
+  return counter + *sub_counter();
+}
@@ -0,0 +1,8 @@
+struct hoge {
+  int hoge;
+  char fuga;
+} hoge;
+
+int main() {
+  return 0;
+}
@@ -0,0 +1,10 @@
+typedef struct hoge {
+  int hoge;
+  char fuga;
+} Hoge;
+
+Hoge hoges[2];
+
+int main() {
+  return 0;
+}
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::global_variable_view::GlobalVariableView;
+use crate::library::elf_section::ElfSection;
+
+// Limits of memory usage, one per line like `.bss <= 48KiB`, where the left side is
+// - `.name`: a section
+// - `region NAME START..END`: the sections in the address range
+// - `unit NAME`: the variables defined in the compilation unit
+// - otherwise: the variables whose names match the pattern, where `*` and `?` are wildcards,
+//   and static variables also match with their units like `file.c:counter`
+// Sizes are bytes in decimal or hexadecimal, optionally followed by KiB, MiB or GiB.
+// `#` starts a comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Budget {
+    pub rules: Vec<BudgetRule>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetRule {
+    pub target: BudgetTarget,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetTarget {
+    Section(String),
+    Region {
+        name: String,
+        start: usize,
+        end: usize,
+    },
+    Unit(String),
+    Variable(String),
+}
+
+// How much of the limit is used
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetUsage {
+    pub target: BudgetTarget,
+    pub used: usize,
+    pub limit: usize,
+}
+
+impl BudgetUsage {
+    pub fn exceeded(&self) -> bool {
+        self.used > self.limit
+    }
+}
+
+impl Budget {
+    // Whether the rules need the compilation units of variables,
+    // which are also needed to match names qualified with the units
+    pub fn has_units(&self) -> bool {
+        self.rules.iter().any(|rule| match &rule.target {
+            BudgetTarget::Unit(_) => true,
+            BudgetTarget::Variable(pattern) => pattern.contains(':'),
+            _ => false,
+        })
+    }
+
+    // Variables are at the top level, and ones without addresses use no memory
+    pub fn check(
+        &self,
+        sections: &[ElfSection],
+        variable_views: &[GlobalVariableView],
+    ) -> Vec<BudgetUsage> {
+        let variables_size = |f: &dyn Fn(&GlobalVariableView) -> bool| -> usize {
+            variable_views
+                .iter()
+                .filter(|variable_view| variable_view.address.is_some() && f(variable_view))
+                .map(|variable_view| variable_view.size)
+                .sum()
+        };
+        self.rules
+            .iter()
+            .map(|rule| {
+                let used = match &rule.target {
+                    BudgetTarget::Section(name) => sections
+                        .iter()
+                        .filter(|section| &section.name == name)
+                        .map(|section| section.size)
+                        .sum(),
+                    // .tbss is the template of thread-local storage, which overlaps the following sections
+                    BudgetTarget::Region { start, end, .. } => sections
+                        .iter()
+                        .filter(|section| section.name != ".tbss")
+                        .map(|section| section.overlap(*start, *end))
+                        .sum(),
+                    BudgetTarget::Unit(name) => variables_size(&|variable_view| {
+                        let unit = variable_view.source.compilation_unit.as_ref();
+                        unit.is_some_and(|unit| unit.is_named(name))
+                    }),
+                    BudgetTarget::Variable(pattern) => variables_size(&|variable_view| {
+                        let name = variable_view.unqualified_name();
+                        matches_pattern(pattern.as_bytes(), name.as_bytes())
+                            || variable_view.qualified_name().is_some_and(|name| {
+                                matches_pattern(pattern.as_bytes(), name.as_bytes())
+                            })
+                    }),
+                };
+                BudgetUsage {
+                    target: rule.target.clone(),
+                    used,
+                    limit: rule.limit,
+                }
+            })
+            .collect()
+    }
+}
+
+// `*` matches any characters and `?` matches a character
+fn matches_pattern(pattern: &[u8], name: &[u8]) -> bool {
+    // the last `*` and the end of the characters it matches, where only the last `*`
+    // is backtracked since the earlier ones can match whatever the later one does
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == b'?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+// `0x100`, `256` or `48KiB`
+fn parse_size(s: &str) -> Result<usize, String> {
+    if s.starts_with("0x") {
+        return parse_number(s).ok_or_else(|| format!("invalid size: {}", s));
+    }
+    let index = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let scale = match &s[index..] {
+        "" | "B" => 1,
+        "KiB" | "K" => 1 << 10,
+        "MiB" | "M" => 1 << 20,
+        "GiB" | "G" => 1 << 30,
+        _ => return Err(format!("invalid size unit: {}", s)),
+    };
+    parse_number(&s[..index])
+        .ok_or_else(|| format!("invalid size: {}", s))?
+        .checked_mul(scale)
+        .ok_or_else(|| format!("too large size: {}", s))
+}
+
+fn parse_number(s: &str) -> Option<usize> {
+    match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+impl FromStr for BudgetRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (target, limit) = match s.find("<=") {
+            Some(index) => (s[..index].trim(), s[index + 2..].trim()),
+            None => return Err(format!("no `<=` in the rule: {}", s)),
+        };
+        let words: Vec<&str> = target.split_whitespace().collect();
+        let target = match words.as_slice() {
+            [] => return Err(format!("no target in the rule: {}", s)),
+            ["region", name, range] => {
+                let (start, end) = match range.find("..") {
+                    Some(index) => (&range[..index], &range[index + 2..]),
+                    None => return Err(format!("invalid region range: {}", range)),
+                };
+                let address = |s: &str| parse_number(s).ok_or(format!("invalid address: {}", s));
+                BudgetTarget::Region {
+                    name: name.to_string(),
+                    start: address(start)?,
+                    end: address(end)?,
+                }
+            }
+            ["unit", name] => BudgetTarget::Unit(name.to_string()),
+            [name] if name.starts_with('.') => BudgetTarget::Section(name.to_string()),
+            [name] => BudgetTarget::Variable(name.to_string()),
+            _ => return Err(format!("invalid target in the rule: {}", s)),
+        };
+        Ok(BudgetRule {
+            target,
+            limit: parse_size(limit)?,
+        })
+    }
+}
+
+impl FromStr for Budget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let rule = line
+                .parse()
+                .map_err(|message| format!("line {}: {}", i + 1, message))?;
+            rules.push(rule);
+        }
+        Ok(Budget { rules })
+    }
+}
+
+impl fmt::Display for BudgetTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetTarget::Section(name) | BudgetTarget::Variable(name) => write!(f, "{}", name),
+            BudgetTarget::Region { name, .. } => write!(f, "region {}", name),
+            BudgetTarget::Unit(name) => write!(f, "unit {}", name),
+        }
+    }
+}
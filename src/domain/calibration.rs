@@ -0,0 +1,53 @@
+use std::str::FromStr;
+
+use super::variable_path::VariablePath;
+
+// Values of variables to be patched, one per line like `hoges[1].hoge = 3`.
+// `#` starts a comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    pub parameters: Vec<Parameter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub path: VariablePath,
+    pub value: String,
+}
+
+impl FromStr for Parameter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, value) = match s.split_once('=') {
+            Some((path, value)) => (path.trim(), value.trim()),
+            None => return Err(format!("no `=` in the parameter: {}", s)),
+        };
+        if value.is_empty() {
+            return Err(format!("no value in the parameter: {}", s));
+        }
+        Ok(Parameter {
+            path: path.parse()?,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl FromStr for Calibration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parameters = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parameter = line
+                .parse()
+                .map_err(|message| format!("line {}: {}", i + 1, message))?;
+            parameters.push(parameter);
+        }
+        Ok(Calibration { parameters })
+    }
+}
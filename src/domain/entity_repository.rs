@@ -17,7 +17,15 @@ impl<E: Entity> Repository<E> {
         self.map.insert(entity.id().clone(), entity);
     }
 
+    pub fn merge(&mut self, other: &mut Self) {
+        self.map.extend(other.map.drain());
+    }
+
     pub fn find_by_id(&self, id: &E::Id) -> Option<&E> {
         self.map.get(id)
     }
+
+    pub fn find_all(&self) -> impl Iterator<Item = &E> {
+        self.map.values()
+    }
 }
@@ -1,4 +1,5 @@
 use super::global_variable::*;
+use super::source_location::SourceLocation;
 use super::type_entry::*;
 use super::variable_declaration_entry::*;
 use crate::library::dwarf::{DwarfInfo, DwarfTag};
@@ -32,11 +33,30 @@ impl FromDwarfInfoOutput {
             children_warnings: Vec::new(),
         }
     }
+
+    fn with_source(self, source: SourceLocation) -> Self {
+        match self {
+            Self::None => Self::None,
+            Self::GlobalVariable(global_variable) => {
+                Self::GlobalVariable(global_variable.with_source(source))
+            }
+            Self::TypeEntry {
+                entry,
+                children_warnings,
+            } => Self::TypeEntry {
+                entry: entry.with_source(source),
+                children_warnings,
+            },
+            Self::VariableDeclarationEntry(entry) => {
+                Self::VariableDeclarationEntry(entry.with_source(source))
+            }
+        }
+    }
 }
 
 impl EntryFactory {
     pub fn from_dwarf_info(entry: &DwarfInfo) -> Result<FromDwarfInfoOutput, String> {
-        match entry.tag() {
+        let output = match entry.tag() {
             DwarfTag::DW_TAG_variable => Self::variable_from_dwarf_info(entry),
             DwarfTag::DW_TAG_typedef => Self::typedef_from_dwarf_info(entry),
             DwarfTag::DW_TAG_volatile_type => Self::volatile_type_from_dwarf_info(entry),
@@ -52,7 +72,8 @@ impl EntryFactory {
             DwarfTag::DW_TAG_subrange_type => Ok(FromDwarfInfoOutput::None),
             DwarfTag::DW_TAG_formal_parameter => Ok(FromDwarfInfoOutput::None),
             DwarfTag::DW_TAG_unimplemented => Ok(FromDwarfInfoOutput::None),
-        }
+        };
+        output.map(|output| output.with_source(SourceLocation::from_dwarf_info(entry)))
     }
 
     fn variable_from_dwarf_info(entry: &DwarfInfo) -> Result<FromDwarfInfoOutput, String> {
@@ -78,7 +99,8 @@ impl EntryFactory {
                     Some(type_ref) => Ok(TypeEntryId::new(type_ref)),
                     None => Err("variable entry should have type"),
                 }?;
-                Ok(GlobalVariable::new_variable(address, name, type_ref))
+                Ok(GlobalVariable::new_variable(address, name, type_ref)
+                    .with_external(entry.external().unwrap_or(false)))
             }
             Some(dec_ref) => {
                 let spec = VariableDeclarationEntryId::new(dec_ref);
@@ -99,7 +121,8 @@ impl EntryFactory {
             Some(type_ref) => Ok(TypeEntryId::new(type_ref)),
             None => Err("variable entry with declaration should have type"),
         }?;
-        Ok(VariableDeclarationEntry::new(id, name, type_ref))
+        Ok(VariableDeclarationEntry::new(id, name, type_ref)
+            .with_external(entry.external().unwrap_or(false)))
     }
 
     fn typedef_from_dwarf_info(entry: &DwarfInfo) -> Result<FromDwarfInfoOutput, String> {
@@ -163,7 +186,7 @@ impl EntryFactory {
             None => Err("base_type entry should have size"),
         }?;
 
-        let entry = TypeEntry::new_base_type_entry(id, name, size);
+        let entry = TypeEntry::new_base_type_entry(id, name, size).with_encoding(entry.encoding());
         Ok(FromDwarfInfoOutput::new_type_entry_with_no_children_warnings(entry))
     }
 
@@ -264,6 +287,7 @@ impl EntryFactory {
                     children_warnings.push(String::from("member entry should have name"));
                     None
                 })?;
+                let location = entry.data_member_location().unwrap_or(0);
                 let type_ref = match entry.type_offset() {
                     Some(type_ref) => Some(TypeEntryId::new(type_ref)),
                     None => {
@@ -276,7 +300,7 @@ impl EntryFactory {
                 let bit_offset = entry.bit_offset();
 
                 Some(UnionTypeMemberEntry::new(
-                    name, type_ref, bit_size, bit_offset,
+                    name, location, type_ref, bit_size, bit_offset,
                 ))
             })
             .collect();
@@ -0,0 +1,331 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::variable_path::{split_qualified_name, VariablePath, VariablePathElement};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeName {
+    Structure(String),
+    Union(String),
+    Enum(String),
+    // typedef names and base types like `unsigned int`
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SizeOfOperand {
+    Type(TypeName),
+    // a single identifier can be either a typedef name or a variable
+    Path(VariablePath),
+}
+
+// C-like expressions to ask the layout of variables and types,
+// like `hoges[1].array[0]`, `&cfg.mode`, `sizeof(Hoge)` and `offsetof(struct hoge, array)`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Variable(VariablePath),
+    AddressOf(VariablePath),
+    SizeOf(SizeOfOperand),
+    OffsetOf {
+        type_name: TypeName,
+        member: Vec<VariablePathElement>,
+    },
+}
+
+impl Expression {
+    // The variable which the expression refers to, if any
+    pub fn variable_name(&self) -> Option<&str> {
+        match self {
+            Self::Variable(path) | Self::AddressOf(path) => Some(&path.name),
+            Self::SizeOf(SizeOfOperand::Path(path)) => Some(&path.name),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    Number(usize),
+    Punctuator(char),
+}
+
+pub(super) struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    source: String,
+    // accepts `[*]` for all the indices of arrays
+    any_index: bool,
+}
+
+impl Parser {
+    pub(super) fn new(source: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c.is_whitespace() {
+                continue;
+            }
+            // variables local to compilation units qualified with the unit, like `file.c:counter`,
+            // where the unit ends at the last `:` since it can have `:` like `C:\src\file.c`
+            let mut lookahead = chars.clone();
+            let mut word = c.to_string();
+            while let Some(c) = lookahead.next_if(|c| is_unit_character(*c) || *c == ':') {
+                word.push(c);
+            }
+            if let (true, (Some(unit), rest)) = (is_unit_character(c), split_qualified_name(&word))
+            {
+                let name: String = rest
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                    .collect();
+                if name.is_empty() {
+                    return Err(format!("no variable after {}: in {}", unit, source));
+                }
+                tokens.push(Token::Identifier(format!("{}:{}", unit, name)));
+                // the rest of the word like `.member` is read as the other tokens
+                for _ in 0..unit.chars().count() + name.len() {
+                    chars.next();
+                }
+                continue;
+            }
+            if c.is_ascii_alphanumeric() || c == '_' {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    word.push(c);
+                }
+                if c.is_ascii_digit() {
+                    let number = match word.strip_prefix("0x").or(word.strip_prefix("0X")) {
+                        Some(hex) => usize::from_str_radix(hex, 16),
+                        None => word.parse(),
+                    };
+                    let number =
+                        number.map_err(|_| format!("invalid number: {} in {}", word, source))?;
+                    tokens.push(Token::Number(number));
+                } else {
+                    tokens.push(Token::Identifier(word));
+                }
+            } else if "&.[](),*".contains(c) {
+                tokens.push(Token::Punctuator(c));
+            } else {
+                return Err(format!("unexpected character: {} in {}", c, source));
+            }
+        }
+        Ok(Parser {
+            tokens,
+            position: 0,
+            source: String::from(source),
+            any_index: false,
+        })
+    }
+
+    fn error(&self) -> String {
+        match self.tokens.get(self.position) {
+            Some(token) => format!("unexpected token: {} in {}", token, self.source),
+            None => format!("unexpected end of expression: {}", self.source),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next_if(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.next_if(&Token::Punctuator(c)) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error()),
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Identifier(identifier)) if !is_keyword(identifier) => {
+                let identifier = identifier.clone();
+                self.position += 1;
+                Ok(identifier)
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    pub(super) fn any_index(mut self, any_index: bool) -> Self {
+        self.any_index = any_index;
+        self
+    }
+
+    pub(super) fn variable_path_to_end(mut self) -> Result<VariablePath, String> {
+        let path = self.variable_path()?;
+        self.expect_end()?;
+        Ok(path)
+    }
+
+    fn variable_path(&mut self) -> Result<VariablePath, String> {
+        let name = self.identifier()?;
+        let elements = self.path_elements()?;
+        Ok(VariablePath { name, elements })
+    }
+
+    fn path_elements(&mut self) -> Result<Vec<VariablePathElement>, String> {
+        let mut elements = Vec::new();
+        loop {
+            if self.next_if(&Token::Punctuator('.')) {
+                elements.push(VariablePathElement::Member(self.identifier()?));
+            } else if self.next_if(&Token::Punctuator('[')) {
+                let element = match self.peek() {
+                    Some(Token::Number(index)) => VariablePathElement::Index(*index),
+                    Some(Token::Punctuator('*')) if self.any_index => VariablePathElement::AnyIndex,
+                    _ => return Err(self.error()),
+                };
+                self.position += 1;
+                self.expect(']')?;
+                elements.push(element);
+            } else {
+                return Ok(elements);
+            }
+        }
+    }
+
+    fn type_name(&mut self) -> Result<TypeName, String> {
+        let keyword = |parser: &Self, keyword: &str| {
+            parser.peek() == Some(&Token::Identifier(String::from(keyword)))
+        };
+        for (tag, constructor) in [
+            ("struct", TypeName::Structure as fn(String) -> TypeName),
+            ("union", TypeName::Union),
+            ("enum", TypeName::Enum),
+        ]
+        .iter()
+        {
+            if keyword(self, tag) {
+                self.position += 1;
+                return Ok(constructor(self.identifier()?));
+            }
+        }
+        // base types can consist of several words
+        let mut words = vec![self.identifier()?];
+        while let Some(Token::Identifier(word)) = self.peek() {
+            words.push(word.clone());
+            self.position += 1;
+        }
+        Ok(TypeName::Named(words.join(" ")))
+    }
+
+    // `sizeof(struct hoge)`, `sizeof(unsigned int)`, `sizeof(Hoge)` or `sizeof hoges[1]`
+    fn size_of_operand(&mut self) -> Result<SizeOfOperand, String> {
+        if !self.next_if(&Token::Punctuator('(')) {
+            return Ok(SizeOfOperand::Path(self.variable_path()?));
+        }
+        let start = self.position;
+        let operand = match self.variable_path() {
+            Ok(path) if self.peek() == Some(&Token::Punctuator(')')) => SizeOfOperand::Path(path),
+            _ => {
+                self.position = start;
+                SizeOfOperand::Type(self.type_name()?)
+            }
+        };
+        self.expect(')')?;
+        Ok(operand)
+    }
+
+    fn expression(&mut self) -> Result<Expression, String> {
+        let expression = if self.next_if(&Token::Punctuator('&')) {
+            Expression::AddressOf(self.variable_path()?)
+        } else if self.next_if(&Token::Identifier(String::from("sizeof"))) {
+            Expression::SizeOf(self.size_of_operand()?)
+        } else if self.next_if(&Token::Identifier(String::from("offsetof"))) {
+            self.expect('(')?;
+            let type_name = self.type_name()?;
+            self.expect(',')?;
+            let mut member = vec![VariablePathElement::Member(self.identifier()?)];
+            member.append(&mut self.path_elements()?);
+            self.expect(')')?;
+            Expression::OffsetOf { type_name, member }
+        } else {
+            Expression::Variable(self.variable_path()?)
+        };
+        self.expect_end()?;
+        Ok(expression)
+    }
+}
+
+fn is_unit_character(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_.-/\\".contains(c)
+}
+
+fn is_keyword(identifier: &str) -> bool {
+    matches!(
+        identifier,
+        "sizeof" | "offsetof" | "struct" | "union" | "enum"
+    )
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Identifier(identifier) => write!(f, "{}", identifier),
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Punctuator(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Parser::new(source)?.expression()
+    }
+}
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeName::Structure(name) => write!(f, "struct {}", name),
+            TypeName::Union(name) => write!(f, "union {}", name),
+            TypeName::Enum(name) => write!(f, "enum {}", name),
+            TypeName::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Variable(path) => write!(f, "{}", path),
+            Expression::AddressOf(path) => write!(f, "&{}", path),
+            Expression::SizeOf(SizeOfOperand::Type(type_name)) => {
+                write!(f, "sizeof({})", type_name)
+            }
+            Expression::SizeOf(SizeOfOperand::Path(path)) => write!(f, "sizeof({})", path),
+            Expression::OffsetOf { type_name, member } => {
+                write!(f, "offsetof({}, ", type_name)?;
+                for (i, element) in member.iter().enumerate() {
+                    match element {
+                        VariablePathElement::Member(name) if i == 0 => write!(f, "{}", name)?,
+                        VariablePathElement::Member(name) => write!(f, ".{}", name)?,
+                        VariablePathElement::Index(index) => write!(f, "[{}]", index)?,
+                        VariablePathElement::AnyIndex => write!(f, "[*]")?,
+                    }
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use super::expression::*;
+use super::global_variable::*;
+use super::global_variable_view::TypeView;
+use super::global_variable_view_factory::GlobalVariableViewFactory;
+use super::type_entry::*;
+use super::type_entry_repository::TypeEntryRepository;
+use super::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use super::variable_path::*;
+use crate::library::dwarf::Location;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionValue {
+    // an object which a variable path designates
+    Object {
+        address: Option<Address>,
+        size: usize,
+        bit_size: Option<usize>,
+        bit_offset: Option<usize>,
+        type_view: TypeView,
+    },
+    // the address of an object
+    Pointer {
+        address: Option<Address>,
+        type_view: TypeView,
+    },
+    // the results of sizeof and offsetof
+    Integer(usize),
+}
+
+// An object on the way of resolving a variable path
+struct Designated {
+    address: Option<Address>,
+    type_ref: TypeEntryId,
+    bit_size: Option<usize>,
+    bit_offset: Option<usize>,
+}
+
+// Evaluates expressions with the type entries, without creating the views of whole variables
+pub struct ExpressionEvaluator<'type_repo, 'dec_repo, 'variables> {
+    type_entry_repository: &'type_repo TypeEntryRepository,
+    variable_declaration_repository: &'dec_repo VariableDeclarationEntryRepository,
+    global_variables: &'variables [GlobalVariable],
+}
+
+impl<'type_repo, 'dec_repo, 'variables> ExpressionEvaluator<'type_repo, 'dec_repo, 'variables> {
+    pub fn new(
+        type_entry_repository: &'type_repo TypeEntryRepository,
+        variable_declaration_repository: &'dec_repo VariableDeclarationEntryRepository,
+        global_variables: &'variables [GlobalVariable],
+    ) -> Self {
+        Self {
+            type_entry_repository,
+            variable_declaration_repository,
+            global_variables,
+        }
+    }
+
+    pub fn evaluate(&self, expression: &Expression) -> Result<ExpressionValue, String> {
+        match expression {
+            Expression::Variable(path) => {
+                let designated = self.designate(path)?;
+                Ok(ExpressionValue::Object {
+                    size: self.size_of(&designated.type_ref)?,
+                    type_view: self.type_view(&designated.type_ref)?,
+                    address: designated.address,
+                    bit_size: designated.bit_size,
+                    bit_offset: designated.bit_offset,
+                })
+            }
+            Expression::AddressOf(path) => {
+                let designated = self.designate(path)?;
+                if designated.bit_size.is_some() {
+                    return Err(format!("cannot take address of bit-field: {}", path));
+                }
+                Ok(ExpressionValue::Pointer {
+                    address: designated.address,
+                    type_view: TypeView::new_pointer_type_view(
+                        self.type_view(&designated.type_ref)?,
+                    ),
+                })
+            }
+            Expression::SizeOf(SizeOfOperand::Type(type_name)) => {
+                let type_ref = self.find_type(type_name)?;
+                self.size_of(&type_ref).map(ExpressionValue::Integer)
+            }
+            Expression::SizeOf(SizeOfOperand::Path(path)) => {
+                // typedef names hide variables as C does
+                let type_name = TypeName::Named(path.name.clone());
+                let type_ref = match self.find_type(&type_name) {
+                    Ok(type_ref) if path.elements.is_empty() => type_ref,
+                    _ => self.designate(path)?.type_ref,
+                };
+                self.size_of(&type_ref).map(ExpressionValue::Integer)
+            }
+            Expression::OffsetOf { type_name, member } => {
+                let base = Designated {
+                    address: Some(Address::new(Location::new(0))),
+                    type_ref: self.find_type(type_name)?,
+                    bit_size: None,
+                    bit_offset: None,
+                };
+                let designated = self.designate_elements(base, member)?;
+                if designated.bit_size.is_some() {
+                    return Err(format!("cannot take offset of bit-field: {}", expression));
+                }
+                Ok(ExpressionValue::Integer(
+                    designated.address.map_or(0, Address::into),
+                ))
+            }
+        }
+    }
+
+    fn designate(&self, path: &VariablePath) -> Result<Designated, String> {
+        let variable = self.find_variable(&path.name)?;
+        self.designate_elements(variable, &path.elements)
+    }
+
+    fn designate_elements(
+        &self,
+        mut designated: Designated,
+        elements: &[VariablePathElement],
+    ) -> Result<Designated, String> {
+        for element in elements {
+            let type_entry = self.underlying_type(&designated.type_ref)?;
+            designated = match (element, &type_entry.kind) {
+                (
+                    VariablePathElement::Member(name),
+                    TypeEntryKind::StructureType { members, .. },
+                ) => {
+                    let member = members
+                        .iter()
+                        .find(|member| &member.name == name)
+                        .ok_or_else(|| format!("no such member: {}", name))?;
+                    Self::member(designated.address, member)?
+                }
+                (VariablePathElement::Member(name), TypeEntryKind::UnionType { members, .. }) => {
+                    let member = members
+                        .iter()
+                        .find(|member| &member.name == name)
+                        .ok_or_else(|| format!("no such member: {}", name))?;
+                    Self::member(designated.address, member)?
+                }
+                (
+                    VariablePathElement::Index(index),
+                    TypeEntryKind::ArrayType {
+                        element_type_ref,
+                        upper_bound,
+                    },
+                ) => {
+                    if matches!(upper_bound, Some(upper_bound) if index > upper_bound) {
+                        return Err(format!("index out of bounds: [{}]", index));
+                    }
+                    let element_size = self.size_of(element_type_ref)?;
+                    let address = designated
+                        .address
+                        .map(|address| {
+                            index
+                                .checked_mul(element_size)
+                                .and_then(|offset| address.add(offset))
+                                .ok_or_else(|| format!("address out of range: [{}]", index))
+                        })
+                        .transpose()?;
+                    Designated {
+                        address,
+                        type_ref: element_type_ref.clone(),
+                        bit_size: None,
+                        bit_offset: None,
+                    }
+                }
+                (VariablePathElement::Member(name), _) => {
+                    return Err(format!("member of non-structure: .{}", name))
+                }
+                (VariablePathElement::Index(index), _) => {
+                    return Err(format!("subscript of non-array: [{}]", index))
+                }
+                (VariablePathElement::AnyIndex, _) => {
+                    return Err(String::from("[*] is not an index"))
+                }
+            }
+        }
+        Ok(designated)
+    }
+
+    fn member<T>(
+        base_address: Option<Address>,
+        member: &MemberEntry<T>,
+    ) -> Result<Designated, String> {
+        let address = base_address
+            .map(|address| {
+                address
+                    .add(member.location)
+                    .ok_or_else(|| format!("address out of range: {}", member.name))
+            })
+            .transpose()?;
+        Ok(Designated {
+            address,
+            type_ref: member.type_ref.clone(),
+            bit_size: member.bit_size,
+            bit_offset: member.bit_offset,
+        })
+    }
+
+    // Prefers the definition with address if the name is defined in several units.
+    // Variables local to compilation units can be qualified with the unit, like `file.c:counter`.
+    fn find_variable(&self, name: &str) -> Result<Designated, String> {
+        let mut variables = self.global_variables.iter().filter_map(|variable| {
+            let (address, variable_name, type_ref, external) = match variable {
+                GlobalVariable::NoSpec {
+                    address,
+                    name,
+                    type_ref,
+                    external,
+                    ..
+                } => (address, name, type_ref, *external),
+                GlobalVariable::HasSpec { address, spec, .. } => {
+                    let dec = self.variable_declaration_repository.find_by_id(spec)?;
+                    (address, &dec.name, &dec.type_ref, dec.external)
+                }
+            };
+            let unit = variable.source().compilation_unit.as_ref();
+            let matches = match split_qualified_name(name) {
+                (Some(unit_name), name) => {
+                    !external
+                        && unit.is_some_and(|unit| unit.is_named(unit_name))
+                        && variable_name == name
+                }
+                (None, name) => variable_name == name,
+            };
+            if matches {
+                Some(Designated {
+                    address: address.clone(),
+                    type_ref: type_ref.clone(),
+                    bit_size: None,
+                    bit_offset: None,
+                })
+            } else {
+                None
+            }
+        });
+        let first = variables
+            .next()
+            .ok_or_else(|| format!("no such variable: {}", name))?;
+        if first.address.is_some() {
+            return Ok(first);
+        }
+        Ok(variables
+            .find(|variable| variable.address.is_some())
+            .unwrap_or(first))
+    }
+
+    fn find_type(&self, type_name: &TypeName) -> Result<TypeEntryId, String> {
+        self.type_entry_repository
+            .find_all()
+            .find(|type_entry| match (type_name, &type_entry.kind) {
+                (TypeName::Structure(name), TypeEntryKind::StructureType { name: found, .. })
+                | (TypeName::Union(name), TypeEntryKind::UnionType { name: found, .. })
+                | (TypeName::Enum(name), TypeEntryKind::EnumType { name: found, .. }) => {
+                    found.as_ref() == Some(name)
+                }
+                (TypeName::Named(name), TypeEntryKind::TypeDef { name: found, .. })
+                | (TypeName::Named(name), TypeEntryKind::BaseType { name: found, .. }) => {
+                    found == name
+                }
+                _ => false,
+            })
+            .map(TypeEntry::id)
+            .ok_or_else(|| format!("no such type: {}", type_name))
+    }
+
+    fn find_type_entry(&self, type_ref: &TypeEntryId) -> Result<&TypeEntry, String> {
+        self.type_entry_repository
+            .find_by_id(type_ref)
+            .ok_or_else(|| {
+                let offset: usize = type_ref.clone().into();
+                format!("unknown type: offset = {:#x}", offset)
+            })
+    }
+
+    // The type entry without typedefs and qualifiers
+    fn underlying_type(&self, type_ref: &TypeEntryId) -> Result<&TypeEntry, String> {
+        let type_entry = self.find_type_entry(type_ref)?;
+        match &type_entry.kind {
+            TypeEntryKind::TypeDef { type_ref, .. }
+            | TypeEntryKind::VolatileType { type_ref }
+            | TypeEntryKind::ConstType { type_ref } => self.underlying_type(type_ref),
+            _ => Ok(type_entry),
+        }
+    }
+
+    fn size_of(&self, type_ref: &TypeEntryId) -> Result<usize, String> {
+        match &self.underlying_type(type_ref)?.kind {
+            TypeEntryKind::PointerType { size, .. }
+            | TypeEntryKind::BaseType { size, .. }
+            | TypeEntryKind::StructureType { size, .. }
+            | TypeEntryKind::UnionType { size, .. } => Ok(*size),
+            TypeEntryKind::EnumType { type_ref, .. } => self.size_of(type_ref),
+            TypeEntryKind::ArrayType {
+                element_type_ref,
+                upper_bound: Some(upper_bound),
+            } => Ok((upper_bound + 1) * self.size_of(element_type_ref)?),
+            TypeEntryKind::ArrayType {
+                upper_bound: None, ..
+            } => Err(String::from("size of array with unknown bound")),
+            TypeEntryKind::FunctionType { .. } => Err(String::from("size of function")),
+            TypeEntryKind::TypeDef { .. }
+            | TypeEntryKind::VolatileType { .. }
+            | TypeEntryKind::ConstType { .. } => unreachable!(),
+        }
+    }
+
+    fn type_view(&self, type_ref: &TypeEntryId) -> Result<TypeView, String> {
+        GlobalVariableViewFactory::new(
+            self.type_entry_repository,
+            self.variable_declaration_repository,
+        )
+        .type_view_from_type_entry(type_ref)
+        .ok_or_else(|| {
+            let offset: usize = type_ref.clone().into();
+            format!("unknown type: offset = {:#x}", offset)
+        })
+    }
+}
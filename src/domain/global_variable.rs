@@ -1,5 +1,6 @@
 use crate::library::dwarf;
 
+use super::source_location::SourceLocation;
 use super::type_entry::TypeEntryId;
 use super::variable_declaration_entry::VariableDeclarationEntryId;
 
@@ -10,8 +11,8 @@ impl Address {
         Address(location)
     }
 
-    pub fn add(&mut self, size: usize) {
-        self.0.add(size);
+    pub fn add(&self, size: usize) -> Option<Address> {
+        self.0.add(size).map(Address)
     }
 }
 
@@ -27,10 +28,14 @@ pub enum GlobalVariable {
         address: Option<Address>,
         name: String,
         type_ref: TypeEntryId,
+        // false for variables local to the compilation unit, like `static int counter;`
+        external: bool,
+        source: SourceLocation,
     },
     HasSpec {
         address: Option<Address>,
         spec: VariableDeclarationEntryId,
+        source: SourceLocation,
     },
 }
 
@@ -40,6 +45,8 @@ impl GlobalVariable {
             address,
             name,
             type_ref,
+            external: true,
+            source: SourceLocation::default(),
         }
     }
 
@@ -47,6 +54,31 @@ impl GlobalVariable {
         address: Option<Address>,
         spec: VariableDeclarationEntryId,
     ) -> Self {
-        Self::HasSpec { address, spec }
+        Self::HasSpec {
+            address,
+            spec,
+            source: SourceLocation::default(),
+        }
+    }
+
+    // Variables with specification take the linkage of their declarations
+    pub fn with_external(mut self, external: bool) -> Self {
+        if let Self::NoSpec { external: e, .. } = &mut self {
+            *e = external;
+        }
+        self
+    }
+
+    pub fn with_source(mut self, source: SourceLocation) -> Self {
+        match &mut self {
+            Self::NoSpec { source: s, .. } | Self::HasSpec { source: s, .. } => *s = source,
+        }
+        self
+    }
+
+    pub fn source(&self) -> &SourceLocation {
+        match self {
+            Self::NoSpec { source, .. } | Self::HasSpec { source, .. } => source,
+        }
     }
 }
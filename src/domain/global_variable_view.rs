@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+
 use super::global_variable::Address;
-use super::type_entry::EnumeratorEntry;
+use super::source_location::SourceLocation;
+use super::type_entry::{EnumeratorEntry, TypeEntryId};
+use super::value::Value;
+use super::variable_path::{split_qualified_name, VariablePathElement};
+use crate::library::dwarf::{BaseTypeEncoding, CompilationUnit};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct GlobalVariableView {
@@ -10,12 +16,137 @@ pub struct GlobalVariableView {
     pub bit_offset: Option<usize>,
     pub type_view: TypeView,
     pub children: Vec<GlobalVariableView>,
+    // false for variables local to the compilation unit, only for variables at the top level
+    pub external: bool,
+    // where the variable and its type are declared, only for variables at the top level
+    pub source: SourceLocation,
+    pub type_source: SourceLocation,
+    // decoded from a memory, only for variables without children and pointers
+    pub value: Option<Value>,
+    // the type pointers point to, to follow them in a memory
+    pub pointee_type_ref: Option<TypeEntryId>,
 }
 
 impl GlobalVariableView {
+    // Qualifies variables local to compilation units with the unit, like `file.c:counter`,
+    // if other variables have the same name, so that the names identify the variables.
+    // The units with the same key are told apart by their paths.
+    pub fn qualify_names(variable_views: &mut [GlobalVariableView]) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for variable_view in variable_views.iter() {
+            *counts.entry(variable_view.name.clone()).or_insert(0) += 1;
+        }
+        let mut qualified_counts: HashMap<String, usize> = HashMap::new();
+        for variable_view in variable_views.iter() {
+            if let Some(name) = variable_view.qualified_name() {
+                *qualified_counts.entry(name).or_insert(0) += 1;
+            }
+        }
+        for variable_view in variable_views
+            .iter_mut()
+            .filter(|variable_view| counts[&variable_view.name] > 1)
+        {
+            let name = match variable_view.qualified_name() {
+                Some(name) if qualified_counts[&name] > 1 => variable_view.qualified_path(),
+                name => name,
+            };
+            if let Some(name) = name {
+                variable_view.name = name;
+            }
+        }
+    }
+
+    // `file.c:counter` if the variable is local to the compilation unit
+    pub fn qualified_name(&self) -> Option<String> {
+        let unit = self.compilation_unit()?.key()?;
+        Some(format!("{}:{}", unit, self.unqualified_name()))
+    }
+
+    // `/path/to/file.c:counter` if the variable is local to the compilation unit
+    fn qualified_path(&self) -> Option<String> {
+        let unit = self.compilation_unit()?.path()?;
+        Some(format!("{}:{}", unit, self.unqualified_name()))
+    }
+
+    // `counter` even if the name is qualified like `file.c:counter`
+    pub fn unqualified_name(&self) -> &str {
+        split_qualified_name(&self.name).1
+    }
+
+    // Whether the variable is local to the compilation unit a qualified name refers to
+    pub fn is_local_to(&self, unit: &str) -> bool {
+        self.compilation_unit()
+            .is_some_and(|compilation_unit| compilation_unit.is_named(unit))
+    }
+
+    // The compilation unit which the variable is local to
+    fn compilation_unit(&self) -> Option<&CompilationUnit> {
+        if self.external {
+            return None;
+        }
+        self.source.compilation_unit.as_deref()
+    }
+
+    // The variables without children, named with the paths from this variable like `hoges[1].hoge`
+    pub fn leaves(self) -> Vec<GlobalVariableView> {
+        if self.children.is_empty() {
+            return vec![self];
+        }
+        let is_array = matches!(self.type_view.underlying(), TypeView::Array { .. });
+        let name = self.name;
+        self.children
+            .into_iter()
+            .flat_map(|mut child| {
+                child.name = if is_array {
+                    format!("{}[{}]", name, child.name)
+                } else {
+                    format!("{}.{}", name, child.name)
+                };
+                child.leaves()
+            })
+            .collect()
+    }
+
     pub fn map_type_view(&mut self, f: impl FnOnce(TypeView) -> TypeView) {
         self.type_view = f(self.type_view.clone())
     }
+
+    // Finds the member or the element which the path points to
+    pub fn find_by_path(self, path: &[VariablePathElement]) -> Option<GlobalVariableView> {
+        let (head, rest) = match path.split_first() {
+            None => return Some(self),
+            Some(split) => split,
+        };
+        let child_name = self.child_name(head)?;
+        self.children
+            .into_iter()
+            .find(|child| child.name == child_name)?
+            .find_by_path(rest)
+    }
+
+    pub fn find_by_path_mut(
+        &mut self,
+        path: &[VariablePathElement],
+    ) -> Option<&mut GlobalVariableView> {
+        let (head, rest) = match path.split_first() {
+            None => return Some(self),
+            Some(split) => split,
+        };
+        let child_name = self.child_name(head)?;
+        self.children
+            .iter_mut()
+            .find(|child| child.name == child_name)?
+            .find_by_path_mut(rest)
+    }
+
+    fn child_name(&self, element: &VariablePathElement) -> Option<String> {
+        let is_array = matches!(self.type_view.underlying(), TypeView::Array { .. });
+        match element {
+            VariablePathElement::Member(name) if !is_array => Some(name.clone()),
+            VariablePathElement::Index(index) if is_array => Some(index.to_string()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -36,6 +167,7 @@ pub enum TypeView {
     },
     Base {
         name: String,
+        encoding: Option<BaseTypeEncoding>,
     },
     Structure {
         name: Option<String>,
@@ -71,6 +203,16 @@ impl From<&EnumeratorEntry> for Enumerator {
 }
 
 impl TypeView {
+    // The type view without typedefs and qualifiers
+    pub fn underlying(&self) -> &TypeView {
+        match self {
+            Self::TypeDef { type_view, .. } => type_view.underlying(),
+            Self::Volatile { type_view } => type_view.underlying(),
+            Self::Const { type_view } => type_view.underlying(),
+            _ => self,
+        }
+    }
+
     pub fn new_typedef_type_view<S: Into<String>>(name: S, type_view: Self) -> Self {
         Self::TypeDef {
             name: name.into(),
@@ -101,7 +243,17 @@ impl TypeView {
     }
 
     pub fn new_base_type_view<S: Into<String>>(name: S) -> Self {
-        Self::Base { name: name.into() }
+        Self::new_encoded_base_type_view(name, None)
+    }
+
+    pub fn new_encoded_base_type_view<S: Into<String>>(
+        name: S,
+        encoding: Option<BaseTypeEncoding>,
+    ) -> Self {
+        Self::Base {
+            name: name.into(),
+            encoding,
+        }
     }
 
     pub fn new_structure_type_view<S: Into<String>>(name: Option<S>) -> Self {
@@ -148,6 +300,11 @@ pub struct GlobalVariableViewBuilder<NameP, AddressP, SizeP, TypeViewP> {
     bit_offset: Option<usize>,
     type_view: TypeViewP,
     children: Vec<GlobalVariableView>,
+    external: bool,
+    source: SourceLocation,
+    type_source: SourceLocation,
+    value: Option<Value>,
+    pointee_type_ref: Option<TypeEntryId>,
 }
 
 impl GlobalVariableViewBuilder<(), (), (), ()> {
@@ -160,6 +317,11 @@ impl GlobalVariableViewBuilder<(), (), (), ()> {
             bit_offset: None,
             type_view: (),
             children: Vec::new(),
+            external: true,
+            source: SourceLocation::default(),
+            type_source: SourceLocation::default(),
+            value: None,
+            pointee_type_ref: None,
         }
     }
 }
@@ -174,6 +336,11 @@ impl GlobalVariableViewBuilder<String, Option<Address>, usize, TypeView> {
             bit_offset: self.bit_offset,
             type_view: self.type_view,
             children: self.children,
+            external: self.external,
+            source: self.source,
+            type_source: self.type_source,
+            value: self.value,
+            pointee_type_ref: self.pointee_type_ref,
         }
     }
 }
@@ -191,6 +358,11 @@ impl<AddressP, SizeP, TypeViewP> GlobalVariableViewBuilder<(), AddressP, SizeP,
             bit_offset: self.bit_offset,
             type_view: self.type_view,
             children: self.children,
+            external: self.external,
+            source: self.source,
+            type_source: self.type_source,
+            value: self.value,
+            pointee_type_ref: self.pointee_type_ref,
         }
     }
 }
@@ -208,6 +380,11 @@ impl<NameP, SizeP, TypeViewP> GlobalVariableViewBuilder<NameP, (), SizeP, TypeVi
             bit_offset: self.bit_offset,
             type_view: self.type_view,
             children: self.children,
+            external: self.external,
+            source: self.source,
+            type_source: self.type_source,
+            value: self.value,
+            pointee_type_ref: self.pointee_type_ref,
         }
     }
 }
@@ -222,6 +399,11 @@ impl<NameP, AddressP, TypeViewP> GlobalVariableViewBuilder<NameP, AddressP, (),
             bit_offset: self.bit_offset,
             type_view: self.type_view,
             children: self.children,
+            external: self.external,
+            source: self.source,
+            type_source: self.type_source,
+            value: self.value,
+            pointee_type_ref: self.pointee_type_ref,
         }
     }
 }
@@ -239,6 +421,11 @@ impl<NameP, AddressP, SizeP> GlobalVariableViewBuilder<NameP, AddressP, SizeP, (
             bit_offset: self.bit_offset,
             type_view: type_view,
             children: self.children,
+            external: self.external,
+            source: self.source,
+            type_source: self.type_source,
+            value: self.value,
+            pointee_type_ref: self.pointee_type_ref,
         }
     }
 }
@@ -260,4 +447,29 @@ impl<NameP, AddressP, SizeP, TypeViewP>
         self.children = children;
         self
     }
+
+    pub fn external(mut self, external: bool) -> Self {
+        self.external = external;
+        self
+    }
+
+    pub fn source(mut self, source: SourceLocation) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn type_source(mut self, type_source: SourceLocation) -> Self {
+        self.type_source = type_source;
+        self
+    }
+
+    pub fn value(mut self, value: Value) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn pointee_type_ref(mut self, type_ref: TypeEntryId) -> Self {
+        self.pointee_type_ref = Some(type_ref);
+        self
+    }
 }
@@ -1,9 +1,11 @@
 use super::global_variable::*;
 use super::global_variable_view::*;
+use super::source_location::SourceLocation;
 use super::type_entry::*;
 use super::type_entry_repository::TypeEntryRepository;
 use super::variable_declaration_entry::*;
 use super::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use crate::library::dwarf;
 use log::warn;
 
 pub struct GlobalVariableViewFactory<'type_repo, 'dec_repo> {
@@ -27,14 +29,41 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
         global_variable: GlobalVariable,
     ) -> Option<GlobalVariableView> {
         match global_variable {
-            GlobalVariable::HasSpec { address, spec } => {
-                self.from_global_variable_with_spec(address, spec)
-            }
+            GlobalVariable::HasSpec {
+                address,
+                spec,
+                source,
+            } => self.from_global_variable_with_spec(address, spec, source),
             GlobalVariable::NoSpec {
                 address,
                 name,
                 type_ref,
-            } => self.variable_view_from_type_ref(name, address, None, None, &type_ref),
+                external,
+                source,
+            } => self
+                .variable_view_from_type_ref(name, address, None, None, &type_ref)
+                .map(|mut view| {
+                    view.external = external;
+                    view.source = source;
+                    view.type_source = self.type_source(&type_ref);
+                    view
+                }),
+        }
+    }
+
+    // Arrays and qualifiers are not declared, so their element types are looked up
+    fn type_source(&self, type_ref: &TypeEntryId) -> SourceLocation {
+        match self.type_entry_repository.find_by_id(type_ref) {
+            None => SourceLocation::default(),
+            Some(type_entry) if type_entry.source.file.is_some() => type_entry.source.clone(),
+            Some(type_entry) => match &type_entry.kind {
+                TypeEntryKind::ArrayType {
+                    element_type_ref, ..
+                } => self.type_source(element_type_ref),
+                TypeEntryKind::VolatileType { type_ref }
+                | TypeEntryKind::ConstType { type_ref } => self.type_source(type_ref),
+                _ => type_entry.source.clone(),
+            },
         }
     }
 
@@ -42,6 +71,7 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
         &self,
         address: Option<Address>,
         spec: VariableDeclarationEntryId,
+        source: SourceLocation,
     ) -> Option<GlobalVariableView> {
         match self.variable_declaration_repository.find_by_id(&spec) {
             None => {
@@ -52,13 +82,20 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
                 );
                 None
             }
-            Some(variable_dec) => self.variable_view_from_type_ref(
-                variable_dec.name.clone(),
-                address,
-                None,
-                None,
-                &variable_dec.type_ref,
-            ),
+            Some(variable_dec) => self
+                .variable_view_from_type_ref(
+                    variable_dec.name.clone(),
+                    address,
+                    None,
+                    None,
+                    &variable_dec.type_ref,
+                )
+                .map(|mut view| {
+                    view.external = variable_dec.external;
+                    view.source = source.or(&variable_dec.source);
+                    view.type_source = self.type_source(&variable_dec.type_ref);
+                    view
+                }),
         }
     }
 
@@ -116,13 +153,14 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
                 TypeEntryKind::BaseType {
                     name: type_name,
                     size,
+                    encoding,
                 } => Some(Self::base_type_variable_view(
                     variable_name,
                     address,
                     *size,
                     bit_size,
                     bit_offset,
-                    type_name.clone(),
+                    TypeView::new_encoded_base_type_view(type_name.clone(), *encoding),
                 )),
                 TypeEntryKind::EnumType {
                     name: type_name,
@@ -187,6 +225,47 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
         }
     }
 
+    // The variable the pointer points to at the address, named `*`, which is None for functions
+    // The size of the variable the pointer points to, to read it before building the view
+    pub fn pointee_size(&self, type_ref: &TypeEntryId) -> Option<usize> {
+        if self.is_function_type(type_ref) {
+            return None;
+        }
+        self.variable_view_from_type_ref(String::from("*"), None, None, None, type_ref)
+            .map(|pointee| pointee.size)
+    }
+
+    pub fn pointee_variable_view(
+        &self,
+        address: usize,
+        type_ref: &TypeEntryId,
+    ) -> Option<GlobalVariableView> {
+        if self.is_function_type(type_ref) {
+            return None;
+        }
+        self.variable_view_from_type_ref(
+            String::from("*"),
+            Some(Address::new(dwarf::Location::new(address))),
+            None,
+            None,
+            type_ref,
+        )
+    }
+
+    fn is_function_type(&self, type_ref: &TypeEntryId) -> bool {
+        match self
+            .type_entry_repository
+            .find_by_id(type_ref)
+            .map(|type_entry| &type_entry.kind)
+        {
+            Some(TypeEntryKind::FunctionType { .. }) => true,
+            Some(TypeEntryKind::TypeDef { type_ref, .. })
+            | Some(TypeEntryKind::VolatileType { type_ref })
+            | Some(TypeEntryKind::ConstType { type_ref }) => self.is_function_type(type_ref),
+            _ => false,
+        }
+    }
+
     fn typedef_variable_view(
         &self,
         variable_name: String,
@@ -276,6 +355,7 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
                         .bit_size(bit_size)
                         .bit_offset(bit_offset)
                         .type_view(TypeView::new_pointer_type_view(type_view))
+                        .pointee_type_ref(type_ref.clone())
                         .build(),
                 )
             }
@@ -288,7 +368,7 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
         size: usize,
         bit_size: Option<usize>,
         bit_offset: Option<usize>,
-        type_name: String,
+        type_view: TypeView,
     ) -> GlobalVariableView {
         GlobalVariableViewBuilder::new()
             .name(variable_name)
@@ -296,7 +376,7 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
             .size(size)
             .bit_size(bit_size)
             .bit_offset(bit_offset)
-            .type_view(TypeView::new_base_type_view(type_name))
+            .type_view(type_view)
             .build()
     }
 
@@ -384,11 +464,7 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
         members
             .iter()
             .flat_map(|member| {
-                let address = base_address.map(|addr| {
-                    let mut addr = addr.clone();
-                    addr.add(member.location);
-                    addr
-                });
+                let address = base_address.and_then(|addr| addr.add(member.location));
                 self.variable_view_from_type_ref(
                     member.name.clone(),
                     address,
@@ -453,10 +529,7 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
                 let mut size = 0;
                 let elements = (0..=upper_bound)
                     .flat_map(|n| {
-                        let address = address.clone().map(|mut addr| {
-                            addr.add(size);
-                            addr
-                        });
+                        let address = address.as_ref().and_then(|addr| addr.add(size));
                         //TODO: What happens if use array as a member with bit field?
                         let element_view = self.variable_view_from_type_ref(
                             n.to_string(),
@@ -474,7 +547,7 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
         }
     }
 
-    fn type_view_from_type_entry(&self, type_entry_id: &TypeEntryId) -> Option<TypeView> {
+    pub fn type_view_from_type_entry(&self, type_entry_id: &TypeEntryId) -> Option<TypeView> {
         match self.type_entry_repository.find_by_id(type_entry_id) {
             None => {
                 let offset: usize = type_entry_id.clone().into();
@@ -504,9 +577,9 @@ impl<'type_repo, 'dec_repo> GlobalVariableViewFactory<'type_repo, 'dec_repo> {
                         Some(TypeView::new_pointer_type_view(type_view))
                     }
                 },
-                TypeEntryKind::BaseType { name, .. } => {
-                    Some(TypeView::new_base_type_view(name.clone()))
-                }
+                TypeEntryKind::BaseType { name, encoding, .. } => Some(
+                    TypeView::new_encoded_base_type_view(name.clone(), *encoding),
+                ),
                 TypeEntryKind::EnumType {
                     name,
                     type_ref,
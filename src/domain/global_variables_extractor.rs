@@ -1,4 +1,5 @@
 use log::warn;
+use rayon::prelude::*;
 
 use super::entry_factory::*;
 use super::global_variable::*;
@@ -6,6 +7,13 @@ use super::type_entry_repository::TypeEntryRepository;
 use super::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
 use crate::library::dwarf::DwarfInfo;
 
+// The entries extracted from a unit, which are merged into the repositories later
+pub struct ExtractedUnit {
+    global_variables: Vec<GlobalVariable>,
+    type_entry_repository: TypeEntryRepository,
+    variable_declaration_repository: VariableDeclarationEntryRepository,
+}
+
 pub struct GlobalVariablesExtractor<'type_repo, 'dec_repo> {
     type_entry_repository: &'type_repo mut TypeEntryRepository,
     variable_declaration_repository: &'dec_repo mut VariableDeclarationEntryRepository,
@@ -22,7 +30,10 @@ impl<'type_repo, 'dec_repo> GlobalVariablesExtractor<'type_repo, 'dec_repo> {
         }
     }
 
-    pub fn extract(&mut self, infos: impl Iterator<Item = DwarfInfo>) -> Vec<GlobalVariable> {
+    pub fn extract<'input>(
+        &mut self,
+        infos: impl Iterator<Item = DwarfInfo<'input>>,
+    ) -> Vec<GlobalVariable> {
         let mut global_variables = Vec::new();
         for info in infos {
             match EntryFactory::from_dwarf_info(&info) {
@@ -47,6 +58,44 @@ impl<'type_repo, 'dec_repo> GlobalVariablesExtractor<'type_repo, 'dec_repo> {
         global_variables
     }
 
+    // Extracts a unit into its own repositories, which can be done in parallel
+    pub fn extract_unit<'input>(infos: impl Iterator<Item = DwarfInfo<'input>>) -> ExtractedUnit {
+        let mut type_entry_repository = TypeEntryRepository::new();
+        let mut variable_declaration_repository = VariableDeclarationEntryRepository::new();
+        let global_variables = GlobalVariablesExtractor::new(
+            &mut type_entry_repository,
+            &mut variable_declaration_repository,
+        )
+        .extract(infos);
+        ExtractedUnit {
+            global_variables,
+            type_entry_repository,
+            variable_declaration_repository,
+        }
+    }
+
+    // Merges the extracted units in order
+    pub fn merge(&mut self, units: Vec<ExtractedUnit>) -> Vec<GlobalVariable> {
+        let mut global_variables = Vec::new();
+        for mut unit in units {
+            global_variables.append(&mut unit.global_variables);
+            self.type_entry_repository
+                .merge(&mut unit.type_entry_repository);
+            self.variable_declaration_repository
+                .merge(&mut unit.variable_declaration_repository);
+        }
+        global_variables
+    }
+
+    // Extracts the units in parallel into per-unit repositories, and merges them in order
+    pub fn extract_units(&mut self, units: Vec<Vec<DwarfInfo>>) -> Vec<GlobalVariable> {
+        let extracted = units
+            .into_par_iter()
+            .map(|infos| Self::extract_unit(infos.into_iter()))
+            .collect();
+        self.merge(extracted)
+    }
+
     fn warning_no_expected_attribute(message: String, entry: &DwarfInfo) {
         let offset: usize = entry.offset().into();
         warn!("Skip this entry: {}: offset = {:#x}", message, offset);
@@ -0,0 +1,70 @@
+use super::global_variable_view::GlobalVariableView;
+use super::value::Value;
+use super::value_decoder::ValueDecoder;
+use crate::library::memory::Memory;
+
+// A variable whose bytes in the image differ from its initial value in the ELF,
+// where `actual` is None if the image does not have the bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageMismatch {
+    pub name: String,
+    pub expected: Option<Value>,
+    pub actual: Option<Value>,
+}
+
+pub struct ImageVerifier<'memory> {
+    expected: &'memory dyn Memory,
+    actual: &'memory dyn Memory,
+    little_endian: bool,
+}
+
+impl<'memory> ImageVerifier<'memory> {
+    pub fn new(
+        expected: &'memory dyn Memory,
+        actual: &'memory dyn Memory,
+        little_endian: bool,
+    ) -> Self {
+        Self {
+            expected,
+            actual,
+            little_endian,
+        }
+    }
+
+    // Compares the variables without children which have initial values in the ELF,
+    // ignoring the ones like in .bss
+    pub fn verify(&self, variable_views: Vec<GlobalVariableView>) -> Vec<ImageMismatch> {
+        variable_views
+            .into_iter()
+            .flat_map(GlobalVariableView::leaves)
+            .filter_map(|variable_view| self.verify_variable(variable_view))
+            .collect()
+    }
+
+    fn verify_variable(&self, variable_view: GlobalVariableView) -> Option<ImageMismatch> {
+        let address: usize = variable_view.address.clone()?.into();
+        let expected = self.expected.read(address, variable_view.size)?;
+        let expected_value = self.decode(self.expected, &variable_view);
+        let actual_value = self.decode(self.actual, &variable_view);
+        let differs = match self.actual.read(address, variable_view.size) {
+            None => true,
+            // the other bits of the bytes may belong to other bit fields
+            Some(_) if variable_view.bit_size.is_some() => expected_value != actual_value,
+            Some(actual) => actual != expected,
+        };
+        if !differs {
+            return None;
+        }
+        Some(ImageMismatch {
+            name: variable_view.name,
+            expected: expected_value,
+            actual: actual_value,
+        })
+    }
+
+    fn decode(&self, memory: &dyn Memory, variable_view: &GlobalVariableView) -> Option<Value> {
+        let mut variable_view = variable_view.clone();
+        ValueDecoder::new(memory, self.little_endian).decode(&mut variable_view);
+        variable_view.value
+    }
+}
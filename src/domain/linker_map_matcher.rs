@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use super::global_variable_view::GlobalVariableView;
+use crate::library::linker_map::{LinkerMap, MapSymbolSize};
+
+// A variable whose address or size in DWARF disagrees with the map file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkerMapMismatch {
+    pub name: String,
+    pub differences: Vec<String>,
+    // the object which the map attributes the variable to
+    pub object: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjectGrouping {
+    // `libfoo.a(foo.o)`
+    Object,
+    // `libfoo.a`
+    Archive,
+}
+
+// Variables attributed to the same object or archive, or to none of them if `object` is None
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectGroup {
+    pub object: Option<String>,
+    pub size: usize,
+    pub variable_views: Vec<GlobalVariableView>,
+}
+
+pub struct LinkerMapMatcher<'map> {
+    linker_map: &'map LinkerMap,
+}
+
+impl<'map> LinkerMapMatcher<'map> {
+    pub fn new(linker_map: &'map LinkerMap) -> Self {
+        Self { linker_map }
+    }
+
+    // Compares the variables at the top level with the symbols of the same names,
+    // and with the input sections which have their addresses.
+    // Symbols local to objects may not be in the map, which is not a mismatch.
+    pub fn check(&self, variable_views: &[GlobalVariableView]) -> Vec<LinkerMapMismatch> {
+        variable_views
+            .iter()
+            .filter_map(|variable_view| self.check_variable(variable_view))
+            .collect()
+    }
+
+    fn check_variable(&self, variable_view: &GlobalVariableView) -> Option<LinkerMapMismatch> {
+        let address: usize = variable_view.address.clone()?.into();
+        let mut differences = Vec::new();
+
+        let symbols: Vec<_> = self
+            .linker_map
+            .find_symbols(Self::symbol_name(variable_view))
+            .collect();
+        match symbols.iter().find(|symbol| symbol.address == address) {
+            Some(symbol) => match symbol.size {
+                MapSymbolSize::Exact(size) if size != variable_view.size => {
+                    differences.push(format!("size: {:#x} != {:#x}", variable_view.size, size))
+                }
+                MapSymbolSize::AtMost(size) if size < variable_view.size => {
+                    differences.push(format!(
+                        "size: {:#x} > {:#x} until the next symbol",
+                        variable_view.size, size
+                    ))
+                }
+                _ => (),
+            },
+            // same-named symbols of other objects are not this variable if it is local
+            None if variable_view.external && !symbols.is_empty() => {
+                let addresses: Vec<String> = symbols
+                    .iter()
+                    .map(|symbol| format!("{:#010x}", symbol.address))
+                    .collect();
+                differences.push(format!(
+                    "address: {:#010x} != {}",
+                    address,
+                    addresses.join(", ")
+                ));
+            }
+            None => (),
+        }
+
+        let section = self.linker_map.find_section(address);
+        // sections which contain the address end in the address space
+        let end = address.checked_add(variable_view.size);
+        match (section, end) {
+            (_, None) => differences.push(format!(
+                "size: {:#x} overflows the address space",
+                variable_view.size
+            )),
+            (Some(section), Some(end)) if end > section.address + section.size => {
+                differences.push(format!(
+                    "size: {:#x} overruns {} of {} by {:#x}",
+                    variable_view.size,
+                    section.name,
+                    section.object,
+                    end - section.address - section.size
+                ))
+            }
+            (Some(_), _) => (),
+            // variables of zero size may be at the end of the sections
+            (None, _) if variable_view.size == 0 => (),
+            (None, _) => {
+                differences.push(format!("address: {:#010x} is in no input section", address))
+            }
+        }
+
+        if differences.is_empty() {
+            return None;
+        }
+        Some(LinkerMapMismatch {
+            name: variable_view.name.clone(),
+            differences,
+            object: section.map(|section| section.object.clone()),
+        })
+    }
+
+    // `counter` for `file.c:counter`
+    fn symbol_name(variable_view: &GlobalVariableView) -> &str {
+        variable_view.unqualified_name()
+    }
+
+    // Groups the variables at the top level by the objects of the input sections which have them,
+    // sorted by the names of the objects, and the variables attributed to none come last
+    pub fn group(
+        &self,
+        variable_views: Vec<GlobalVariableView>,
+        grouping: ObjectGrouping,
+    ) -> Vec<ObjectGroup> {
+        let mut groups: BTreeMap<String, Vec<GlobalVariableView>> = BTreeMap::new();
+        let mut unknown = Vec::new();
+        for variable_view in variable_views {
+            let section = variable_view
+                .address
+                .clone()
+                .and_then(|address| self.linker_map.find_section(address.into()));
+            match section {
+                Some(section) => {
+                    let object = match grouping {
+                        ObjectGrouping::Object => section.object.as_str(),
+                        ObjectGrouping::Archive => section.archive(),
+                    };
+                    groups
+                        .entry(object.to_string())
+                        .or_default()
+                        .push(variable_view);
+                }
+                None => unknown.push(variable_view),
+            }
+        }
+
+        let groups = groups
+            .into_iter()
+            .map(|(object, variable_views)| (Some(object), variable_views));
+        let unknown = Some((None, unknown)).filter(|(_, unknown)| !unknown.is_empty());
+        groups
+            .chain(unknown)
+            .map(|(object, mut variable_views)| {
+                variable_views.sort_by_key(|variable_view| {
+                    variable_view.address.clone().map(Into::<usize>::into)
+                });
+                ObjectGroup {
+                    object,
+                    size: variable_views
+                        .iter()
+                        .map(|variable_view| variable_view.size)
+                        .sum(),
+                    variable_views,
+                }
+            })
+            .collect()
+    }
+}
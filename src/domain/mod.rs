@@ -1,11 +1,27 @@
+pub mod budget;
+pub mod calibration;
 pub mod entity;
 pub mod entity_repository;
 pub mod entry_factory;
+pub mod expression;
+pub mod expression_evaluator;
 pub mod global_variable;
 pub mod global_variable_view;
 pub mod global_variable_view_factory;
 pub mod global_variables_extractor;
+pub mod image_verifier;
+pub mod linker_map_matcher;
+pub mod odr_checker;
+pub mod pointer_follower;
+pub mod sampler;
+pub mod source_location;
+pub mod type_deduplicator;
 pub mod type_entry;
 pub mod type_entry_repository;
+pub mod union_rules;
+pub mod value;
+pub mod value_decoder;
+pub mod value_encoder;
 pub mod variable_declaration_entry;
 pub mod variable_declaration_entry_repository;
+pub mod variable_path;
@@ -0,0 +1,463 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::global_variable::GlobalVariable;
+use super::global_variable_view_factory::GlobalVariableViewFactory;
+use super::source_location::SourceLocation;
+use super::type_deduplicator::{CanonicalType, TypeDeduplicator};
+use super::type_entry::*;
+use super::type_entry_repository::TypeEntryRepository;
+use super::variable_declaration_entry::VariableDeclarationEntry;
+use super::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+
+// Types or variables which share a name but are defined differently in compilation units.
+// `differences` compare the first definition with the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OdrViolation {
+    pub name: String,
+    pub differences: Vec<String>,
+    pub definitions: Vec<Vec<SourceLocation>>,
+}
+
+pub struct OdrChecker<'type_repo, 'dec_repo> {
+    type_entry_repository: &'type_repo TypeEntryRepository,
+    variable_declaration_repository: &'dec_repo VariableDeclarationEntryRepository,
+    canonical_types: Vec<CanonicalType>,
+    classes: HashMap<TypeEntryId, usize>,
+}
+
+impl<'type_repo, 'dec_repo> OdrChecker<'type_repo, 'dec_repo> {
+    pub fn new(
+        type_entry_repository: &'type_repo TypeEntryRepository,
+        variable_declaration_repository: &'dec_repo VariableDeclarationEntryRepository,
+    ) -> Self {
+        let canonical_types = TypeDeduplicator::new(type_entry_repository).deduplicate();
+        let classes = canonical_types
+            .iter()
+            .enumerate()
+            .flat_map(|(class, canonical_type)| {
+                canonical_type
+                    .offsets
+                    .iter()
+                    .map(move |id| (id.clone(), class))
+            })
+            .collect();
+        Self {
+            type_entry_repository,
+            variable_declaration_repository,
+            canonical_types,
+            classes,
+        }
+    }
+
+    pub fn check(&self, global_variables: &[GlobalVariable]) -> Vec<OdrViolation> {
+        let mut violations = self.check_types();
+        violations.append(&mut self.check_variables(global_variables));
+        violations
+    }
+
+    // Named structures, unions, enums and typedefs
+    fn check_types(&self) -> Vec<OdrViolation> {
+        let mut named_types: BTreeMap<String, Vec<&CanonicalType>> = BTreeMap::new();
+        for canonical_type in self.canonical_types.iter() {
+            if let Some(name) = Self::type_key(&canonical_type.entry.kind) {
+                named_types.entry(name).or_default().push(canonical_type);
+            }
+        }
+
+        named_types
+            .into_iter()
+            .filter(|(_, canonical_types)| canonical_types.len() > 1)
+            .filter_map(|(name, canonical_types)| {
+                let definitions = canonical_types.into_iter().map(|canonical_type| {
+                    let sources = canonical_type
+                        .offsets
+                        .iter()
+                        .filter_map(|id| self.type_entry_repository.find_by_id(id))
+                        .map(|type_entry| type_entry.source.clone())
+                        .collect();
+                    (&canonical_type.entry, sources)
+                });
+                self.violation(name, definitions, |a, b| self.type_differences(a, b))
+            })
+            .collect()
+    }
+
+    // extern declarations whose types disagree with the definitions
+    fn check_variables(&self, global_variables: &[GlobalVariable]) -> Vec<OdrViolation> {
+        let mut variables: BTreeMap<String, Vec<(TypeEntryId, SourceLocation)>> = BTreeMap::new();
+        for global_variable in global_variables {
+            match global_variable {
+                GlobalVariable::NoSpec {
+                    name,
+                    type_ref,
+                    external: true,
+                    source,
+                    ..
+                } => variables
+                    .entry(name.clone())
+                    .or_default()
+                    .push((type_ref.clone(), source.clone())),
+                GlobalVariable::HasSpec { spec, source, .. } => {
+                    if let Some(dec) = self.variable_declaration_repository.find_by_id(spec) {
+                        if dec.external {
+                            variables
+                                .entry(dec.name.clone())
+                                .or_default()
+                                .push((dec.type_ref.clone(), source.clone().or(&dec.source)));
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        let mut declarations: Vec<&VariableDeclarationEntry> = self
+            .variable_declaration_repository
+            .find_all()
+            .filter(|dec| dec.external)
+            .collect();
+        declarations.sort_by(|a, b| a.id.cmp(&b.id));
+        for dec in declarations {
+            // declarations of variables defined in other binaries are not checked
+            if let Some(definitions) = variables.get_mut(&dec.name) {
+                definitions.push((dec.type_ref.clone(), dec.source.clone()));
+            }
+        }
+
+        variables
+            .into_iter()
+            .filter_map(|(name, definitions)| {
+                let definitions = definitions
+                    .into_iter()
+                    .map(|(type_ref, source)| (type_ref, vec![source]));
+                self.violation(name, definitions, |a, b| {
+                    self.type_ref_difference(a, b)
+                        .map(|difference| format!("type: {}", difference))
+                        .into_iter()
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    // Groups the definitions which have no differences from each other
+    fn violation<D>(
+        &self,
+        name: String,
+        definitions: impl Iterator<Item = (D, Vec<SourceLocation>)>,
+        differences: impl Fn(&D, &D) -> Vec<String>,
+    ) -> Option<OdrViolation> {
+        let mut groups: Vec<(D, Vec<SourceLocation>)> = Vec::new();
+        for (definition, mut sources) in definitions {
+            match groups
+                .iter_mut()
+                .find(|(group, _)| differences(group, &definition).is_empty())
+            {
+                Some((_, group_sources)) => group_sources.append(&mut sources),
+                None => groups.push((definition, sources)),
+            }
+        }
+        if groups.len() < 2 {
+            return None;
+        }
+
+        let mut all_differences: Vec<String> = Vec::new();
+        for (definition, _) in groups.iter().skip(1) {
+            for difference in differences(&groups[0].0, definition) {
+                if !all_differences.contains(&difference) {
+                    all_differences.push(difference);
+                }
+            }
+        }
+        Some(OdrViolation {
+            name,
+            differences: all_differences,
+            definitions: groups.into_iter().map(|(_, sources)| sources).collect(),
+        })
+    }
+
+    fn type_key(kind: &TypeEntryKind) -> Option<String> {
+        match kind {
+            TypeEntryKind::StructureType {
+                name: Some(name), ..
+            } => Some(format!("struct {}", name)),
+            TypeEntryKind::UnionType {
+                name: Some(name), ..
+            } => Some(format!("union {}", name)),
+            TypeEntryKind::EnumType {
+                name: Some(name), ..
+            } => Some(format!("enum {}", name)),
+            TypeEntryKind::TypeDef { name, .. } => Some(format!("typedef {}", name)),
+            _ => None,
+        }
+    }
+
+    // Only the differences found in the types themselves,
+    // since named types which they refer to are checked by their own names
+    fn type_differences(&self, a: &&TypeEntry, b: &&TypeEntry) -> Vec<String> {
+        match (&a.kind, &b.kind) {
+            (
+                TypeEntryKind::StructureType {
+                    size: a_size,
+                    members: a_members,
+                    ..
+                },
+                TypeEntryKind::StructureType {
+                    size: b_size,
+                    members: b_members,
+                    ..
+                },
+            ) => {
+                let a_members: Vec<&MemberEntry<Structure>> =
+                    a_members.iter().map(|m| &**m).collect();
+                let b_members: Vec<&MemberEntry<Structure>> =
+                    b_members.iter().map(|m| &**m).collect();
+                let mut differences = Self::size_difference(*a_size, *b_size);
+                differences.append(&mut self.member_differences(&a_members, &b_members));
+                differences
+            }
+            (
+                TypeEntryKind::UnionType {
+                    size: a_size,
+                    members: a_members,
+                    ..
+                },
+                TypeEntryKind::UnionType {
+                    size: b_size,
+                    members: b_members,
+                    ..
+                },
+            ) => {
+                let a_members: Vec<&MemberEntry<Union>> = a_members.iter().map(|m| &**m).collect();
+                let b_members: Vec<&MemberEntry<Union>> = b_members.iter().map(|m| &**m).collect();
+                let mut differences = Self::size_difference(*a_size, *b_size);
+                differences.append(&mut self.member_differences(&a_members, &b_members));
+                differences
+            }
+            (
+                TypeEntryKind::EnumType {
+                    type_ref: a_type_ref,
+                    enumerators: a_enumerators,
+                    ..
+                },
+                TypeEntryKind::EnumType {
+                    type_ref: b_type_ref,
+                    enumerators: b_enumerators,
+                    ..
+                },
+            ) => {
+                let mut differences: Vec<String> = self
+                    .type_ref_difference(a_type_ref, b_type_ref)
+                    .map(|difference| format!("type: {}", difference))
+                    .into_iter()
+                    .collect();
+                differences.append(&mut Self::enumerator_differences(
+                    a_enumerators,
+                    b_enumerators,
+                ));
+                differences
+            }
+            (
+                TypeEntryKind::TypeDef {
+                    type_ref: a_type_ref,
+                    ..
+                },
+                TypeEntryKind::TypeDef {
+                    type_ref: b_type_ref,
+                    ..
+                },
+            ) => {
+                let a_type = self.type_entry_repository.find_by_id(a_type_ref);
+                let b_type = self.type_entry_repository.find_by_id(b_type_ref);
+                match (a_type, b_type) {
+                    // like `typedef struct { ... } Point;`, which has no other name to be checked by
+                    (Some(a_type), Some(b_type))
+                        if Self::is_anonymous_aggregate(&a_type.kind)
+                            && Self::is_anonymous_aggregate(&b_type.kind) =>
+                    {
+                        self.type_differences(&a_type, &b_type)
+                    }
+                    _ => self
+                        .type_ref_difference(a_type_ref, b_type_ref)
+                        .map(|difference| format!("type: {}", difference))
+                        .into_iter()
+                        .collect(),
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn is_anonymous_aggregate(kind: &TypeEntryKind) -> bool {
+        matches!(
+            kind,
+            TypeEntryKind::StructureType { name: None, .. }
+                | TypeEntryKind::UnionType { name: None, .. }
+                | TypeEntryKind::EnumType { name: None, .. }
+        )
+    }
+
+    fn size_difference(a: usize, b: usize) -> Vec<String> {
+        if a == b {
+            Vec::new()
+        } else {
+            vec![format!("size: {} != {}", a, b)]
+        }
+    }
+
+    fn member_differences<T>(&self, a: &[&MemberEntry<T>], b: &[&MemberEntry<T>]) -> Vec<String> {
+        let mut differences = Vec::new();
+        for a_member in a {
+            let b_member = match b.iter().find(|b_member| b_member.name == a_member.name) {
+                Some(b_member) => b_member,
+                None => {
+                    differences.push(format!(
+                        "member {}: only in some definitions",
+                        a_member.name
+                    ));
+                    continue;
+                }
+            };
+            if a_member.location != b_member.location {
+                differences.push(format!(
+                    "member {} offset: {} != {}",
+                    a_member.name, a_member.location, b_member.location
+                ));
+            }
+            let bits = |member: &MemberEntry<T>| match (member.bit_offset, member.bit_size) {
+                (Some(offset), Some(size)) => format!("({}:{})", offset, size),
+                _ => String::from("none"),
+            };
+            if bits(a_member) != bits(b_member) {
+                differences.push(format!(
+                    "member {} bit field: {} != {}",
+                    a_member.name,
+                    bits(a_member),
+                    bits(b_member)
+                ));
+            }
+            if let Some(difference) =
+                self.type_ref_difference(&a_member.type_ref, &b_member.type_ref)
+            {
+                differences.push(format!("member {} type: {}", a_member.name, difference));
+            }
+        }
+        for b_member in b {
+            if !a.iter().any(|a_member| a_member.name == b_member.name) {
+                differences.push(format!(
+                    "member {}: only in some definitions",
+                    b_member.name
+                ));
+            }
+        }
+        differences
+    }
+
+    fn enumerator_differences(a: &[EnumeratorEntry], b: &[EnumeratorEntry]) -> Vec<String> {
+        let mut differences = Vec::new();
+        for a_enumerator in a {
+            match b
+                .iter()
+                .find(|b_enumerator| b_enumerator.name == a_enumerator.name)
+            {
+                Some(b_enumerator) if b_enumerator.value != a_enumerator.value => {
+                    differences.push(format!(
+                        "enumerator {}: {} != {}",
+                        a_enumerator.name, a_enumerator.value, b_enumerator.value
+                    ))
+                }
+                Some(_) => (),
+                None => differences.push(format!(
+                    "enumerator {}: only in some definitions",
+                    a_enumerator.name
+                )),
+            }
+        }
+        for b_enumerator in b {
+            if !a
+                .iter()
+                .any(|a_enumerator| a_enumerator.name == b_enumerator.name)
+            {
+                differences.push(format!(
+                    "enumerator {}: only in some definitions",
+                    b_enumerator.name
+                ));
+            }
+        }
+        differences
+    }
+
+    // The names of the types if they differ.
+    // Types with the same name differ only if they are anonymous,
+    // and types which are not extracted, like incomplete structures, are not compared.
+    fn type_ref_difference(&self, a: &TypeEntryId, b: &TypeEntryId) -> Option<String> {
+        if let (Some(a_class), Some(b_class)) = (self.classes.get(a), self.classes.get(b)) {
+            if a_class == b_class {
+                return None;
+            }
+        }
+        let a_name = self.type_name(a)?;
+        let b_name = self.type_name(b)?;
+        if a_name != b_name {
+            Some(format!("{} != {}", a_name, b_name))
+        } else if self.is_anonymous(a) {
+            Some(format!("{} differs", a_name.trim_end()))
+        } else {
+            None
+        }
+    }
+
+    fn type_name(&self, type_ref: &TypeEntryId) -> Option<String> {
+        if !self.is_extracted(type_ref) {
+            return None;
+        }
+        let factory = GlobalVariableViewFactory::new(
+            self.type_entry_repository,
+            self.variable_declaration_repository,
+        );
+        factory
+            .type_view_from_type_entry(type_ref)
+            .map(|type_view| type_view.to_string())
+    }
+
+    // Whether the type and the types which its name consists of are extracted
+    fn is_extracted(&self, type_ref: &TypeEntryId) -> bool {
+        match self.type_entry_repository.find_by_id(type_ref) {
+            None => false,
+            Some(type_entry) => match &type_entry.kind {
+                TypeEntryKind::TypeDef { type_ref, .. }
+                | TypeEntryKind::VolatileType { type_ref }
+                | TypeEntryKind::ConstType { type_ref }
+                | TypeEntryKind::EnumType { type_ref, .. }
+                | TypeEntryKind::PointerType {
+                    type_ref: Some(type_ref),
+                    ..
+                } => self.is_extracted(type_ref),
+                TypeEntryKind::ArrayType {
+                    element_type_ref, ..
+                } => self.is_extracted(element_type_ref),
+                _ => true,
+            },
+        }
+    }
+
+    fn is_anonymous(&self, type_ref: &TypeEntryId) -> bool {
+        match self.type_entry_repository.find_by_id(type_ref) {
+            None => false,
+            Some(type_entry) => match &type_entry.kind {
+                TypeEntryKind::VolatileType { type_ref }
+                | TypeEntryKind::ConstType { type_ref }
+                | TypeEntryKind::PointerType {
+                    type_ref: Some(type_ref),
+                    ..
+                } => self.is_anonymous(type_ref),
+                TypeEntryKind::ArrayType {
+                    element_type_ref, ..
+                } => self.is_anonymous(element_type_ref),
+                TypeEntryKind::StructureType { name, .. }
+                | TypeEntryKind::UnionType { name, .. }
+                | TypeEntryKind::EnumType { name, .. } => name.is_none(),
+                _ => false,
+            },
+        }
+    }
+}
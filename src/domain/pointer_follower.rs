@@ -0,0 +1,87 @@
+use super::global_variable_view::GlobalVariableView;
+use super::global_variable_view_factory::GlobalVariableViewFactory;
+use super::value::Value;
+use super::value_decoder::ValueDecoder;
+use crate::library::memory::Memory;
+
+// Expands the variables pointers point to in the memory as their children,
+// like the nodes of linked lists
+pub struct PointerFollower<'type_repo, 'dec_repo, 'memory> {
+    global_variable_view_factory: GlobalVariableViewFactory<'type_repo, 'dec_repo>,
+    memory: &'memory dyn Memory,
+    value_decoder: ValueDecoder<'memory>,
+    depth: usize,
+}
+
+impl<'type_repo, 'dec_repo, 'memory> PointerFollower<'type_repo, 'dec_repo, 'memory> {
+    pub fn new(
+        global_variable_view_factory: GlobalVariableViewFactory<'type_repo, 'dec_repo>,
+        memory: &'memory dyn Memory,
+        little_endian: bool,
+        depth: usize,
+    ) -> Self {
+        Self {
+            global_variable_view_factory,
+            memory,
+            value_decoder: ValueDecoder::new(memory, little_endian),
+            depth,
+        }
+    }
+
+    // Follows the decoded pointers up to the depth, where pointers to
+    // the variables being expanded are not followed again to stop at cycles
+    pub fn follow(&self, variable_view: &mut GlobalVariableView) {
+        let mut expanding: Vec<usize> = variable_view
+            .address
+            .clone()
+            .map(Into::into)
+            .into_iter()
+            .collect();
+        self.follow_with_depth(variable_view, self.depth, &mut expanding);
+    }
+
+    fn follow_with_depth(
+        &self,
+        variable_view: &mut GlobalVariableView,
+        depth: usize,
+        expanding: &mut Vec<usize>,
+    ) {
+        for child in variable_view.children.iter_mut() {
+            self.follow_with_depth(child, depth, expanding);
+        }
+        if depth == 0 {
+            return;
+        }
+        let (type_ref, pointer) = match (&variable_view.pointee_type_ref, &variable_view.value) {
+            (Some(type_ref), Some(Value::Pointer(pointer))) if *pointer != 0 => {
+                (type_ref, *pointer as usize)
+            }
+            _ => return,
+        };
+        // pointers are the addresses in the memory, not in the ELF
+        let address = pointer.wrapping_sub(self.memory.load_bias());
+        if expanding.contains(&address) {
+            return;
+        }
+        // pointers out of the memory, including ones at the end of the address space, are not followed
+        let size = match self.global_variable_view_factory.pointee_size(type_ref) {
+            Some(size) if size != 0 => size,
+            _ => return,
+        };
+        if self.memory.read(address, size).is_none() {
+            return;
+        }
+        let mut pointee = match self
+            .global_variable_view_factory
+            .pointee_variable_view(address, type_ref)
+        {
+            Some(pointee) => pointee,
+            None => return,
+        };
+        self.value_decoder.decode(&mut pointee);
+        expanding.push(address);
+        self.follow_with_depth(&mut pointee, depth - 1, expanding);
+        expanding.pop();
+        variable_view.children.push(pointee);
+    }
+}
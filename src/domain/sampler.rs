@@ -0,0 +1,43 @@
+use super::global_variable_view::GlobalVariableView;
+use super::value::Value;
+use super::value_decoder::ValueDecoder;
+use crate::library::memory::Memory;
+
+// Reads the values of variables repeatedly, where structures and arrays are sampled
+// as the variables without children in them
+pub struct Sampler {
+    variable_views: Vec<GlobalVariableView>,
+    little_endian: bool,
+}
+
+impl Sampler {
+    pub fn new(variable_views: Vec<GlobalVariableView>, little_endian: bool) -> Self {
+        Self {
+            variable_views: variable_views
+                .into_iter()
+                .flat_map(GlobalVariableView::leaves)
+                .collect(),
+            little_endian,
+        }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.variable_views
+            .iter()
+            .map(|variable_view| variable_view.name.as_str())
+            .collect()
+    }
+
+    // None for the variables which are not in the memory
+    pub fn sample(&self, memory: &dyn Memory) -> Vec<Option<Value>> {
+        let value_decoder = ValueDecoder::new(memory, self.little_endian);
+        self.variable_views
+            .iter()
+            .map(|variable_view| {
+                let mut variable_view = variable_view.clone();
+                value_decoder.decode(&mut variable_view);
+                variable_view.value
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::library::dwarf::{CompilationUnit, DwarfInfo};
+
+// Where a variable or a type is declared, which is known only if sources are read
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub compilation_unit: Option<Arc<CompilationUnit>>,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+impl SourceLocation {
+    pub fn new(
+        compilation_unit: Option<Arc<CompilationUnit>>,
+        file: Option<String>,
+        line: Option<usize>,
+    ) -> Self {
+        Self {
+            compilation_unit,
+            file,
+            line,
+        }
+    }
+
+    pub fn from_dwarf_info(info: &DwarfInfo) -> Self {
+        Self::new(info.compilation_unit(), info.decl_file(), info.decl_line())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.compilation_unit.is_none() && self.file.is_none()
+    }
+
+    // Falls back to `other` where the declaration file and line are not known,
+    // like definitions which only refer to their declarations
+    pub fn or(self, other: &SourceLocation) -> Self {
+        if self.file.is_some() {
+            return self;
+        }
+        Self {
+            compilation_unit: self
+                .compilation_unit
+                .or_else(|| other.compilation_unit.clone()),
+            file: other.file.clone(),
+            line: other.line,
+        }
+    }
+}
+
+// `file:line`
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{}", file, line),
+            (Some(file), None) => write!(f, "{}", file),
+            (None, _) => Ok(()),
+        }
+    }
+}
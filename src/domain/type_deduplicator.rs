@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::global_variable_view::TypeView;
+use super::source_location::SourceLocation;
+use super::type_entry::*;
+use super::type_entry_repository::TypeEntryRepository;
+use crate::library::dwarf::BaseTypeEncoding;
+
+// Structurally identical types, like a structure declared in every compilation unit.
+// `entry` is the one at the smallest offset, and `offsets` are all of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanonicalType {
+    pub entry: TypeEntry,
+    pub offsets: Vec<TypeEntryId>,
+}
+
+// A canonical type as it is listed, with the offsets of the identical types
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanonicalTypeView {
+    pub type_view: TypeView,
+    pub offsets: Vec<TypeEntryId>,
+    pub source: SourceLocation,
+}
+
+// The type without the types it refers to, which are compared by their classes
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Shape {
+    TypeDef(String),
+    Volatile,
+    Const,
+    Pointer(usize, bool),
+    Base(String, usize, Option<BaseTypeEncoding>),
+    Enum(Option<String>, Vec<(String, isize)>),
+    Structure(Option<String>, usize, Vec<MemberShape>),
+    Union(Option<String>, usize, Vec<MemberShape>),
+    Array(Option<usize>),
+    Function(usize, bool),
+    // refered but not extracted, which is equal only to itself
+    Unknown(TypeEntryId),
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct MemberShape {
+    name: String,
+    location: usize,
+    bit_size: Option<usize>,
+    bit_offset: Option<usize>,
+}
+
+impl<T> From<&MemberEntry<T>> for MemberShape {
+    fn from(member: &MemberEntry<T>) -> Self {
+        MemberShape {
+            name: member.name.clone(),
+            location: member.location,
+            bit_size: member.bit_size,
+            bit_offset: member.bit_offset,
+        }
+    }
+}
+
+pub struct TypeDeduplicator<'repo> {
+    type_entry_repository: &'repo TypeEntryRepository,
+}
+
+impl<'repo> TypeDeduplicator<'repo> {
+    pub fn new(type_entry_repository: &'repo TypeEntryRepository) -> Self {
+        Self {
+            type_entry_repository,
+        }
+    }
+
+    // Types are identical if they have the same names, sizes and members,
+    // and refer to identical types recursively.
+    // The classes are refined from the shapes until they are stable, which terminates on cycles.
+    pub fn deduplicate(&self) -> Vec<CanonicalType> {
+        let mut ids: Vec<TypeEntryId> = self
+            .type_entry_repository
+            .find_all()
+            .map(TypeEntry::id)
+            .collect();
+        ids.sort();
+        let mut indexes: HashMap<TypeEntryId, usize> = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.clone(), index))
+            .collect();
+
+        let mut shapes = Vec::new();
+        let mut references = Vec::new();
+        for id in ids.clone() {
+            let entry = self.type_entry_repository.find_by_id(&id).unwrap();
+            let mut refered = Vec::new();
+            for type_ref in Self::type_refs(&entry.kind) {
+                let index = *indexes.entry(type_ref.clone()).or_insert_with(|| {
+                    ids.push(type_ref.clone());
+                    ids.len() - 1
+                });
+                refered.push(index);
+            }
+            shapes.push(Self::shape(&entry.kind));
+            references.push(refered);
+        }
+        for id in &ids[shapes.len()..] {
+            shapes.push(Shape::Unknown(id.clone()));
+            references.push(Vec::new());
+        }
+
+        let mut classes = Self::number(shapes.iter());
+        loop {
+            let signatures = classes
+                .iter()
+                .zip(references.iter())
+                .map(|(class, refered)| {
+                    let refered: Vec<usize> = refered.iter().map(|index| classes[*index]).collect();
+                    (*class, refered)
+                });
+            let refined = Self::number(signatures);
+            // refinement never merges classes, so the same number of classes means stable
+            let stable = refined.iter().max() == classes.iter().max();
+            classes = refined;
+            if stable {
+                break;
+            }
+        }
+
+        let mut canonical_types: Vec<CanonicalType> = Vec::new();
+        let mut canonical_indexes: HashMap<usize, usize> = HashMap::new();
+        for (id, class) in ids.into_iter().zip(classes) {
+            let entry = match self.type_entry_repository.find_by_id(&id) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            // ids are sorted, so the first one of the class is at the smallest offset
+            match canonical_indexes.get(&class) {
+                Some(index) => canonical_types[*index].offsets.push(id),
+                None => {
+                    canonical_indexes.insert(class, canonical_types.len());
+                    canonical_types.push(CanonicalType {
+                        entry: entry.clone(),
+                        offsets: vec![id],
+                    });
+                }
+            }
+        }
+        canonical_types
+    }
+
+    // Numbers the keys in order of appearance
+    fn number<K: Eq + Hash>(keys: impl Iterator<Item = K>) -> Vec<usize> {
+        let mut numbers = HashMap::new();
+        keys.map(|key| {
+            let next = numbers.len();
+            *numbers.entry(key).or_insert(next)
+        })
+        .collect()
+    }
+
+    fn shape(kind: &TypeEntryKind) -> Shape {
+        match kind {
+            TypeEntryKind::TypeDef { name, .. } => Shape::TypeDef(name.clone()),
+            TypeEntryKind::VolatileType { .. } => Shape::Volatile,
+            TypeEntryKind::ConstType { .. } => Shape::Const,
+            TypeEntryKind::PointerType { size, type_ref } => {
+                Shape::Pointer(*size, type_ref.is_some())
+            }
+            TypeEntryKind::BaseType {
+                name,
+                size,
+                encoding,
+            } => Shape::Base(name.clone(), *size, *encoding),
+            TypeEntryKind::EnumType {
+                name, enumerators, ..
+            } => Shape::Enum(
+                name.clone(),
+                enumerators
+                    .iter()
+                    .map(|enumerator| (enumerator.name.clone(), enumerator.value))
+                    .collect(),
+            ),
+            TypeEntryKind::StructureType {
+                name,
+                size,
+                members,
+            } => Shape::Structure(
+                name.clone(),
+                *size,
+                members.iter().map(|member| (&**member).into()).collect(),
+            ),
+            TypeEntryKind::UnionType {
+                name,
+                size,
+                members,
+            } => Shape::Union(
+                name.clone(),
+                *size,
+                members.iter().map(|member| (&**member).into()).collect(),
+            ),
+            TypeEntryKind::ArrayType { upper_bound, .. } => Shape::Array(*upper_bound),
+            TypeEntryKind::FunctionType {
+                argument_type_ref,
+                return_type_ref,
+            } => Shape::Function(argument_type_ref.len(), return_type_ref.is_some()),
+        }
+    }
+
+    // The types which the type refers to, in order
+    fn type_refs(kind: &TypeEntryKind) -> Vec<&TypeEntryId> {
+        match kind {
+            TypeEntryKind::TypeDef { type_ref, .. }
+            | TypeEntryKind::VolatileType { type_ref }
+            | TypeEntryKind::ConstType { type_ref }
+            | TypeEntryKind::EnumType { type_ref, .. } => vec![type_ref],
+            TypeEntryKind::PointerType { type_ref, .. } => type_ref.iter().collect(),
+            TypeEntryKind::BaseType { .. } => Vec::new(),
+            TypeEntryKind::StructureType { members, .. } => {
+                members.iter().map(|member| &member.type_ref).collect()
+            }
+            TypeEntryKind::UnionType { members, .. } => {
+                members.iter().map(|member| &member.type_ref).collect()
+            }
+            TypeEntryKind::ArrayType {
+                element_type_ref, ..
+            } => vec![element_type_ref],
+            TypeEntryKind::FunctionType {
+                argument_type_ref,
+                return_type_ref,
+            } => argument_type_ref
+                .iter()
+                .chain(return_type_ref.iter())
+                .collect(),
+        }
+    }
+}
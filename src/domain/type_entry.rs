@@ -2,9 +2,10 @@ use std::marker::PhantomData;
 use std::ops::Deref;
 
 use super::entity::Entity;
+use super::source_location::SourceLocation;
 use crate::library::dwarf;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
 pub struct TypeEntryId(dwarf::Offset);
 impl TypeEntryId {
     pub fn new(offset: dwarf::Offset) -> TypeEntryId {
@@ -44,6 +45,8 @@ pub enum TypeEntryKind {
     BaseType {
         name: String,
         size: usize,
+        // None if DW_AT_encoding is missing or not supported
+        encoding: Option<dwarf::BaseTypeEncoding>,
     },
     EnumType {
         name: Option<String>,
@@ -80,22 +83,31 @@ pub struct EnumeratorEntry {
 pub struct TypeEntry {
     id: TypeEntryId,
     pub kind: TypeEntryKind,
+    pub source: SourceLocation,
 }
 
 impl TypeEntry {
+    fn new(id: TypeEntryId, kind: TypeEntryKind) -> TypeEntry {
+        TypeEntry {
+            id,
+            kind,
+            source: SourceLocation::default(),
+        }
+    }
+
     pub fn new_typedef_entry(id: TypeEntryId, name: String, type_ref: TypeEntryId) -> TypeEntry {
         let kind = TypeEntryKind::TypeDef { name, type_ref };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_volatile_type_entry(id: TypeEntryId, type_ref: TypeEntryId) -> TypeEntry {
         let kind = TypeEntryKind::VolatileType { type_ref };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_const_type_entry(id: TypeEntryId, type_ref: TypeEntryId) -> TypeEntry {
         let kind = TypeEntryKind::ConstType { type_ref };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_pointer_type_entry(
@@ -104,12 +116,24 @@ impl TypeEntry {
         type_ref: Option<TypeEntryId>,
     ) -> TypeEntry {
         let kind = TypeEntryKind::PointerType { size, type_ref };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_base_type_entry(id: TypeEntryId, name: String, size: usize) -> TypeEntry {
-        let kind = TypeEntryKind::BaseType { name, size };
-        TypeEntry { id, kind }
+        let kind = TypeEntryKind::BaseType {
+            name,
+            size,
+            encoding: None,
+        };
+        Self::new(id, kind)
+    }
+
+    // Only for base types
+    pub fn with_encoding(mut self, encoding: Option<dwarf::BaseTypeEncoding>) -> TypeEntry {
+        if let TypeEntryKind::BaseType { encoding: e, .. } = &mut self.kind {
+            *e = encoding;
+        }
+        self
     }
 
     pub fn new_enum_type_entry(
@@ -123,7 +147,7 @@ impl TypeEntry {
             type_ref,
             enumerators,
         };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_structure_type_entry(
@@ -137,7 +161,7 @@ impl TypeEntry {
             size,
             members,
         };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_union_type_entry(
@@ -151,7 +175,7 @@ impl TypeEntry {
             size,
             members,
         };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_array_type_entry(
@@ -163,7 +187,7 @@ impl TypeEntry {
             element_type_ref,
             upper_bound,
         };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn new_function_type_entry(
@@ -175,12 +199,17 @@ impl TypeEntry {
             argument_type_ref,
             return_type_ref,
         };
-        TypeEntry { id, kind }
+        Self::new(id, kind)
     }
 
     pub fn id(&self) -> TypeEntryId {
         self.id.clone()
     }
+
+    pub fn with_source(mut self, source: SourceLocation) -> Self {
+        self.source = source;
+        self
+    }
 }
 
 impl Entity for TypeEntry {
@@ -252,6 +281,7 @@ impl Deref for StructureTypeMemberEntry {
 impl UnionTypeMemberEntry {
     pub fn new(
         name: String,
+        location: usize,
         type_ref: TypeEntryId,
         bit_size: Option<usize>,
         bit_offset: Option<usize>,
@@ -259,6 +289,7 @@ impl UnionTypeMemberEntry {
         Self(
             MemberEntryBuilder::new_union()
                 .name(name)
+                .location(location)
                 .type_ref(type_ref)
                 .bit_size(bit_size)
                 .bit_offset(bit_offset)
@@ -366,6 +397,14 @@ impl<NameP, TypeRefP> MemberEntryBuilder<NameP, (), TypeRefP, Structure> {
     }
 }
 
+// Members of unions are located at 0 unless data_member_location is given
+impl<NameP, TypeRefP> MemberEntryBuilder<NameP, usize, TypeRefP, Union> {
+    pub fn location(mut self, location: usize) -> Self {
+        self.location = location;
+        self
+    }
+}
+
 impl<NameP, LocationP, T> MemberEntryBuilder<NameP, LocationP, (), T> {
     pub fn type_ref(
         self,
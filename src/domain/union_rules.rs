@@ -0,0 +1,232 @@
+use log::warn;
+use std::str::FromStr;
+
+use super::global_variable_view::{GlobalVariableView, TypeView};
+use super::value::Value;
+use super::value_encoder::parse_integer;
+use super::variable_path::{VariablePath, VariablePathElement};
+
+// Which members of tagged unions are active by the values of their discriminants,
+// one rule per line like `msg.type == MSG_A -> msg.u.a`. `#` starts a comment.
+// `[*]` applies the rule to each element of arrays, like `msgs[*].type == MSG_A -> msgs[*].u.a`,
+// where the discriminant is in the same elements as the member.
+// Unions without matching rules show all the members as reinterpretations of the same bytes.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UnionRules {
+    pub rules: Vec<UnionRule>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionRule {
+    pub discriminant: VariablePath,
+    // an enumerator name or an integer like the values of `set`
+    pub value: String,
+    pub member: VariablePath,
+}
+
+impl UnionRule {
+    // The paths to the member in the variable with `[*]` replaced by the indices of the arrays,
+    // and the paths to the discriminant in the same elements
+    fn paths(
+        &self,
+        variable_view: &mut GlobalVariableView,
+    ) -> Vec<(Vec<VariablePathElement>, Vec<VariablePathElement>)> {
+        expand(variable_view, &self.member.elements)
+            .into_iter()
+            .map(|member| {
+                let mut indices =
+                    member
+                        .iter()
+                        .zip(&self.member.elements)
+                        .filter_map(|(element, pattern)| match pattern {
+                            VariablePathElement::AnyIndex => Some(element.clone()),
+                            _ => None,
+                        });
+                let discriminant = self
+                    .discriminant
+                    .elements
+                    .iter()
+                    .map(|element| match element {
+                        VariablePathElement::AnyIndex => indices.next().unwrap(),
+                        element => element.clone(),
+                    })
+                    .collect();
+                (discriminant, member)
+            })
+            .collect()
+    }
+
+    fn matches(
+        &self,
+        variable_view: &mut GlobalVariableView,
+        discriminant: &[VariablePathElement],
+    ) -> bool {
+        let discriminant = match variable_view.find_by_path_mut(discriminant) {
+            Some(discriminant) => discriminant,
+            None => return false,
+        };
+        let text = self.value.as_str();
+        match &discriminant.value {
+            Some(Value::Enumerator { name, value }) => {
+                name.as_deref() == Some(text) || parse_integer(text) == Some(*value)
+            }
+            Some(Value::Signed(value)) => parse_integer(text) == Some(*value),
+            Some(Value::Unsigned(value)) => {
+                parse_integer(text).is_some_and(|integer| integer >= 0 && integer as u128 == *value)
+            }
+            Some(Value::Boolean(value)) => match text {
+                "true" | "1" => *value,
+                "false" | "0" => !*value,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl UnionRules {
+    // Keeps only the active members of the unions in the decoded variable,
+    // where the first matching rule of each union wins
+    pub fn apply(&self, variable_view: &mut GlobalVariableView) {
+        let names = [
+            Some(variable_view.name.clone()),
+            variable_view.qualified_name(),
+        ];
+        let rules = self
+            .rules
+            .iter()
+            .filter(|rule| names.contains(&Some(rule.member.name.clone())));
+        let mut selected: Vec<Vec<VariablePathElement>> = Vec::new();
+        for rule in rules {
+            for (discriminant, member) in rule.paths(variable_view) {
+                let (member_name, union_path) = match member.split_last() {
+                    Some((VariablePathElement::Member(name), union_path)) => (name, union_path),
+                    _ => continue,
+                };
+                if selected.iter().any(|selected| selected == union_path)
+                    || !rule.matches(variable_view, &discriminant)
+                {
+                    continue;
+                }
+                let union_view = match variable_view.find_by_path_mut(union_path) {
+                    Some(union_view) => union_view,
+                    None => continue,
+                };
+                if !matches!(union_view.type_view.underlying(), TypeView::Union { .. })
+                    || !union_view
+                        .children
+                        .iter()
+                        .any(|member| &member.name == member_name)
+                {
+                    warn!("{} is not a member of a union", rule.member);
+                    continue;
+                }
+                union_view
+                    .children
+                    .retain(|member| &member.name == member_name);
+                selected.push(union_path.to_vec());
+            }
+        }
+    }
+}
+
+// The paths in the variable the pattern matches, with `[*]` replaced by each index of the arrays.
+// Paths without `[*]` are kept as they are even if they are not in the variable.
+fn expand(
+    variable_view: &mut GlobalVariableView,
+    pattern: &[VariablePathElement],
+) -> Vec<Vec<VariablePathElement>> {
+    let (head, rest) = match pattern.split_first() {
+        None => return vec![Vec::new()],
+        Some(split) => split,
+    };
+    let heads = match head {
+        VariablePathElement::AnyIndex => variable_view
+            .children
+            .iter()
+            .filter_map(|element| element.name.parse().ok())
+            .map(VariablePathElement::Index)
+            .collect(),
+        head => vec![head.clone()],
+    };
+    let mut paths = Vec::new();
+    for head in heads {
+        let child = variable_view.find_by_path_mut(std::slice::from_ref(&head));
+        let rests = match child {
+            Some(child) => expand(child, rest),
+            None if !rest.contains(&VariablePathElement::AnyIndex) => vec![rest.to_vec()],
+            None => continue,
+        };
+        for rest in rests {
+            let mut path = vec![head.clone()];
+            path.extend(rest);
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+impl FromStr for UnionRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (condition, member) = match s.split_once("->") {
+            Some((condition, member)) => (condition, member.trim()),
+            None => return Err(format!("no `->` in the rule: {}", s)),
+        };
+        let (discriminant, value) = match condition.split_once("==") {
+            Some((discriminant, value)) => (discriminant.trim(), value.trim()),
+            None => return Err(format!("no `==` in the rule: {}", s)),
+        };
+        if value.is_empty() {
+            return Err(format!("no value in the rule: {}", s));
+        }
+        let discriminant = VariablePath::parse_pattern(discriminant)?;
+        let member = VariablePath::parse_pattern(member)?;
+        if !matches!(member.elements.last(), Some(VariablePathElement::Member(_))) {
+            return Err(format!("{} is not a member of a union", member));
+        }
+        if discriminant.name != member.name {
+            return Err(format!(
+                "{} and {} are not in the same variable",
+                discriminant, member
+            ));
+        }
+        let any_indices = |path: &VariablePath| {
+            path.elements
+                .iter()
+                .filter(|element| **element == VariablePathElement::AnyIndex)
+                .count()
+        };
+        if any_indices(&discriminant) != any_indices(&member) {
+            return Err(format!(
+                "{} and {} do not have the same number of [*]",
+                discriminant, member
+            ));
+        }
+        Ok(UnionRule {
+            discriminant,
+            value: value.to_string(),
+            member,
+        })
+    }
+}
+
+impl FromStr for UnionRules {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let rule = line
+                .parse()
+                .map_err(|message| format!("line {}: {}", i + 1, message))?;
+            rules.push(rule);
+        }
+        Ok(UnionRules { rules })
+    }
+}
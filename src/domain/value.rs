@@ -0,0 +1,13 @@
+// The value of a variable without children, decoded by its type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+    Boolean(bool),
+    // `name` is None if no enumerator has the value
+    Enumerator { name: Option<String>, value: i128 },
+    Pointer(u64),
+    // base types which cannot be decoded, like long double
+    Bytes(Vec<u8>),
+}
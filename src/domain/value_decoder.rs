@@ -0,0 +1,137 @@
+use super::global_variable_view::{GlobalVariableView, TypeView};
+use super::value::Value;
+use crate::library::dwarf::BaseTypeEncoding;
+use crate::library::memory::Memory;
+
+pub struct ValueDecoder<'memory> {
+    memory: &'memory dyn Memory,
+    little_endian: bool,
+}
+
+impl<'memory> ValueDecoder<'memory> {
+    pub fn new(memory: &'memory dyn Memory, little_endian: bool) -> Self {
+        Self {
+            memory,
+            little_endian,
+        }
+    }
+
+    // Decodes the variables without children whose bytes are in the memory,
+    // and returns whether any of them is decoded
+    pub fn decode(&self, variable_view: &mut GlobalVariableView) -> bool {
+        if !variable_view.children.is_empty() {
+            let mut decoded = false;
+            for child in variable_view.children.iter_mut() {
+                decoded |= self.decode(child);
+            }
+            return decoded;
+        }
+        variable_view.value = self.decode_value(variable_view);
+        variable_view.value.is_some()
+    }
+
+    fn decode_value(&self, variable_view: &GlobalVariableView) -> Option<Value> {
+        let address: usize = variable_view.address.clone()?.into();
+        let size = variable_view.size;
+        let (bit_offset, bit_size) = match (variable_view.bit_offset, variable_view.bit_size) {
+            (Some(bit_offset), Some(bit_size)) => (bit_offset, bit_size),
+            _ => (0, size * 8),
+        };
+
+        match variable_view.type_view.underlying() {
+            TypeView::Base { encoding, .. } => match encoding {
+                Some(BaseTypeEncoding::Float) => {
+                    let bytes = self.memory.read(address, size)?;
+                    match size {
+                        4 => Some(Value::Float(
+                            f32::from_bits(self.integer(&bytes)? as u32) as f64
+                        )),
+                        8 => Some(Value::Float(f64::from_bits(self.integer(&bytes)? as u64))),
+                        _ => Some(Value::Bytes(bytes)),
+                    }
+                }
+                Some(BaseTypeEncoding::Boolean) => {
+                    let value = self.read_bits(address, bit_offset, bit_size)?;
+                    Some(Value::Boolean(value != 0))
+                }
+                Some(BaseTypeEncoding::Signed) => {
+                    let value = self.read_bits(address, bit_offset, bit_size)?;
+                    Some(Value::Signed(sign_extend(value, bit_size)))
+                }
+                Some(BaseTypeEncoding::Unsigned) | None => {
+                    match self.read_bits(address, bit_offset, bit_size) {
+                        Some(value) => Some(Value::Unsigned(value)),
+                        // too large to be an integer
+                        None => self.memory.read(address, size).map(Value::Bytes),
+                    }
+                }
+            },
+            TypeView::Enum {
+                type_view,
+                enumerators,
+                ..
+            } => {
+                let value = self.read_bits(address, bit_offset, bit_size)?;
+                let value = match type_view.underlying() {
+                    TypeView::Base {
+                        encoding: Some(BaseTypeEncoding::Signed),
+                        ..
+                    } => sign_extend(value, bit_size),
+                    _ => value as i128,
+                };
+                let name = enumerators
+                    .iter()
+                    .find(|enumerator| enumerator.value as i128 == value)
+                    .map(|enumerator| enumerator.name.clone());
+                Some(Value::Enumerator { name, value })
+            }
+            TypeView::Pointer { .. } | TypeView::VoidPointer => {
+                let value = self.read_bits(address, 0, size * 8)?;
+                Some(Value::Pointer(value as u64))
+            }
+            // no children, like empty structures
+            _ => None,
+        }
+    }
+
+    // Bits are counted from the LSB of the bytes at the address, which are read as an integer
+    // and may be beyond the size of the storage unit, and only the bytes of the bits are read
+    fn read_bits(&self, address: usize, bit_offset: usize, bit_size: usize) -> Option<u128> {
+        if bit_size == 0 || bit_size > 128 {
+            return None;
+        }
+        // the bytes below the bits are skipped, which are the first ones in little endian
+        let skipped = bit_offset / 8;
+        let length = (bit_offset % 8 + bit_size).div_ceil(8);
+        let address = if self.little_endian {
+            address + skipped
+        } else {
+            address
+        };
+        let bytes = self.memory.read(address, length)?;
+        let value = self.integer(&bytes)? >> (bit_offset % 8);
+        Some(match bit_size {
+            128 => value,
+            _ => value & ((1 << bit_size) - 1),
+        })
+    }
+
+    fn integer(&self, bytes: &[u8]) -> Option<u128> {
+        if bytes.len() > 16 {
+            return None;
+        }
+        let mut buffer = [0; 16];
+        if self.little_endian {
+            buffer[..bytes.len()].copy_from_slice(bytes);
+            Some(u128::from_le_bytes(buffer))
+        } else {
+            buffer[16 - bytes.len()..].copy_from_slice(bytes);
+            Some(u128::from_be_bytes(buffer))
+        }
+    }
+}
+
+fn sign_extend(value: u128, bit_size: usize) -> i128 {
+    let shift = 128 - bit_size;
+    ((value << shift) as i128) >> shift
+}
@@ -0,0 +1,218 @@
+use super::global_variable_view::{GlobalVariableView, TypeView};
+use crate::library::dwarf::BaseTypeEncoding;
+use crate::library::memory::Memory;
+
+// Bytes to be written at the address
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryWrite {
+    pub address: usize,
+    pub bytes: Vec<u8>,
+}
+
+pub struct ValueEncoder<'memory> {
+    memory: &'memory dyn Memory,
+    little_endian: bool,
+}
+
+impl<'memory> ValueEncoder<'memory> {
+    pub fn new(memory: &'memory dyn Memory, little_endian: bool) -> Self {
+        Self {
+            memory,
+            little_endian,
+        }
+    }
+
+    // Encodes the text by the type of the variable, which is
+    // - an integer in decimal or hexadecimal like `-3` or `0x20`, also for pointers
+    // - `true`, `false`, `1` or `0` for booleans
+    // - a number like `2.5` for floats
+    // - an enumerator name or an integer for enums
+    // The bytes around bit fields are read from the memory to keep the other bits.
+    pub fn encode(
+        &self,
+        variable_view: &GlobalVariableView,
+        text: &str,
+    ) -> Result<MemoryWrite, String> {
+        let name = &variable_view.name;
+        let address: usize = match &variable_view.address {
+            Some(address) => address.clone().into(),
+            None => return Err(format!("{} has no address", name)),
+        };
+        let size = variable_view.size;
+        let (bit_offset, bit_size) = match (variable_view.bit_offset, variable_view.bit_size) {
+            (Some(bit_offset), Some(bit_size)) => (bit_offset, bit_size),
+            _ => (0, size * 8),
+        };
+        if bit_size == 0 {
+            return Err(format!("{} has no size", name));
+        }
+        let text = text.trim();
+        let invalid = || format!("invalid value for {}: {}", name, text);
+
+        let bits = match variable_view.type_view.underlying() {
+            TypeView::Base { encoding, .. } => match encoding {
+                Some(BaseTypeEncoding::Float) => {
+                    let value: f64 = text.parse().map_err(|_| invalid())?;
+                    let bytes = self.float_bytes(value, size)?;
+                    return Ok(MemoryWrite { address, bytes });
+                }
+                Some(BaseTypeEncoding::Boolean) => match text {
+                    "true" | "1" => 1,
+                    "false" | "0" => 0,
+                    _ => return Err(invalid()),
+                },
+                Some(BaseTypeEncoding::Signed) => {
+                    signed_bits(parse_integer(text).ok_or_else(invalid)?, bit_size)?
+                }
+                Some(BaseTypeEncoding::Unsigned) | None => {
+                    unsigned_bits(parse_integer(text).ok_or_else(invalid)?, bit_size)?
+                }
+            },
+            TypeView::Enum {
+                type_view,
+                enumerators,
+                ..
+            } => {
+                let value = match enumerators
+                    .iter()
+                    .find(|enumerator| enumerator.name == text)
+                {
+                    Some(enumerator) => enumerator.value as i128,
+                    None => parse_integer(text)
+                        .ok_or_else(|| format!("{} is not an enumerator of {}", text, name))?,
+                };
+                match type_view.underlying() {
+                    TypeView::Base {
+                        encoding: Some(BaseTypeEncoding::Signed),
+                        ..
+                    } => signed_bits(value, bit_size)?,
+                    _ => unsigned_bits(value, bit_size)?,
+                }
+            }
+            TypeView::Pointer { .. } | TypeView::VoidPointer => {
+                unsigned_bits(parse_integer(text).ok_or_else(invalid)?, bit_size)?
+            }
+            _ => return Err(format!("cannot set {}, set its members instead", name)),
+        };
+        self.write_bits(address, size, bit_offset, bit_size, bits)
+    }
+
+    fn float_bytes(&self, value: f64, size: usize) -> Result<Vec<u8>, String> {
+        match size {
+            4 => {
+                let single = value as f32;
+                if value.is_finite() && !single.is_finite() {
+                    return Err(format!("{} is out of range of float", value));
+                }
+                Ok(self.bytes(single.to_bits() as u128, 4))
+            }
+            8 => Ok(self.bytes(value.to_bits() as u128, 8)),
+            _ => Err(format!("cannot set floats of {} bytes", size)),
+        }
+    }
+
+    // Bits are counted from the LSB of the bytes at the address like ValueDecoder,
+    // and only the bytes of the bits are written to keep the neighbouring members
+    fn write_bits(
+        &self,
+        address: usize,
+        size: usize,
+        bit_offset: usize,
+        bit_size: usize,
+        bits: u128,
+    ) -> Result<MemoryWrite, String> {
+        if size > 16 {
+            return Err(format!("cannot set integers of {} bytes", size));
+        }
+        if bit_offset == 0 && bit_size == size * 8 {
+            return Ok(MemoryWrite {
+                address,
+                bytes: self.bytes(bits, size),
+            });
+        }
+        // the bytes below the bits are skipped, which are the first ones in little endian
+        let skipped = bit_offset / 8;
+        let length = (bit_offset % 8 + bit_size).div_ceil(8);
+        let shift = bit_offset % 8;
+        let address = if self.little_endian {
+            address + skipped
+        } else {
+            address
+        };
+        if length > 16 {
+            return Err(format!("cannot set bit fields of {} bits", bit_size));
+        }
+        let current = self
+            .memory
+            .read(address, length)
+            .ok_or_else(|| format!("{:#010x} is not in the memory", address))?;
+        let mask = mask(bit_size) << shift;
+        let value = (self.integer(&current) & !mask) | ((bits << shift) & mask);
+        Ok(MemoryWrite {
+            address,
+            bytes: self.bytes(value, length),
+        })
+    }
+
+    fn integer(&self, bytes: &[u8]) -> u128 {
+        let mut buffer = [0; 16];
+        if self.little_endian {
+            buffer[..bytes.len()].copy_from_slice(bytes);
+            u128::from_le_bytes(buffer)
+        } else {
+            buffer[16 - bytes.len()..].copy_from_slice(bytes);
+            u128::from_be_bytes(buffer)
+        }
+    }
+
+    fn bytes(&self, value: u128, size: usize) -> Vec<u8> {
+        if self.little_endian {
+            value.to_le_bytes()[..size].to_vec()
+        } else {
+            value.to_be_bytes()[16 - size..].to_vec()
+        }
+    }
+}
+
+// `-3`, `42` or `0x2a`, where the sign is only before the digits or `0x`
+pub(crate) fn parse_integer(text: &str) -> Option<i128> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (radix, digits) = match text.strip_prefix("0x") {
+        Some(hex) => (16, hex),
+        None => (10, text),
+    };
+    // the parsers of the standard library accept another sign
+    if digits.starts_with(['-', '+']) {
+        return None;
+    }
+    let value = i128::from_str_radix(digits, radix).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+fn mask(bit_size: usize) -> u128 {
+    match bit_size {
+        128 => u128::MAX,
+        _ => (1 << bit_size) - 1,
+    }
+}
+
+fn signed_bits(value: i128, bit_size: usize) -> Result<u128, String> {
+    if bit_size < 128 {
+        let max = (1i128 << (bit_size - 1)) - 1;
+        let min = -max - 1;
+        if value < min || value > max {
+            return Err(format!("{} is out of range {}..={}", value, min, max));
+        }
+    }
+    Ok(value as u128 & mask(bit_size))
+}
+
+fn unsigned_bits(value: i128, bit_size: usize) -> Result<u128, String> {
+    if value < 0 || (bit_size < 128 && value as u128 > mask(bit_size)) {
+        return Err(format!("{} is out of range 0..={}", value, mask(bit_size)));
+    }
+    Ok(value as u128)
+}
@@ -1,9 +1,10 @@
 use crate::library::dwarf;
 
 use super::entity::Entity;
+use super::source_location::SourceLocation;
 use super::type_entry::TypeEntryId;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
 pub struct VariableDeclarationEntryId(dwarf::Offset);
 impl VariableDeclarationEntryId {
     pub fn new(offset: dwarf::Offset) -> Self {
@@ -29,11 +30,29 @@ pub struct VariableDeclarationEntry {
     pub id: VariableDeclarationEntryId,
     pub name: String,
     pub type_ref: TypeEntryId,
+    pub external: bool,
+    pub source: SourceLocation,
 }
 
 impl VariableDeclarationEntry {
     pub fn new(id: VariableDeclarationEntryId, name: String, type_ref: TypeEntryId) -> Self {
-        Self { id, name, type_ref }
+        Self {
+            id,
+            name,
+            type_ref,
+            external: true,
+            source: SourceLocation::default(),
+        }
+    }
+
+    pub fn with_external(mut self, external: bool) -> Self {
+        self.external = external;
+        self
+    }
+
+    pub fn with_source(mut self, source: SourceLocation) -> Self {
+        self.source = source;
+        self
     }
 }
 
@@ -0,0 +1,80 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::expression::Parser;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariablePathElement {
+    Member(String),
+    Index(usize),
+    // `[*]` in the patterns of the paths, matching all the indices of arrays
+    AnyIndex,
+}
+
+// A path to a global variable or its part, like `g_config.timeouts[2]`.
+// Variables local to compilation units can be qualified with the unit, like `file.c:counter`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariablePath {
+    pub name: String,
+    pub elements: Vec<VariablePathElement>,
+}
+
+impl VariablePath {
+    // The name in DWARF without the compilation unit
+    pub fn unqualified_name(&self) -> &str {
+        split_qualified_name(&self.name).1
+    }
+
+    // Parses a path which can have `[*]` for all the indices of arrays, like `msgs[*].type`
+    pub fn parse_pattern(path: &str) -> Result<Self, String> {
+        Self::parse(path, true)
+    }
+
+    fn parse(path: &str, any_index: bool) -> Result<Self, String> {
+        let parse = |path| {
+            Parser::new(path)?
+                .any_index(any_index)
+                .variable_path_to_end()
+        };
+        match split_qualified_name(path) {
+            (Some(""), _) => Err(format!("empty compilation unit: {}", path)),
+            (Some(unit), rest) => {
+                let mut variable_path = parse(rest)?;
+                variable_path.name = format!("{}:{}", unit, variable_path.name);
+                Ok(variable_path)
+            }
+            (None, _) => parse(path),
+        }
+    }
+}
+
+// The compilation unit and the name of `src/file.c:counter`, split at the last `:`
+// since the names of units can have `:` like `C:\src\file.c`
+pub fn split_qualified_name(name: &str) -> (Option<&str>, &str) {
+    match name.rsplit_once(':') {
+        Some((unit, name)) => (Some(unit), name),
+        None => (None, name),
+    }
+}
+
+impl FromStr for VariablePath {
+    type Err = String;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::parse(path, false)
+    }
+}
+
+impl fmt::Display for VariablePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for element in &self.elements {
+            match element {
+                VariablePathElement::Member(member) => write!(f, ".{}", member)?,
+                VariablePathElement::Index(index) => write!(f, "[{}]", index)?,
+                VariablePathElement::AnyIndex => write!(f, "[*]")?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,12 @@
+use crate::domain::budget::BudgetUsage;
+use crate::domain::expression_evaluator::ExpressionValue;
 use crate::domain::global_variable_view::*;
+use crate::domain::image_verifier::ImageMismatch;
+use crate::domain::linker_map_matcher::{LinkerMapMismatch, ObjectGroup};
+use crate::domain::odr_checker::OdrViolation;
+use crate::domain::source_location::SourceLocation;
+use crate::domain::type_deduplicator::CanonicalTypeView;
+use crate::domain::value::Value;
 use std::fmt;
 use std::fmt::Write;
 
@@ -6,63 +14,524 @@ const ADDRESS_WIDTH: usize = 10;
 const SIZE_WIDTH: usize = 5;
 const BITFIELD_WIDTH: usize = 7;
 const VARIABLE_NAME_WIDTH: usize = 20;
+const COPIES_WIDTH: usize = 6;
+const LINE_WIDTH: usize = 5;
 
 pub struct FromElfStdOut {
     blocks: Vec<FromElfBlock>,
+    source_widths: Option<SourceWidths>,
 }
 
 impl FromElfStdOut {
-    pub fn new(variable_views: Vec<GlobalVariableView>) -> FromElfStdOut {
-        let blocks = variable_views
+    // `source` prepends the columns of the declarations and the compilation units,
+    // and `little_endian` is the byte order of the ELF to show the bits of bit fields
+    pub fn new(
+        variable_views: Vec<GlobalVariableView>,
+        source: bool,
+        little_endian: bool,
+    ) -> FromElfStdOut {
+        let blocks: Vec<FromElfBlock> = variable_views
             .into_iter()
-            .map(|variable_view| FromElfBlock::from_variable_view(variable_view))
+            .map(|variable_view| {
+                FromElfBlock::from_variable_view(variable_view, source, little_endian)
+            })
             .collect();
-        FromElfStdOut { blocks: blocks }
+        // the columns are aligned through all the blocks
+        let source_widths = if source {
+            Some(SourceWidths::new(
+                blocks
+                    .iter()
+                    .flat_map(|block| block.lines.iter())
+                    .filter_map(|line| line.source.as_ref()),
+            ))
+        } else {
+            None
+        };
+        FromElfStdOut {
+            blocks,
+            source_widths,
+        }
     }
 
     pub fn print(&self) {
         for block in &self.blocks {
-            block.print();
+            block.print(self.source_widths.as_ref());
             println!();
         }
     }
 }
 
+pub struct ExpressionStdOut {
+    expression: String,
+    value: ExpressionValue,
+    // the value of the object decoded from a memory
+    decoded: Option<Value>,
+    little_endian: bool,
+}
+
+impl ExpressionStdOut {
+    pub fn new(
+        expression: String,
+        value: ExpressionValue,
+        decoded: Option<Value>,
+        little_endian: bool,
+    ) -> ExpressionStdOut {
+        ExpressionStdOut {
+            expression,
+            value,
+            decoded,
+            little_endian,
+        }
+    }
+
+    pub fn print(&self) {
+        match &self.value {
+            ExpressionValue::Object {
+                address,
+                size,
+                bit_size,
+                bit_offset,
+                type_view,
+            } => {
+                let bitfield = OptionalBitField::new(*bit_offset, *bit_size, self.little_endian);
+                let line = FromElfLine {
+                    address: address.clone().map(|addr| addr.into()),
+                    size: bitfield.bytes().unwrap_or(*size),
+                    bitfield,
+                    variable_name: self.expression.clone(),
+                    variable_type: type_view.to_string(),
+                    value: self.decoded.clone(),
+                    source: None,
+                };
+                FromElfBlock { lines: vec![line] }.print(None);
+            }
+            ExpressionValue::Pointer { address, type_view } => {
+                let address: usize = address.clone().map_or(0, |addr| addr.into());
+                println!(
+                    "{} = {:#0ADDRESS_WIDTH$x} ({})",
+                    self.expression,
+                    address,
+                    type_view,
+                    ADDRESS_WIDTH = ADDRESS_WIDTH
+                );
+            }
+            ExpressionValue::Integer(value) => {
+                println!("{} = {} ({:#x})", self.expression, value, value);
+            }
+        }
+    }
+}
+
+pub struct OdrStdOut {
+    violations: Vec<OdrViolation>,
+}
+
+impl OdrStdOut {
+    pub fn new(violations: Vec<OdrViolation>) -> OdrStdOut {
+        OdrStdOut { violations }
+    }
+
+    pub fn print(&self) {
+        for violation in &self.violations {
+            println!("{} is defined differently", violation.name);
+            for difference in &violation.differences {
+                println!("  {}", difference);
+            }
+            for (i, sources) in violation.definitions.iter().enumerate() {
+                let mut places: Vec<String> = Vec::new();
+                for place in sources.iter().map(Self::place) {
+                    if !places.contains(&place) {
+                        places.push(place);
+                    }
+                }
+                println!("  definition {}: {}", i + 1, places.join(", "));
+            }
+            println!();
+        }
+    }
+
+    // `unit (file:line)`
+    fn place(source: &SourceLocation) -> String {
+        let unit = source
+            .compilation_unit
+            .as_ref()
+            .and_then(|unit| unit.name.clone());
+        match (unit, source.file.is_some()) {
+            (Some(unit), true) => format!("{} ({})", unit, source),
+            (Some(unit), false) => unit,
+            (None, true) => source.to_string(),
+            (None, false) => String::from("unknown"),
+        }
+    }
+}
+
+pub struct ObjectGroupStdOut {
+    groups: Vec<(String, usize, FromElfStdOut)>,
+}
+
+impl ObjectGroupStdOut {
+    pub fn new(groups: Vec<ObjectGroup>, source: bool, little_endian: bool) -> ObjectGroupStdOut {
+        let groups = groups
+            .into_iter()
+            .map(|group| {
+                let object = group.object.unwrap_or_else(|| String::from("unknown"));
+                let variables = FromElfStdOut::new(group.variable_views, source, little_endian);
+                (object, group.size, variables)
+            })
+            .collect();
+        ObjectGroupStdOut { groups }
+    }
+
+    pub fn print(&self) {
+        for (object, size, variables) in &self.groups {
+            println!("{}  total = {:#x}", object, size);
+            println!();
+            variables.print();
+        }
+    }
+}
+
+pub struct LinkerMapStdOut {
+    mismatches: Vec<LinkerMapMismatch>,
+}
+
+impl LinkerMapStdOut {
+    pub fn new(mismatches: Vec<LinkerMapMismatch>) -> LinkerMapStdOut {
+        LinkerMapStdOut { mismatches }
+    }
+
+    pub fn print(&self) {
+        for mismatch in &self.mismatches {
+            println!("{} disagrees with the map", mismatch.name);
+            for difference in &mismatch.differences {
+                println!("  {}", difference);
+            }
+            if let Some(object) = &mismatch.object {
+                println!("  object: {}", object);
+            }
+            println!();
+        }
+    }
+}
+
+pub struct ImageMismatchStdOut {
+    mismatches: Vec<ImageMismatch>,
+}
+
+impl ImageMismatchStdOut {
+    pub fn new(mismatches: Vec<ImageMismatch>) -> ImageMismatchStdOut {
+        ImageMismatchStdOut { mismatches }
+    }
+
+    pub fn print(&self) {
+        let value = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => String::from("-"),
+        };
+        for mismatch in &self.mismatches {
+            println!("{} differs from the ELF", mismatch.name);
+            println!("  elf:   {}", value(&mismatch.expected));
+            match &mismatch.actual {
+                None => println!("  image: not in the image"),
+                actual => println!("  image: {}", value(actual)),
+            }
+            println!();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+pub struct BudgetStdOut {
+    usages: Vec<BudgetUsage>,
+    format: ReportFormat,
+}
+
+impl BudgetStdOut {
+    pub fn new(usages: Vec<BudgetUsage>, format: ReportFormat) -> BudgetStdOut {
+        BudgetStdOut { usages, format }
+    }
+
+    pub fn print(&self) {
+        match self.format {
+            ReportFormat::Text => self.print_text(),
+            ReportFormat::Json => self.print_json(),
+        }
+    }
+
+    fn print_text(&self) {
+        println!(
+            "{:VARIABLE_NAME_WIDTH$} {:>10} {:>10} {:>7}",
+            "target",
+            "used",
+            "limit",
+            "usage",
+            VARIABLE_NAME_WIDTH = VARIABLE_NAME_WIDTH
+        );
+        for usage in &self.usages {
+            let percentage = if usage.limit == 0 {
+                String::from("-")
+            } else {
+                format!("{:.1}%", usage.used as f64 * 100.0 / usage.limit as f64)
+            };
+            println!(
+                "{:VARIABLE_NAME_WIDTH$} {:>10} {:>10} {:>7}{}",
+                usage.target.to_string(),
+                usage.used,
+                usage.limit,
+                percentage,
+                if usage.exceeded() { "  exceeded" } else { "" },
+                VARIABLE_NAME_WIDTH = VARIABLE_NAME_WIDTH
+            );
+        }
+    }
+
+    // `{"exceeded": 1, "usages": [{"target": ".bss", "used": 1024, "limit": 4096, "exceeded": false}, ...]}`
+    fn print_json(&self) {
+        let usages: Vec<String> = self
+            .usages
+            .iter()
+            .map(|usage| {
+                format!(
+                    "{{\"target\": {}, \"used\": {}, \"limit\": {}, \"exceeded\": {}}}",
+                    json_string(&usage.target.to_string()),
+                    usage.used,
+                    usage.limit,
+                    usage.exceeded()
+                )
+            })
+            .collect();
+        let exceeded = self.usages.iter().filter(|usage| usage.exceeded()).count();
+        println!(
+            "{{\"exceeded\": {}, \"usages\": [{}]}}",
+            exceeded,
+            usages.join(", ")
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleFormat {
+    Csv,
+    Json,
+}
+
+// A line per sample, led by the seconds since the Unix epoch
+pub struct SampleStdOut {
+    names: Vec<String>,
+    format: SampleFormat,
+}
+
+impl SampleStdOut {
+    pub fn new(names: Vec<String>, format: SampleFormat) -> SampleStdOut {
+        SampleStdOut { names, format }
+    }
+
+    // The header line of CSV, nothing for JSON
+    pub fn print_header(&self) {
+        if self.format == SampleFormat::Csv {
+            println!("timestamp,{}", self.names.join(","));
+        }
+    }
+
+    // Values which cannot be read are empty in CSV and null in JSON
+    pub fn print(&self, timestamp: f64, values: &[Option<Value>]) {
+        match self.format {
+            SampleFormat::Csv => {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| value.as_ref().map(csv_value).unwrap_or_default())
+                    .collect();
+                println!("{:.6},{}", timestamp, values.join(","));
+            }
+            // `{"timestamp": 1700000000.000000, "state": "RUNNING", "counter.ticks": 42}`
+            SampleFormat::Json => {
+                let values: Vec<String> = self
+                    .names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| {
+                        let value = value.as_ref().map(json_value);
+                        format!(
+                            "{}: {}",
+                            json_string(name),
+                            value.as_deref().unwrap_or("null")
+                        )
+                    })
+                    .collect();
+                println!("{{\"timestamp\": {:.6}, {}}}", timestamp, values.join(", "));
+            }
+        }
+    }
+}
+
+// Numbers without hexadecimal and enums by their names, to be easy to plot
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Unsigned(value) => value.to_string(),
+        Value::Enumerator {
+            name: Some(name), ..
+        } => name.clone(),
+        Value::Enumerator { name: None, value } => value.to_string(),
+        value => value.to_string(),
+    }
+}
+
+fn json_value(value: &Value) -> String {
+    match value {
+        Value::Float(value) if !value.is_finite() => String::from("null"),
+        Value::Enumerator {
+            name: Some(name), ..
+        } => json_string(name),
+        Value::Pointer(_) | Value::Bytes(_) => json_string(&value.to_string()),
+        value => csv_value(value),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+pub struct TypeStdOut {
+    types: Vec<CanonicalTypeView>,
+    // the width of the column of the declaration files, which is shown with `source`
+    file_width: Option<usize>,
+}
+
+impl TypeStdOut {
+    pub fn new(types: Vec<CanonicalTypeView>, source: bool) -> TypeStdOut {
+        let file_width = if source {
+            types
+                .iter()
+                .filter_map(|canonical_type| canonical_type.source.file.as_ref())
+                .map(String::len)
+                .chain(std::iter::once("file".len()))
+                .max()
+        } else {
+            None
+        };
+        TypeStdOut { types, file_width }
+    }
+
+    // The offset of the first one of the identical types and the number of them
+    pub fn print(&self) {
+        let sources = self.file_width.map_or(String::new(), |file_width| {
+            format!(
+                "{:file_width$} {:LINE_WIDTH$} ",
+                "file",
+                "line",
+                file_width = file_width,
+                LINE_WIDTH = LINE_WIDTH
+            )
+        });
+        println!(
+            "{}{:ADDRESS_WIDTH$} {:COPIES_WIDTH$} type",
+            sources,
+            "offset",
+            "copies",
+            ADDRESS_WIDTH = ADDRESS_WIDTH,
+            COPIES_WIDTH = COPIES_WIDTH
+        );
+        for canonical_type in &self.types {
+            let source = &canonical_type.source;
+            let sources = self.file_width.map_or(String::new(), |file_width| {
+                format!(
+                    "{:file_width$} {:LINE_WIDTH$} ",
+                    source.file.as_deref().unwrap_or(""),
+                    source.line.map_or(String::new(), |line| line.to_string()),
+                    file_width = file_width,
+                    LINE_WIDTH = LINE_WIDTH
+                )
+            });
+            let offset: usize = canonical_type.offsets[0].clone().into();
+            println!(
+                "{}{:#0ADDRESS_WIDTH$x} {:<COPIES_WIDTH$} {}",
+                sources,
+                offset,
+                canonical_type.offsets.len(),
+                canonical_type.type_view,
+                ADDRESS_WIDTH = ADDRESS_WIDTH,
+                COPIES_WIDTH = COPIES_WIDTH
+            );
+        }
+    }
+}
+
 struct FromElfBlock {
     lines: Vec<FromElfLine>,
 }
 
 impl FromElfBlock {
-    fn from_variable_view(variable_view: GlobalVariableView) -> FromElfBlock {
-        Self::from_variable_view_with_parent(variable_view, &ParentName::None)
+    fn from_variable_view(
+        variable_view: GlobalVariableView,
+        source: bool,
+        little_endian: bool,
+    ) -> FromElfBlock {
+        let source = if source {
+            Sources::new(&variable_view.source, &variable_view.type_source)
+        } else {
+            None
+        };
+        let mut block =
+            Self::from_variable_view_with_parent(variable_view, &ParentName::None, little_endian);
+        // sources are shown only at the top level, since members share them
+        if let Some(line) = block.lines.first_mut() {
+            line.source = source;
+        }
+        block
     }
 
     fn from_variable_view_with_parent(
         variable_view: GlobalVariableView,
         parent_name: &ParentName,
+        little_endian: bool,
     ) -> FromElfBlock {
         let variable_name = parent_name.with_parent(&variable_view.name);
         let parent_name = parent_name
             .new_parent_from_variable_view(&variable_view.name, &variable_view.type_view);
 
+        let bitfield = OptionalBitField::new(
+            variable_view.bit_offset,
+            variable_view.bit_size,
+            little_endian,
+        );
         let mut lines = vec![FromElfLine {
             address: variable_view.address.map(|addr| addr.clone().into()),
-            size: variable_view.size,
-            bitfield: OptionalBitField::new(variable_view.bit_offset, variable_view.bit_size),
+            // bit fields are moved to the bytes they occupy, not the storage units
+            size: bitfield.bytes().unwrap_or(variable_view.size),
+            bitfield,
             variable_name: variable_name,
             variable_type: variable_view.type_view.to_string(),
+            value: variable_view.value,
+            source: None,
         }];
 
         for child in variable_view.children {
-            let mut block = Self::from_variable_view_with_parent(child, &parent_name);
+            let mut block =
+                Self::from_variable_view_with_parent(child, &parent_name, little_endian);
             lines.append(&mut block.lines);
         }
         FromElfBlock { lines }
     }
 
-    fn print(&self) {
+    fn print(&self, source_widths: Option<&SourceWidths>) {
         println!(
-            "{:ADDRESS_WIDTH$} {:SIZE_WIDTH$}{:BITFIELD_WIDTH$} {:VARIABLE_NAME_WIDTH$} {}",
+            "{}{:ADDRESS_WIDTH$} {:SIZE_WIDTH$}{:BITFIELD_WIDTH$} {:VARIABLE_NAME_WIDTH$} {}",
+            source_widths.map_or(String::new(), SourceWidths::header),
             "address",
             "size",
             "(bit)",
@@ -74,7 +543,9 @@ impl FromElfBlock {
             VARIABLE_NAME_WIDTH = VARIABLE_NAME_WIDTH
         );
         for line in &self.lines {
-            println!("{}", line);
+            let sources =
+                source_widths.map_or(String::new(), |widths| widths.columns(line.source.as_ref()));
+            println!("{}{}", sources, line);
         }
     }
 }
@@ -85,6 +556,8 @@ struct FromElfLine {
     bitfield: OptionalBitField,
     variable_name: String,
     variable_type: String,
+    value: Option<Value>,
+    source: Option<Sources>,
 }
 
 impl fmt::Display for FromElfLine {
@@ -102,7 +575,90 @@ impl fmt::Display for FromElfLine {
             SIZE_WIDTH = SIZE_WIDTH,
             BITFIELD_WIDTH = BITFIELD_WIDTH,
             VARIABLE_NAME_WIDTH = VARIABLE_NAME_WIDTH,
-        )
+        )?;
+        if let (Some(address), Some(bitfield)) = (self.address, &self.bitfield.0) {
+            write!(f, "  bits = {}", bitfield.range(address))?;
+        }
+        match &self.value {
+            Some(value) => write!(f, "  value = {}", value),
+            None => Ok(()),
+        }
+    }
+}
+
+// The optional columns to jump from variables to their sources
+struct Sources {
+    source: SourceLocation,
+    type_source: SourceLocation,
+}
+
+const SOURCE_COLUMNS: [&str; 7] = [
+    "file",
+    "line",
+    "type_file",
+    "type_line",
+    "unit",
+    "comp_dir",
+    "producer",
+];
+
+impl Sources {
+    fn new(source: &SourceLocation, type_source: &SourceLocation) -> Option<Self> {
+        if source.is_empty() && type_source.is_empty() {
+            return None;
+        }
+        Some(Sources {
+            source: source.clone(),
+            type_source: type_source.clone(),
+        })
+    }
+
+    // The cells in the order of `SOURCE_COLUMNS`, which are empty if they are not known
+    fn cells(&self) -> [String; 7] {
+        let unit = self.source.compilation_unit.as_ref();
+        let cell = |value: Option<&String>| value.cloned().unwrap_or_default();
+        let line = |line: Option<usize>| line.map_or(String::new(), |line| line.to_string());
+        [
+            cell(self.source.file.as_ref()),
+            line(self.source.line),
+            cell(self.type_source.file.as_ref()),
+            line(self.type_source.line),
+            cell(unit.and_then(|unit| unit.name.as_ref())),
+            cell(unit.and_then(|unit| unit.comp_dir.as_ref())),
+            cell(unit.and_then(|unit| unit.producer.as_ref())),
+        ]
+    }
+}
+
+// The widths of the columns of the sources, which fit the longest cells
+struct SourceWidths([usize; 7]);
+
+impl SourceWidths {
+    fn new<'a>(sources: impl Iterator<Item = &'a Sources>) -> Self {
+        let mut widths = SOURCE_COLUMNS.map(str::len);
+        for sources in sources {
+            for (width, cell) in widths.iter_mut().zip(sources.cells().iter()) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        SourceWidths(widths)
+    }
+
+    fn header(&self) -> String {
+        self.row(&SOURCE_COLUMNS.map(String::from))
+    }
+
+    // members share the sources of their variables, and leave the columns empty
+    fn columns(&self, sources: Option<&Sources>) -> String {
+        self.row(&sources.map(Sources::cells).unwrap_or_default())
+    }
+
+    fn row(&self, cells: &[String; 7]) -> String {
+        self.0
+            .iter()
+            .zip(cells.iter())
+            .map(|(width, cell)| format!("{:width$} ", cell, width = width))
+            .collect()
     }
 }
 
@@ -111,6 +667,9 @@ enum ParentName {
     Structure(String),
     Union(String),
     Array(String),
+    // the variable the pointer points to, whose members are like `p->member`
+    Pointer(String),
+    Pointee(String),
 }
 
 impl ParentName {
@@ -119,17 +678,26 @@ impl ParentName {
         variable_view_name: &String,
         type_view: &TypeView,
     ) -> ParentName {
-        match type_view {
-            TypeView::Structure { .. } => Self::Structure(self.with_parent(variable_view_name)),
-            TypeView::Union { .. } => Self::Union(self.with_parent(variable_view_name)),
-            TypeView::Array { .. } => Self::Array(self.with_parent(variable_view_name)),
-            TypeView::TypeDef { type_view, .. } => {
+        match (self, type_view) {
+            (Self::Pointer(pointer), TypeView::Structure { .. } | TypeView::Union { .. }) => {
+                Self::Pointee(pointer.clone())
+            }
+            (Self::Pointer(pointer), TypeView::Array { .. }) => {
+                Self::Array(format!("(*{})", pointer))
+            }
+            (_, TypeView::Structure { .. }) => {
+                Self::Structure(self.with_parent(variable_view_name))
+            }
+            (_, TypeView::Union { .. }) => Self::Union(self.with_parent(variable_view_name)),
+            (_, TypeView::Array { .. }) => Self::Array(self.with_parent(variable_view_name)),
+            (_, TypeView::Pointer { .. }) => Self::Pointer(self.with_parent(variable_view_name)),
+            (_, TypeView::TypeDef { type_view, .. }) => {
                 self.new_parent_from_variable_view(variable_view_name, type_view)
             }
-            TypeView::Volatile { type_view } => {
+            (_, TypeView::Volatile { type_view }) => {
                 self.new_parent_from_variable_view(variable_view_name, type_view)
             }
-            TypeView::Const { type_view } => {
+            (_, TypeView::Const { type_view }) => {
                 self.new_parent_from_variable_view(variable_view_name, type_view)
             }
             _ => Self::None,
@@ -142,23 +710,98 @@ impl ParentName {
             Self::Structure(parent_name) => format!("{}.{}", parent_name, child_name),
             Self::Union(parent_name) => format!("{}.{}", parent_name, child_name),
             Self::Array(parent_name) => format!("{}[{}]", parent_name, child_name),
+            Self::Pointer(parent_name) => format!("*{}", parent_name),
+            Self::Pointee(parent_name) => format!("{}->{}", parent_name, child_name),
         }
     }
 }
 
 struct OptionalBitField(Option<BitField>);
 impl OptionalBitField {
-    fn new(offset: Option<usize>, size: Option<usize>) -> Self {
+    fn new(offset: Option<usize>, size: Option<usize>, little_endian: bool) -> Self {
         match (offset, size) {
-            (Some(offset), Some(size)) => OptionalBitField(Some(BitField { offset, size })),
+            (Some(offset), Some(size)) => OptionalBitField(Some(BitField {
+                offset,
+                size,
+                little_endian,
+            })),
             _ => OptionalBitField(None),
         }
     }
+
+    fn bytes(&self) -> Option<usize> {
+        self.0.as_ref().map(BitField::bytes)
+    }
 }
 
+// The offset is counted from the LSB of the bytes the bit field occupies,
+// which are read as an integer in the byte order of the ELF
 struct BitField {
     offset: usize,
     size: usize,
+    little_endian: bool,
+}
+
+impl BitField {
+    fn bytes(&self) -> usize {
+        (self.offset + self.size).div_ceil(8)
+    }
+
+    // The absolute range of the bits as `address.bit` from the first byte to the last byte
+    // in memory, where bits are counted from the LSB of each byte
+    fn range(&self, address: usize) -> String {
+        // where the n-th bit from the LSB of the bytes at the address is
+        let position = |n: usize| {
+            if self.little_endian {
+                (address + n / 8, n % 8)
+            } else {
+                (address + self.bytes() - 1 - n / 8, n % 8)
+            }
+        };
+        let lsb = position(self.offset);
+        let msb = position(self.offset + self.size - 1);
+        let (first, last) = if self.little_endian {
+            (lsb, msb)
+        } else {
+            (msb, lsb)
+        };
+        format!(
+            "{:#0ADDRESS_WIDTH$x}.{}-{:#0ADDRESS_WIDTH$x}.{}",
+            first.0,
+            first.1,
+            last.0,
+            last.1,
+            ADDRESS_WIDTH = ADDRESS_WIDTH
+        )
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Signed(value) => write!(f, "{}", value),
+            Value::Unsigned(value) => write!(f, "{} ({:#x})", value, value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::Enumerator {
+                name: Some(name),
+                value,
+            } => write!(f, "{} ({})", name, value),
+            Value::Enumerator { name: None, value } => write!(f, "{} (no enumerator)", value),
+            Value::Pointer(value) => {
+                write!(
+                    f,
+                    "{:#0ADDRESS_WIDTH$x}",
+                    value,
+                    ADDRESS_WIDTH = ADDRESS_WIDTH
+                )
+            }
+            Value::Bytes(bytes) => {
+                let bytes: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+                write!(f, "[{}]", bytes.join(" "))
+            }
+        }
+    }
 }
 
 impl fmt::Display for OptionalBitField {
@@ -192,7 +835,7 @@ impl fmt::Display for TypeView {
             TypeView::Const { type_view } => format!("const {}", type_view).fmt(f),
             TypeView::VoidPointer => format!("void pointer").fmt(f),
             TypeView::Pointer { type_view } => format!("pointer to {}", type_view).fmt(f),
-            TypeView::Base { name } => format!("{}", name).fmt(f),
+            TypeView::Base { name, .. } => format!("{}", name).fmt(f),
             TypeView::Structure { name } => {
                 format!("struct {}", name.as_ref().unwrap_or(&String::from(""))).fmt(f)
             }
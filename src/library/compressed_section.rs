@@ -0,0 +1,122 @@
+use flate2::read::ZlibDecoder;
+use log::warn;
+use object::{Object, ObjectSection, SectionFlags};
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::io::Read;
+
+const SHF_COMPRESSED: u64 = 0x800;
+const ELFCOMPRESS_ZLIB: u32 = 1;
+const ELFCOMPRESS_ZSTD: u32 = 2;
+
+enum Compression {
+    Zlib,
+    Zstd,
+}
+
+// Returns the uncompressed data of the section.
+// Sections are compressed with SHF_COMPRESSED by `--compress-debug-sections`,
+// or renamed to .zdebug_* by the legacy GNU style compression.
+pub fn section_data<'data>(object: &object::File<'data>, name: &str) -> Option<Cow<'data, [u8]>> {
+    // object falls back to .zdebug_* sections when .debug_* sections are not found
+    let section = object.section_by_name(name)?;
+    let data = section.data();
+    let is_gnu_compressed = matches!(section.name(), Some(name) if name.starts_with(".zdebug_"));
+    let is_compressed = match section.flags() {
+        SectionFlags::Elf { sh_flags } => sh_flags & SHF_COMPRESSED != 0,
+        _ => false,
+    };
+
+    let uncompressed = if is_gnu_compressed {
+        decompress_gnu(&data)
+    } else if is_compressed {
+        decompress_elf(object, &data)
+    } else {
+        return Some(data);
+    };
+    match uncompressed {
+        Some(uncompressed) => Some(Cow::Owned(uncompressed)),
+        None => {
+            warn!("failed to decompress section: {}", name);
+            None
+        }
+    }
+}
+
+fn decompress_gnu(data: &[u8]) -> Option<Vec<u8>> {
+    // "ZLIB" followed by the uncompressed size in big endian
+    if data.get(..4)? != b"ZLIB" {
+        return None;
+    }
+    let size = u64::from_be_bytes(data.get(4..12)?.try_into().ok()?);
+    decompress(Compression::Zlib, data.get(12..)?, size as usize)
+}
+
+fn decompress_elf(object: &object::File, data: &[u8]) -> Option<Vec<u8>> {
+    let read_u32 = |bytes: &[u8]| -> Option<u32> {
+        let bytes = bytes.try_into().ok()?;
+        Some(if object.is_little_endian() {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+    let read_u64 = |bytes: &[u8]| -> Option<u64> {
+        let bytes = bytes.try_into().ok()?;
+        Some(if object.is_little_endian() {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    };
+
+    // Elf32_Chdr and Elf64_Chdr
+    let (compression_type, size, header_size) = if object.is_64() {
+        let size = read_u64(data.get(8..16)?)?;
+        (read_u32(data.get(..4)?)?, size as usize, 24)
+    } else {
+        let size = read_u32(data.get(4..8)?)?;
+        (read_u32(data.get(..4)?)?, size as usize, 12)
+    };
+    let compression = match compression_type {
+        ELFCOMPRESS_ZLIB => Compression::Zlib,
+        ELFCOMPRESS_ZSTD => Compression::Zstd,
+        compression_type => {
+            warn!("unknown compression type: {}", compression_type);
+            return None;
+        }
+    };
+    decompress(compression, data.get(header_size..)?, size)
+}
+
+fn decompress(compression: Compression, data: &[u8], size: usize) -> Option<Vec<u8>> {
+    // the size in the header is not trusted to allocate, nor to decompress beyond it
+    let mut uncompressed = Vec::with_capacity(size.min(data.len().saturating_mul(64)));
+    let limit = size as u64 + 1;
+    let result = match compression {
+        Compression::Zlib => ZlibDecoder::new(data)
+            .take(limit)
+            .read_to_end(&mut uncompressed),
+        Compression::Zstd => {
+            let mut data = data;
+            ruzstd::StreamingDecoder::new(&mut data)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+                .and_then(|decoder| decoder.take(limit).read_to_end(&mut uncompressed))
+        }
+    };
+    match result {
+        Ok(_) if uncompressed.len() == size => Some(uncompressed),
+        Ok(_) => {
+            warn!(
+                "size of decompressed section is different: expected {:#x}, actual {:#x}",
+                size,
+                uncompressed.len()
+            );
+            None
+        }
+        Err(err) => {
+            warn!("failed to decompress: {}", err);
+            None
+        }
+    }
+}
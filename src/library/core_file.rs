@@ -0,0 +1,101 @@
+use log::warn;
+use object::Object;
+use std::fs;
+
+use super::elf_reader::{ElfReader, PT_LOAD, PT_NOTE};
+use super::memory::Memory;
+
+const ET_CORE: u16 = 4;
+
+struct Segment {
+    address: usize,
+    size: usize,
+    offset: usize,
+    // bytes beyond the file size are not dumped, like read-only mappings of files
+    file_size: usize,
+}
+
+// An ELF core file, whose PT_LOAD segments are the memory of the process at the crash
+pub struct CoreFile {
+    mmap: memmap::Mmap,
+    segments: Vec<Segment>,
+    // where position independent executables are loaded, which is added to the addresses in DWARF
+    load_bias: usize,
+}
+
+impl CoreFile {
+    pub fn open(core_path: &str, elf_path: &str) -> Result<Self, String> {
+        let entry = {
+            let file = fs::File::open(elf_path).map_err(|err| format!("{}: {}", elf_path, err))?;
+            let mmap = unsafe { memmap::Mmap::map(&file) }
+                .map_err(|err| format!("{}: {}", elf_path, err))?;
+            object::File::parse(&mmap)
+                .map_err(|err| format!("{}: {}", elf_path, err))?
+                .entry() as usize
+        };
+        let file = fs::File::open(core_path).map_err(|err| format!("{}: {}", core_path, err))?;
+        // empty files cannot be mapped
+        let mmap =
+            unsafe { memmap::Mmap::map(&file) }.map_err(|err| format!("{}: {}", core_path, err))?;
+        let reader = ElfReader::new(&mmap).ok_or(format!("{}: not an ELF file", core_path))?;
+        if reader.u16(0x10) != Some(ET_CORE) {
+            return Err(format!("{}: not a core file", core_path));
+        }
+
+        let mut segments = Vec::new();
+        let mut load_bias = 0;
+        for header in reader
+            .program_headers()
+            .ok_or(format!("{}: broken program headers", core_path))?
+        {
+            match header.p_type {
+                PT_LOAD => segments.push(Segment {
+                    address: header.virtual_address,
+                    size: header.memory_size,
+                    offset: header.offset,
+                    file_size: header.file_size,
+                }),
+                PT_NOTE => {
+                    if let Some(auxv_entry) = reader.auxv_entry(&header) {
+                        load_bias = auxv_entry.wrapping_sub(entry);
+                    }
+                }
+                _ => (),
+            }
+        }
+        if segments.is_empty() {
+            warn!("no PT_LOAD segments in {}", core_path);
+        }
+        Ok(Self {
+            mmap,
+            segments,
+            load_bias,
+        })
+    }
+}
+
+impl Memory for CoreFile {
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>> {
+        let address = address.wrapping_add(self.load_bias);
+        let end = address.checked_add(size)?;
+        let segment = self.segments.iter().find(|segment| {
+            segment.address <= address
+                && segment
+                    .address
+                    .checked_add(segment.size)
+                    .is_some_and(|segment_end| end <= segment_end)
+        })?;
+        let start = address - segment.address;
+        if start + size > segment.file_size {
+            return None;
+        }
+        let start = segment.offset.checked_add(start)?;
+        self.mmap
+            .get(start..start.checked_add(size)?)
+            .map(<[u8]>::to_vec)
+    }
+
+    fn load_bias(&self) -> usize {
+        self.load_bias
+    }
+}
@@ -0,0 +1,146 @@
+use log::{info, warn};
+use object::Object;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_DEBUG_DIR: &str = "/usr/lib/debug";
+
+pub struct DebugFileLocator {
+    debug_dirs: Vec<PathBuf>,
+}
+
+impl DebugFileLocator {
+    pub fn new(debug_dirs: Vec<PathBuf>) -> Self {
+        let debug_dirs = if debug_dirs.is_empty() {
+            vec![PathBuf::from(DEFAULT_DEBUG_DIR)]
+        } else {
+            debug_dirs
+        };
+        Self { debug_dirs }
+    }
+
+    // follows the same search order as gdb: build-id first, then .gnu_debuglink
+    pub fn locate(&self, elf_path: &Path, object: &object::File) -> Option<PathBuf> {
+        self.locate_by_build_id(object)
+            .or_else(|| self.locate_by_debuglink(elf_path, object))
+    }
+
+    // dwz records the supplementary object in .gnu_debugaltlink, DWARF 5 in .debug_sup
+    pub fn locate_supplementary(&self, elf_path: &Path, object: &object::File) -> Option<PathBuf> {
+        let (name, build_id) = match object.section_data_by_name(".gnu_debugaltlink") {
+            Some(data) => {
+                let (name, rest) = Self::split_c_string(&data)?;
+                (name, Some(rest.to_vec()))
+            }
+            None => {
+                let data = object.section_data_by_name(".debug_sup")?;
+                // version (2 bytes) and is_supplementary flag (1 byte) precede the file name
+                let (name, _) = Self::split_c_string(data.get(3..)?)?;
+                (name, None)
+            }
+        };
+
+        let name_path = Path::new(&name);
+        let mut candidates = Vec::new();
+        if name_path.is_absolute() {
+            candidates.push(name_path.to_path_buf());
+        } else {
+            let elf_dir = elf_path.parent().unwrap_or_else(|| Path::new(""));
+            candidates.push(elf_dir.join(name_path));
+        }
+        if let Some(file_name) = name_path.file_name() {
+            for debug_dir in &self.debug_dirs {
+                candidates.push(debug_dir.join(".dwz").join(file_name));
+            }
+        }
+
+        let path = candidates
+            .into_iter()
+            .find(|path| path.is_file())
+            .or_else(|| self.locate_by_build_id_bytes(build_id.as_ref()?));
+        if path.is_none() {
+            warn!("supplementary object file is not found: {}", name);
+        }
+        path
+    }
+
+    fn split_c_string(data: &[u8]) -> Option<(String, &[u8])> {
+        let end = data.iter().position(|byte| *byte == 0)?;
+        let name = String::from_utf8_lossy(&data[..end]).into_owned();
+        Some((name, &data[end + 1..]))
+    }
+
+    fn locate_by_build_id(&self, object: &object::File) -> Option<PathBuf> {
+        self.locate_by_build_id_bytes(object.build_id()?)
+    }
+
+    fn locate_by_build_id_bytes(&self, build_id: &[u8]) -> Option<PathBuf> {
+        if build_id.len() < 2 {
+            return None;
+        }
+        let build_id: String = build_id
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let path = self
+            .debug_dirs
+            .iter()
+            .map(|dir| {
+                dir.join(".build-id")
+                    .join(&build_id[..2])
+                    .join(format!("{}.debug", &build_id[2..]))
+            })
+            .find(|path| path.is_file());
+        if path.is_none() {
+            info!("debug file is not found by build-id: {}", build_id);
+        }
+        path
+    }
+
+    fn locate_by_debuglink(&self, elf_path: &Path, object: &object::File) -> Option<PathBuf> {
+        let (name, crc) = object.gnu_debuglink()?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        let elf_dir = elf_path.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut candidates = vec![elf_dir.join(&name), elf_dir.join(".debug").join(&name)];
+        let absolute_elf_dir = fs::canonicalize(elf_dir).ok();
+        for debug_dir in &self.debug_dirs {
+            if let Some(relative_elf_dir) = absolute_elf_dir
+                .as_ref()
+                .and_then(|dir| dir.strip_prefix("/").ok())
+            {
+                candidates.push(debug_dir.join(relative_elf_dir).join(&name));
+            }
+            candidates.push(debug_dir.join(&name));
+        }
+
+        let path = candidates
+            .into_iter()
+            .filter(|path| path.is_file())
+            .find(|path| Self::has_crc(path, crc));
+        if path.is_none() {
+            info!("debug file is not found by .gnu_debuglink: {}", name);
+        }
+        path
+    }
+
+    fn has_crc(path: &Path, crc: u32) -> bool {
+        match fs::read(path) {
+            Ok(data) => {
+                let mut hasher = crc32fast::Hasher::new();
+                hasher.update(&data);
+                let actual = hasher.finalize();
+                if actual != crc {
+                    warn!(
+                        "skip debug file whose crc does not match .gnu_debuglink: {}: expected {:#x}, actual {:#x}",
+                        path.display(),
+                        crc,
+                        actual
+                    );
+                }
+                actual == crc
+            }
+            Err(_) => false,
+        }
+    }
+}
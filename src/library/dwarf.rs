@@ -1,18 +1,57 @@
-use log::info;
+use log::{info, warn};
 use object::Object;
-use std::{borrow, fs};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::{borrow, fs, panic, thread};
+
+use super::compressed_section;
+use super::debug_file::DebugFileLocator;
+use super::dwarf_package::DwarfPackage;
+use super::dwarf_unit::{UnitHeader, UnitHeaders, UnitType};
+use super::name_index;
+
+type DwarfReader<'input> = gimli::read::EndianSlice<'input, gimli::RunTimeEndian>;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+pub enum Section {
+    DebugInfo,
+    // split DWARF unit, identified by the offset of its skeleton unit in .debug_info
+    DebugInfoDwo(usize),
+    // .debug_info of the supplementary object file made by dwz
+    DebugInfoSup,
+    DebugTypes,
+    // split DWARF type unit, identified by the offset of its skeleton unit in .debug_info
+    DebugTypesDwo(usize),
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+pub struct Offset {
+    section: Section,
+    offset: usize,
+}
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Offset(usize);
 impl Offset {
     pub fn new(size: usize) -> Offset {
-        Offset(size)
+        Offset::new_in_section(Section::DebugInfo, size)
+    }
+
+    pub fn new_in_section(section: Section, size: usize) -> Offset {
+        Offset {
+            section,
+            offset: size,
+        }
+    }
+
+    pub fn section(&self) -> Section {
+        self.section.clone()
     }
 }
 
 impl Into<usize> for Offset {
     fn into(self) -> usize {
-        self.0
+        self.offset
     }
 }
 
@@ -23,8 +62,9 @@ impl Location {
         Location(size)
     }
 
-    pub fn add(&mut self, size: usize) {
-        self.0 += size;
+    // None beyond the end of the address space
+    pub fn add(&self, size: usize) -> Option<Location> {
+        self.0.checked_add(size).map(Location)
     }
 }
 
@@ -76,11 +116,30 @@ impl From<gimli::DwTag> for DwarfTag {
     }
 }
 
+// How the values of base types are represented, from DW_AT_encoding.
+// Characters are integers, and complex or decimal numbers are not supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaseTypeEncoding {
+    Signed,
+    Unsigned,
+    Float,
+    Boolean,
+}
+
+// The compilation unit which entries belong to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilationUnit {
+    pub name: Option<String>,
+    pub comp_dir: Option<String>,
+    pub producer: Option<String>,
+}
+
+// Names are borrowed from the sections of the mmapped object file while streaming
 #[derive(Debug, PartialEq)]
-pub struct DwarfInfo {
+pub struct DwarfInfo<'input> {
     offset: Offset,
     tag: DwarfTag,
-    name: Option<String>,
+    name: Option<borrow::Cow<'input, str>>,
     type_offset: Option<Offset>,
     byte_size: Option<usize>,
     bit_size: Option<usize>,
@@ -91,10 +150,39 @@ pub struct DwarfInfo {
     data_member_location: Option<usize>,
     declaration: Option<bool>,
     specification: Option<Offset>,
-    children: Vec<DwarfInfo>,
+    external: Option<bool>,
+    encoding: Option<BaseTypeEncoding>,
+    compilation_unit: Option<Arc<CompilationUnit>>,
+    decl_file: Option<String>,
+    decl_line: Option<usize>,
+    children: Vec<DwarfInfo<'input>>,
 }
 
-impl DwarfInfo {
+impl<'input> DwarfInfo<'input> {
+    fn new(offset: Offset, tag: DwarfTag) -> Self {
+        DwarfInfo {
+            offset,
+            tag,
+            name: None,
+            type_offset: None,
+            byte_size: None,
+            bit_size: None,
+            bit_offset: None,
+            location: None,
+            upper_bound: None,
+            const_value: None,
+            data_member_location: None,
+            declaration: None,
+            specification: None,
+            external: None,
+            encoding: None,
+            compilation_unit: None,
+            decl_file: None,
+            decl_line: None,
+            children: Vec::new(),
+        }
+    }
+
     pub fn offset(&self) -> Offset {
         self.offset.clone()
     }
@@ -104,7 +192,7 @@ impl DwarfInfo {
     }
 
     pub fn name(&self) -> Option<String> {
-        self.name.clone()
+        self.name.as_ref().map(|name| name.to_string())
     }
 
     pub fn type_offset(&self) -> Option<Offset> {
@@ -147,358 +235,1290 @@ impl DwarfInfo {
         self.specification.clone()
     }
 
-    pub fn children(&self) -> &Vec<DwarfInfo> {
+    pub fn external(&self) -> Option<bool> {
+        self.external
+    }
+
+    pub fn encoding(&self) -> Option<BaseTypeEncoding> {
+        self.encoding
+    }
+
+    pub fn compilation_unit(&self) -> Option<Arc<CompilationUnit>> {
+        self.compilation_unit.clone()
+    }
+
+    pub fn decl_file(&self) -> Option<String> {
+        self.decl_file.clone()
+    }
+
+    pub fn decl_line(&self) -> Option<usize> {
+        self.decl_line
+    }
+
+    pub fn children(&self) -> &Vec<DwarfInfo<'input>> {
         &self.children
     }
+
+    // Copies the borrowed names to outlive the object file
+    pub fn into_owned(self) -> DwarfInfo<'static> {
+        DwarfInfo {
+            offset: self.offset,
+            tag: self.tag,
+            name: self.name.map(|name| borrow::Cow::Owned(name.into_owned())),
+            type_offset: self.type_offset,
+            byte_size: self.byte_size,
+            bit_size: self.bit_size,
+            bit_offset: self.bit_offset,
+            location: self.location,
+            upper_bound: self.upper_bound,
+            const_value: self.const_value,
+            data_member_location: self.data_member_location,
+            declaration: self.declaration,
+            specification: self.specification,
+            external: self.external,
+            encoding: self.encoding,
+            compilation_unit: self.compilation_unit,
+            decl_file: self.decl_file,
+            decl_line: self.decl_line,
+            children: self
+                .children
+                .into_iter()
+                .map(DwarfInfo::into_owned)
+                .collect(),
+        }
+    }
+}
+
+// gimli does not decode DW_OP_GNU_addr_index, which GCC emits for split DWARF 4
+const DW_OP_GNU_ADDR_INDEX: u8 = 0xfb;
+
+struct UnitContext<'input, 'dwarf> {
+    dwarf: &'dwarf gimli::read::Dwarf<DwarfReader<'input>>,
+    unit: &'dwarf gimli::read::Unit<DwarfReader<'input>>,
+    section: Section,
+    unit_offset: usize,
+    references: &'dwarf UnitReferences,
+    compilation_unit: Arc<CompilationUnit>,
+    source: Option<UnitSource>,
+}
+
+impl<'input, 'dwarf> UnitContext<'input, 'dwarf> {
+    fn new(
+        dwarf: &'dwarf gimli::read::Dwarf<DwarfReader<'input>>,
+        unit: &'dwarf DwarfUnit<'input>,
+        references: &'dwarf UnitReferences,
+    ) -> Self {
+        let compilation_unit = Arc::new(CompilationUnit::new(dwarf, &unit.unit));
+        let source = if references.sections.source {
+            Some(UnitSource::new(dwarf, &unit.unit, &compilation_unit))
+        } else {
+            None
+        };
+        UnitContext {
+            dwarf,
+            unit: &unit.unit,
+            section: unit.section.clone(),
+            unit_offset: unit.unit_offset,
+            references,
+            compilation_unit,
+            source,
+        }
+    }
+
+    fn offset(&self, offset: gimli::UnitOffset) -> Offset {
+        Offset::new_in_section(self.section.clone(), self.unit_offset + offset.0)
+    }
+}
+
+impl CompilationUnit {
+    fn new<'input>(
+        dwarf: &gimli::read::Dwarf<DwarfReader<'input>>,
+        unit: &gimli::read::Unit<DwarfReader<'input>>,
+    ) -> Self {
+        let to_string = |value: DwarfReader| value.to_string_lossy().into_owned();
+        let mut entries = unit.entries();
+        let producer = match entries.next_dfs() {
+            Ok(Some((_, entry))) => entry
+                .attr_value(gimli::DW_AT_producer)
+                .ok()
+                .flatten()
+                .and_then(|value| dwarf.attr_string(unit, value).ok())
+                .map(to_string),
+            _ => None,
+        };
+        CompilationUnit {
+            name: unit.name.map(to_string),
+            comp_dir: unit.comp_dir.map(to_string),
+            producer,
+        }
+    }
+
+    // The name which qualifies variables local to the unit, like `src/file.c`,
+    // relative to the compilation directory if the name is an absolute path in it
+    pub fn key(&self) -> Option<String> {
+        let name = self.name.as_ref()?;
+        let relative = self
+            .comp_dir
+            .as_ref()
+            .and_then(|comp_dir| Path::new(name).strip_prefix(comp_dir).ok());
+        match relative {
+            Some(relative) => Some(relative.to_string_lossy().into_owned()),
+            None => Some(name.clone()),
+        }
+    }
+
+    // The name joined to the compilation directory, which tells apart
+    // the units with the same key compiled in different directories
+    pub fn path(&self) -> Option<String> {
+        let name = self.name.as_ref()?;
+        match &self.comp_dir {
+            Some(comp_dir) => Some(
+                Path::new(comp_dir)
+                    .join(name)
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            None => Some(name.clone()),
+        }
+    }
+
+    // Whether a qualified name refers to the unit by its key, its path or its name in DWARF
+    pub fn is_named(&self, unit: &str) -> bool {
+        self.name.as_deref() == Some(unit)
+            || self.key().as_deref() == Some(unit)
+            || self.path().as_deref() == Some(unit)
+    }
+}
+
+// The file names in the line program header, which are indexed by DW_AT_decl_file
+struct UnitSource {
+    files: Vec<Option<String>>,
+}
+
+impl UnitSource {
+    fn new<'input>(
+        dwarf: &gimli::read::Dwarf<DwarfReader<'input>>,
+        unit: &gimli::read::Unit<DwarfReader<'input>>,
+        compilation_unit: &CompilationUnit,
+    ) -> Self {
+        let attr_string = |value| {
+            dwarf
+                .attr_string(unit, value)
+                .ok()
+                .map(|value| value.to_string_lossy().into_owned())
+        };
+        let comp_dir = compilation_unit.comp_dir.as_ref();
+
+        let files = match unit.line_program.as_ref() {
+            Some(program) => {
+                let header = program.header();
+                // file indexes start from 1 before DWARF 5
+                (0..=header.file_names().len() as u64)
+                    .map(|index| {
+                        let file = header.file(index)?;
+                        let name = attr_string(file.path_name())?;
+                        // files in the compilation directory are shown as they are
+                        match file.directory(header).and_then(attr_string) {
+                            Some(directory)
+                                if !Path::new(&name).is_absolute()
+                                    && Some(&directory) != comp_dir =>
+                            {
+                                Some(Path::new(&directory).join(name).display().to_string())
+                            }
+                            _ => Some(name),
+                        }
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        UnitSource { files }
+    }
+}
+
+// The sections which the units of a DWARF object are read from.
+// `info_base` is the offset of the contribution to .debug_info in a DWARF package.
+#[derive(Clone)]
+struct UnitSections {
+    info: Section,
+    info_base: usize,
+    types: Option<Section>,
+    // whether the compilation units and the declaration files and lines are read
+    source: bool,
+}
+
+// Resolves references which point out of the unit
+struct UnitReferences {
+    sections: UnitSections,
+    type_signatures: HashMap<u64, Offset>,
+}
+
+struct DwarfUnit<'input> {
+    unit: gimli::read::Unit<DwarfReader<'input>>,
+    section: Section,
+    unit_offset: usize,
+    dwo_id: Option<u64>,
+}
+
+struct SkeletonUnit {
+    dwo_name: String,
+    dwo_id: Option<u64>,
+    addr_base: gimli::DebugAddrBase,
+}
+
+// Streams the entries at the top level of a unit
+struct UnitInfos<'input, 'dwarf> {
+    context: UnitContext<'input, 'dwarf>,
+    entries: gimli::read::EntriesCursor<'dwarf, 'dwarf, DwarfReader<'input>>,
+}
+
+impl<'input, 'dwarf> UnitInfos<'input, 'dwarf> {
+    fn new(context: UnitContext<'input, 'dwarf>) -> Self {
+        let mut entries = context.unit.entries();
+        let _ = entries.next_entry(); // skip compilatoin unit entry
+        UnitInfos { context, entries }
+    }
+}
+
+impl<'input, 'dwarf> Iterator for UnitInfos<'input, 'dwarf> {
+    type Item = DwarfInfo<'input>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        DwarfInfoIntoIterator::next_info(&self.context, &mut self.entries)
+    }
+}
+
+// The units of an object file, where entries are looked up by offsets
+struct LookupObject<'input, 'dwarf> {
+    dwarf: &'dwarf gimli::read::Dwarf<DwarfReader<'input>>,
+    units: Vec<DwarfUnit<'input>>,
+    references: UnitReferences,
+}
+
+impl<'input, 'dwarf> LookupObject<'input, 'dwarf> {
+    fn new(dwarf: &'dwarf gimli::read::Dwarf<DwarfReader<'input>>, sections: UnitSections) -> Self {
+        let (units, references) = DwarfInfoIntoIterator::dwarf_units(dwarf, sections);
+        LookupObject {
+            dwarf,
+            units,
+            references,
+        }
+    }
 }
 
 pub struct DwarfInfoIntoIterator {
     elf_path: String,
+    debug_dirs: Vec<String>,
+    source: bool,
+    // units are read in parallel unless they are streamed in order
+    parallel: bool,
 }
 
 impl DwarfInfoIntoIterator {
     pub fn new(elf_path: String) -> DwarfInfoIntoIterator {
-        DwarfInfoIntoIterator { elf_path }
+        DwarfInfoIntoIterator {
+            elf_path,
+            debug_dirs: Vec::new(),
+            source: false,
+            parallel: true,
+        }
+    }
+
+    pub fn debug_dir<S: Into<String>>(mut self, debug_dir: S) -> Self {
+        self.debug_dirs.push(debug_dir.into());
+        self
+    }
+
+    // Reads the compilation units and the declaration files and lines of the entries,
+    // which needs the file names in .debug_line
+    pub fn source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
+    fn unit_infos_from_object<T, F>(
+        elf_path: &Path,
+        object: &object::File,
+        locator: &DebugFileLocator,
+        source: bool,
+        parallel: bool,
+        f: &F,
+    ) -> Vec<T>
+    where
+        T: Send,
+        F: for<'input> Fn(&mut dyn Iterator<Item = DwarfInfo<'input>>) -> Option<T> + Sync,
+    {
+        let endian = Self::endian(object);
+
+        // the ELF is read without the supplementary object file if it cannot be read
+        let sup_path = locator.locate_supplementary(elf_path, object);
+        let sup_mmap = sup_path.as_ref().and_then(|sup_path| {
+            info!("load supplementary object file: {}", sup_path.display());
+            Self::map_object_file(sup_path)
+        });
+        let sup_object = sup_path
+            .as_ref()
+            .zip(sup_mmap.as_ref())
+            .and_then(|(sup_path, mmap)| Self::parse_object_file(sup_path, mmap));
+        let dwarf_cow = Self::load_dwarf(object, sup_object.as_ref(), "");
+
+        // Borrow a `Cow<[u8]>` to create an `EndianSlice`.
+        let borrow_section: &dyn for<'b> Fn(
+            &'b borrow::Cow<[u8]>,
+        )
+            -> gimli::EndianSlice<'b, gimli::RunTimeEndian> =
+            &|section| gimli::EndianSlice::new(section, endian);
+
+        // Create `EndianSlice`s for all of the sections.
+        let dwarf = dwarf_cow.borrow(&borrow_section);
+
+        // A DWARF package is conventionally placed next to the executable.
+        let package_path = PathBuf::from(format!("{}.dwp", elf_path.display()));
+        let package_mmap = if package_path.is_file() {
+            Self::map_object_file(&package_path)
+        } else {
+            None
+        };
+        let package_object = package_mmap
+            .as_ref()
+            .and_then(|mmap| Self::parse_object_file(&package_path, mmap));
+        let package = package_object.as_ref().and_then(DwarfPackage::new);
+
+        // Iterate over the compilation units and type units.
+        let sections = UnitSections {
+            info: Section::DebugInfo,
+            info_base: 0,
+            types: Some(Section::DebugTypes),
+            source,
+        };
+        let (units, references) = Self::dwarf_units(&dwarf, sections);
+        // units are independent of each other, and collected in the original order
+        let unit_infos = |unit| {
+            let context = UnitContext::new(&dwarf, unit, &references);
+            match Self::get_skeleton_unit(&context, unit.dwo_id) {
+                Some(skeleton) => {
+                    Self::split_unit_infos(elf_path, &context, skeleton, package.as_ref(), f)
+                }
+                None => f(&mut UnitInfos::new(context)),
+            }
+        };
+        let mut results = match Self::collect_units(&units, parallel, unit_infos) {
+            Ok(results) => results,
+            Err(results) => return results,
+        };
+
+        if let Some(sup_object) = sup_object.as_ref() {
+            results.append(&mut Self::supplementary_infos(
+                sup_object, source, parallel, f,
+            ));
+        }
+        results
+    }
+
+    fn supplementary_infos<T, F>(
+        sup_object: &object::File,
+        source: bool,
+        parallel: bool,
+        f: &F,
+    ) -> Vec<T>
+    where
+        T: Send,
+        F: for<'input> Fn(&mut dyn Iterator<Item = DwarfInfo<'input>>) -> Option<T> + Sync,
+    {
+        let endian = Self::endian(sup_object);
+        let dwarf_cow = Self::load_dwarf(sup_object, None, "");
+        let borrow_section: &dyn for<'b> Fn(
+            &'b borrow::Cow<[u8]>,
+        )
+            -> gimli::EndianSlice<'b, gimli::RunTimeEndian> =
+            &|section| gimli::EndianSlice::new(section, endian);
+        let dwarf = dwarf_cow.borrow(&borrow_section);
+
+        let sections = UnitSections {
+            info: Section::DebugInfoSup,
+            info_base: 0,
+            types: None,
+            source,
+        };
+        let (units, references) = Self::dwarf_units(&dwarf, sections);
+        let unit_infos = |unit| {
+            f(&mut UnitInfos::new(UnitContext::new(
+                &dwarf,
+                unit,
+                &references,
+            )))
+        };
+        match Self::collect_units(&units, parallel, unit_infos) {
+            Ok(results) | Err(results) => results,
+        }
+    }
+
+    // The results of the units in order, or `Err` with the results before the unit
+    // for which `f` returns `None`, where the rest of the units are not read.
+    // In parallel the units after it may be read already, so no results are returned.
+    fn collect_units<'a, U, T, F>(units: &'a [U], parallel: bool, f: F) -> Result<Vec<T>, Vec<T>>
+    where
+        U: Sync,
+        T: Send,
+        F: Fn(&'a U) -> Option<T> + Sync,
+    {
+        if parallel {
+            return units
+                .par_iter()
+                .map(&f)
+                .collect::<Option<Vec<T>>>()
+                .ok_or_else(Vec::new);
+        }
+        let results: Vec<T> = units.iter().map_while(&f).collect();
+        if results.len() < units.len() {
+            return Err(results);
+        }
+        Ok(results)
+    }
+
+    fn split_unit_infos<T, F>(
+        elf_path: &Path,
+        skeleton_context: &UnitContext,
+        skeleton: SkeletonUnit,
+        package: Option<&DwarfPackage>,
+        f: &F,
+    ) -> T
+    where
+        F: for<'input> Fn(&mut dyn Iterator<Item = DwarfInfo<'input>>) -> T,
+    {
+        let mut sections = UnitSections {
+            info: Section::DebugInfoDwo(skeleton_context.unit_offset),
+            info_base: 0,
+            types: Some(Section::DebugTypesDwo(skeleton_context.unit_offset)),
+            source: skeleton_context.references.sections.source,
+        };
+        if let (Some(package), Some(dwo_id)) = (package, skeleton.dwo_id) {
+            if let Some((mut dwarf, base)) = package.split_dwarf(dwo_id) {
+                // indexed addresses of split units live in .debug_addr of the executable
+                dwarf.debug_addr = skeleton_context.dwarf.debug_addr;
+                sections.info_base = base;
+                return Self::split_dwarf_infos(&dwarf, sections, skeleton.addr_base, f);
+            }
+        }
+
+        let comp_dir = skeleton_context
+            .unit
+            .comp_dir
+            .map(|dir| dir.to_string_lossy().into_owned());
+        let dwo_path = match Self::find_dwo_file(elf_path, comp_dir, &skeleton.dwo_name) {
+            Some(dwo_path) => dwo_path,
+            None => {
+                warn!(
+                    "split DWARF object is not found: {}: skeleton offset = {:#x}",
+                    skeleton.dwo_name, skeleton_context.unit_offset
+                );
+                return f(&mut std::iter::empty());
+            }
+        };
+        info!("load split DWARF object: {}", dwo_path.display());
+        let mmap = match Self::map_object_file(&dwo_path) {
+            Some(mmap) => mmap,
+            None => return f(&mut std::iter::empty()),
+        };
+        let object = match Self::parse_object_file(&dwo_path, &mmap) {
+            Some(object) => object,
+            None => return f(&mut std::iter::empty()),
+        };
+        let endian = Self::endian(&object);
+        let dwarf_cow = Self::load_dwarf(&object, None, ".dwo");
+        let borrow_section: &dyn for<'b> Fn(
+            &'b borrow::Cow<[u8]>,
+        )
+            -> gimli::EndianSlice<'b, gimli::RunTimeEndian> =
+            &|section| gimli::EndianSlice::new(section, endian);
+        let mut dwarf = dwarf_cow.borrow(&borrow_section);
+        // indexed addresses of split units live in .debug_addr of the executable
+        dwarf.debug_addr = skeleton_context.dwarf.debug_addr;
+        Self::split_dwarf_infos(&dwarf, sections, skeleton.addr_base, f)
+    }
+
+    fn split_dwarf_infos<T, F>(
+        dwarf: &gimli::read::Dwarf<DwarfReader>,
+        sections: UnitSections,
+        addr_base: gimli::DebugAddrBase,
+        f: &F,
+    ) -> T
+    where
+        F: for<'input> Fn(&mut dyn Iterator<Item = DwarfInfo<'input>>) -> T,
+    {
+        let (mut units, references) = Self::dwarf_units(dwarf, sections);
+        for unit in units.iter_mut() {
+            unit.unit.addr_base = addr_base;
+        }
+        let mut infos = units
+            .iter()
+            .flat_map(|unit| UnitInfos::new(UnitContext::new(dwarf, unit, &references)));
+        f(&mut infos)
+    }
+
+    // Reads the units in .debug_info followed by the type units in .debug_types,
+    // and collects the signatures of type units to resolve DW_FORM_ref_sig8.
+    fn dwarf_units<'input>(
+        dwarf: &gimli::read::Dwarf<DwarfReader<'input>>,
+        sections: UnitSections,
+    ) -> (Vec<DwarfUnit<'input>>, UnitReferences) {
+        let mut units = Vec::new();
+        let mut type_signatures = HashMap::new();
+
+        for header in UnitHeaders::new(&dwarf.debug_info) {
+            let UnitHeader { header, unit_type } = header.unwrap();
+            let unit_offset = sections.info_base + header.offset().0;
+            let dwo_id = match unit_type {
+                UnitType::Compilation { dwo_id } => dwo_id,
+                UnitType::Type {
+                    signature,
+                    type_offset,
+                } => {
+                    let offset =
+                        Offset::new_in_section(sections.info.clone(), unit_offset + type_offset.0);
+                    type_signatures.insert(signature, offset);
+                    None
+                }
+            };
+            units.push(DwarfUnit {
+                unit: dwarf.unit(header).unwrap(),
+                section: sections.info.clone(),
+                unit_offset,
+                dwo_id,
+            });
+        }
+
+        if let Some(types) = sections.types.as_ref() {
+            let mut headers = dwarf.type_units();
+            while let Some(header) = headers.next().unwrap() {
+                let unit_offset = header.offset().0;
+                let offset =
+                    Offset::new_in_section(types.clone(), unit_offset + header.type_offset().0);
+                type_signatures.insert(header.type_signature().0, offset);
+                units.push(DwarfUnit {
+                    unit: dwarf.type_unit(header).unwrap(),
+                    section: types.clone(),
+                    unit_offset,
+                    dwo_id: None,
+                });
+            }
+        }
+
+        let references = UnitReferences {
+            sections,
+            type_signatures,
+        };
+        (units, references)
+    }
+
+    fn find_dwo_file(elf_path: &Path, comp_dir: Option<String>, dwo_name: &str) -> Option<PathBuf> {
+        let dwo_path = Path::new(dwo_name);
+        let mut candidates = match comp_dir {
+            Some(comp_dir) => vec![Path::new(&comp_dir).join(dwo_path)],
+            None => vec![dwo_path.to_path_buf()],
+        };
+        // binaries are often moved away from the directory they are built in
+        if let (Some(elf_dir), Some(dwo_file_name)) = (elf_path.parent(), dwo_path.file_name()) {
+            candidates.push(elf_dir.join(dwo_file_name));
+        }
+        candidates.into_iter().find(|path| path.is_file())
     }
 
     fn next_info<'input, 'abbrev, 'unit>(
-        header: &gimli::CompilationUnitHeader<
-            gimli::read::EndianSlice<'input, gimli::RunTimeEndian>,
-        >,
-        dwarf: &gimli::read::Dwarf<gimli::read::EndianSlice<'input, gimli::RunTimeEndian>>,
-        encoding: gimli::Encoding,
-        entries: &mut gimli::read::EntriesCursor<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<DwarfInfo> {
+        context: &UnitContext<'input, '_>,
+        entries: &mut gimli::read::EntriesCursor<'abbrev, 'unit, DwarfReader<'input>>,
+    ) -> Option<DwarfInfo<'input>> {
         let _ = entries.next_entry();
-        match entries.current() {
-            None => None,
-            Some(entry) => {
-                let offset = Self::get_offset(header, entry);
-                let tag = DwarfTag::from(entry.tag());
-                let name = Self::get_name(dwarf, entry);
-                let type_offset = Self::get_type_offset(header, entry);
-                let byte_size = Self::get_byte_size(entry);
-                let bit_size = Self::get_bit_size(entry);
-                let bit_offset = Self::get_bit_offset(entry);
-                let location = Self::get_location(header, encoding, entry);
-                let upper_bound = Self::get_upper_bound(entry);
-                let const_value = Self::get_const_value(entry);
-                let data_member_location = Self::get_data_member_location(entry);
-                let declaration = Self::get_declaration(entry);
-                let specification = Self::get_specification(header, entry);
-
-                let mut children = Vec::new();
-                if entry.has_children() {
-                    while let Some(info) = Self::next_info(header, dwarf, encoding, entries) {
-                        children.push(info);
+        let entry = entries.current()?;
+        let mut info = DwarfInfo::new(context.offset(entry.offset()), entry.tag().into());
+        let mut data_bit_offset = None;
+        // read only the needed attributes in a single pass
+        let mut attrs = entry.attrs();
+        while let Some(attr) = attrs.next().unwrap() {
+            let value = attr.value();
+            match attr.name() {
+                gimli::DW_AT_name => info.name = Self::get_name(context, value),
+                gimli::DW_AT_type => info.type_offset = Self::get_reference(context, value),
+                gimli::DW_AT_byte_size => info.byte_size = Self::get_udata(value),
+                gimli::DW_AT_bit_size => info.bit_size = Self::get_udata(value),
+                gimli::DW_AT_bit_offset => info.bit_offset = Self::get_udata(value),
+                gimli::DW_AT_data_bit_offset => data_bit_offset = Self::get_udata(value),
+                gimli::DW_AT_location => info.location = Self::get_location(context, &info, value),
+                gimli::DW_AT_upper_bound => info.upper_bound = Self::get_udata(value),
+                gimli::DW_AT_const_value => info.const_value = Self::get_const_value(value),
+                gimli::DW_AT_data_member_location => {
+                    info.data_member_location =
+                        Self::get_data_member_location(context, &info, value)
+                }
+                gimli::DW_AT_declaration => info.declaration = Self::get_flag(value),
+                gimli::DW_AT_specification => {
+                    info.specification = Self::get_reference(context, value)
+                }
+                gimli::DW_AT_external => info.external = Self::get_flag(value),
+                gimli::DW_AT_encoding => info.encoding = Self::get_encoding(value),
+                gimli::DW_AT_decl_file => {
+                    if let Some(source) = context.source.as_ref() {
+                        info.decl_file = match value {
+                            gimli::AttributeValue::FileIndex(index) => {
+                                source.files.get(index as usize).cloned().flatten()
+                            }
+                            _ => None,
+                        };
                     }
                 }
-                Some(DwarfInfo {
-                    offset,
-                    tag,
-                    name,
-                    type_offset,
-                    byte_size,
-                    bit_size,
-                    bit_offset,
-                    location,
-                    upper_bound,
-                    const_value,
-                    data_member_location,
-                    declaration,
-                    specification,
-                    children: children,
-                })
+                gimli::DW_AT_decl_line if context.source.is_some() => {
+                    info.decl_line = Self::get_udata(value)
+                }
+                _ => (),
             }
         }
+        // variables local to the unit are told apart from the others by the unit
+        let internal_variable = info.tag == DwarfTag::DW_TAG_variable
+            && info.external.is_none()
+            && info.specification.is_none();
+        if context.source.is_some() || internal_variable {
+            info.compilation_unit = Some(context.compilation_unit.clone());
+        }
+        if info.bit_size.is_some() {
+            Self::normalize_bit_field(context, &mut info, data_bit_offset);
+        }
+
+        if entry.has_children() {
+            // children of functions and the other unsupported entries are never used
+            if info.tag == DwarfTag::DW_TAG_unimplemented {
+                Self::skip_children(entries);
+            } else {
+                while let Some(child) = Self::next_info(context, entries) {
+                    info.children.push(child);
+                }
+            }
+        }
+        Some(info)
     }
 
-    fn get_offset<'input, 'abbrev, 'unit>(
-        header: &gimli::CompilationUnitHeader<
-            gimli::read::EndianSlice<'input, gimli::RunTimeEndian>,
-        >,
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Offset {
-        Offset::new(entry.offset().to_debug_info_offset(header).0)
-    }
-
-    fn get_name<'input, 'abbrev, 'unit>(
-        dwarf: &gimli::read::Dwarf<gimli::read::EndianSlice<'input, gimli::RunTimeEndian>>,
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<String> {
-        entry
-            .attr_value(gimli::DW_AT_name)
-            .unwrap()
-            .and_then(|value| value.string_value(&dwarf.debug_str))
-            .map(|r| r.to_string().unwrap())
-            .map(String::from)
-    }
-
-    fn get_type_offset<'input, 'abbrev, 'unit>(
-        header: &gimli::CompilationUnitHeader<
-            gimli::read::EndianSlice<'input, gimli::RunTimeEndian>,
-        >,
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<Offset> {
-        if let Some(gimli::read::AttributeValue::UnitRef(offset)) =
-            entry.attr_value(gimli::DW_AT_type).unwrap()
-        {
-            Some(Offset::new(offset.to_debug_info_offset(header).0))
+    // Normalizes DW_AT_bit_offset and DW_AT_data_bit_offset of bit fields
+    // to the byte containing the first bit in memory and the bit offset from the LSB of
+    // the `(bit_offset + bit_size).div_ceil(8)` bytes there, which are the only bytes
+    // the bit field occupies and are read as an integer in the endianness of the object.
+    // The bit offset is less than 8 in both endiannesses.
+    fn normalize_bit_field(
+        context: &UnitContext,
+        info: &mut DwarfInfo,
+        data_bit_offset: Option<usize>,
+    ) {
+        use gimli::{Endianity, Reader, Section};
+
+        let bit_size = info.bit_size.unwrap_or(0);
+        let big_endian = context.dwarf.debug_info.reader().endian().is_big_endian();
+        let location = info.data_member_location.unwrap_or(0);
+        // the offset of the first bit in memory order
+        let offset = match (data_bit_offset, info.bit_offset, info.byte_size) {
+            (Some(data_bit_offset), _, _) => Some(location * 8 + data_bit_offset),
+            // DW_AT_bit_offset is counted from the MSB of the storage unit
+            (None, Some(bit_offset), _) if big_endian => Some(location * 8 + bit_offset),
+            (None, Some(bit_offset), Some(byte_size)) => {
+                ((location + byte_size) * 8).checked_sub(bit_offset + bit_size)
+            }
+            _ => None,
+        };
+        let offset = match offset {
+            Some(offset) => offset,
+            None => {
+                let offset: usize = info.offset().into();
+                warn!(
+                    "failed to get the bit offset of the bit field: offset = {:#x}",
+                    offset
+                );
+                info.bit_offset = None;
+                return;
+            }
+        };
+
+        let bit_offset = offset % 8;
+        let bytes = (bit_offset + bit_size).div_ceil(8);
+        info.data_member_location = Some(offset / 8);
+        info.bit_offset = Some(if big_endian {
+            bytes * 8 - bit_offset - bit_size
         } else {
-            None
+            bit_offset
+        });
+    }
+
+    // Moves the cursor to the null entry terminating the children of the current entry
+    fn skip_children<'input, 'abbrev, 'unit>(
+        entries: &mut gimli::read::EntriesCursor<'abbrev, 'unit, DwarfReader<'input>>,
+    ) {
+        let mut depth = 1;
+        while depth > 0 {
+            if entries.next_entry().unwrap().is_none() {
+                break;
+            }
+            match entries.current() {
+                Some(entry) if entry.has_children() => depth += 1,
+                Some(_) => (),
+                None => depth -= 1,
+            }
         }
     }
 
-    fn get_byte_size<'abbrev, 'unit>(
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<usize> {
-        entry
-            .attr_value(gimli::DW_AT_byte_size)
+    fn get_skeleton_unit(context: &UnitContext, dwo_id: Option<u64>) -> Option<SkeletonUnit> {
+        let mut entries = context.unit.entries();
+        let _ = entries.next_entry();
+        let entry = entries.current()?;
+        let dwo_name = entry
+            .attr_value(gimli::DW_AT_GNU_dwo_name)
+            .unwrap()
+            .or_else(|| entry.attr_value(gimli::DW_AT_dwo_name).unwrap())
+            .and_then(|value| context.dwarf.attr_string(context.unit, value).ok())
+            .map(|r| r.to_string_lossy().into_owned())?;
+        // DWARF 5 puts the DWO id in the unit header
+        let dwo_id = entry
+            .attr_value(gimli::DW_AT_GNU_dwo_id)
             .unwrap()
             .and_then(|value| value.udata_value())
-            .map(|byte_size| byte_size as usize)
+            .or(dwo_id);
+        let addr_base = match entry.attr_value(gimli::DW_AT_GNU_addr_base).unwrap() {
+            Some(gimli::read::AttributeValue::SecOffset(base)) => gimli::DebugAddrBase(base),
+            _ => context.unit.addr_base,
+        };
+        Some(SkeletonUnit {
+            dwo_name,
+            dwo_id,
+            addr_base,
+        })
     }
 
-    fn get_bit_size<'abbrev, 'unit>(
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<usize> {
-        entry
-            .attr_value(gimli::DW_AT_bit_size)
-            .unwrap()
-            .and_then(|value| value.udata_value())
-            .map(|byte_size| byte_size as usize)
+    fn get_name<'input>(
+        context: &UnitContext<'input, '_>,
+        value: gimli::read::AttributeValue<DwarfReader<'input>>,
+    ) -> Option<borrow::Cow<'input, str>> {
+        context
+            .dwarf
+            .attr_string(context.unit, value)
+            .ok()
+            .map(|name| name.to_string_lossy())
     }
 
-    fn get_bit_offset<'abbrev, 'unit>(
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<usize> {
-        entry
-            .attr_value(gimli::DW_AT_bit_offset)
-            .unwrap()
-            .and_then(|value| value.udata_value())
-            .map(|byte_size| byte_size as usize)
-    }
-
-    fn get_location<'input, 'abbrev, 'unit>(
-        header: &gimli::CompilationUnitHeader<
-            gimli::read::EndianSlice<'input, gimli::RunTimeEndian>,
-        >,
-        encoding: gimli::Encoding,
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
+    fn get_reference<'input>(
+        context: &UnitContext<'input, '_>,
+        value: gimli::read::AttributeValue<DwarfReader<'input>>,
+    ) -> Option<Offset> {
+        match value {
+            gimli::read::AttributeValue::UnitRef(offset) => Some(context.offset(offset)),
+            gimli::read::AttributeValue::DebugInfoRef(offset) => Some(Offset::new_in_section(
+                context.references.sections.info.clone(),
+                context.references.sections.info_base + offset.0,
+            )),
+            gimli::read::AttributeValue::DebugTypesRef(signature) => {
+                let offset = context
+                    .references
+                    .type_signatures
+                    .get(&signature.0)
+                    .cloned();
+                if offset.is_none() {
+                    warn!("type unit is not found: signature = {:#x}", signature.0);
+                }
+                offset
+            }
+            gimli::read::AttributeValue::DebugInfoRefSup(offset) => {
+                Some(Offset::new_in_section(Section::DebugInfoSup, offset.0))
+            }
+            _ => None,
+        }
+    }
+
+    fn get_udata(value: gimli::read::AttributeValue<DwarfReader>) -> Option<usize> {
+        value.udata_value().map(|udata| udata as usize)
+    }
+
+    fn get_location<'input>(
+        context: &UnitContext<'input, '_>,
+        info: &DwarfInfo,
+        location: gimli::read::AttributeValue<DwarfReader<'input>>,
     ) -> Option<Location> {
         // TODO: always should get location
         // Currently not because handling RequiresFrameBase from Evaluation is needed
-        match DwarfTag::from(entry.tag()) {
-            DwarfTag::DW_TAG_variable => entry
-                .attr_value(gimli::DW_AT_location)
-                .unwrap()
-                .and_then(|location| {
-                    let mut eval = match location.exprloc_value() {
-                        Some(value) => Some(value.evaluation(encoding)),
-                        None => {
-                            info!("location attribute  which is not exprloc is not supported yet: offset = {:#x}", entry.offset().to_debug_info_offset(header).0);
-                            None
-                        }
-                    }?;
-                    let mut result = eval.evaluate().unwrap();
-                    while result != gimli::EvaluationResult::Complete {
-                        match result {
-                            gimli::EvaluationResult::RequiresRelocatedAddress(address) => {
-                                result = eval.resume_with_relocated_address(address).unwrap()
-                            }
-                            result => {
-                                info!("Evaluation requires more information: {:?}", result);
-                                return None
-                            }
-                        }
-                    }
+        if info.tag != DwarfTag::DW_TAG_variable {
+            return None;
+        }
+        let expression = match location.exprloc_value() {
+            Some(value) => Some(value),
+            None => {
+                let offset: usize = info.offset().into();
+                info!(
+                    "location attribute  which is not exprloc is not supported yet: offset = {:#x}",
+                    offset
+                );
+                None
+            }
+        }?;
+        if let Some(address) = Self::get_gnu_indexed_address(context, &expression) {
+            return Some(Location::new(address as usize));
+        }
+        let mut eval = expression.evaluation(context.unit.encoding());
+        let mut result = eval.evaluate().unwrap();
+        while result != gimli::EvaluationResult::Complete {
+            match result {
+                gimli::EvaluationResult::RequiresRelocatedAddress(address) => {
+                    result = eval.resume_with_relocated_address(address).unwrap()
+                }
+                gimli::EvaluationResult::RequiresIndexedAddress { index, .. } => {
+                    let address = context.dwarf.address(context.unit, index).unwrap();
+                    result = eval.resume_with_indexed_address(address).unwrap()
+                }
+                result => {
+                    info!("Evaluation requires more information: {:?}", result);
+                    return None;
+                }
+            }
+        }
 
-                    let result = eval.result();
-                    if let Some(gimli::Location::Address { address }) =
-                        result.get(0).map(|piece| piece.location)
-                    {
-                        Some(address)
-                    } else {
-                        info!(
-                            "The head of Evaluation result is not address: results is {:?}",
-                            result
-                        );
-                        None
-                    }
-                })
-                .map(|size| Location::new(size as usize)),
-            _ => None,
+        let result = eval.result();
+        if let Some(gimli::Location::Address { address }) =
+            result.get(0).map(|piece| piece.location)
+        {
+            Some(Location::new(address as usize))
+        } else {
+            info!(
+                "The head of Evaluation result is not address: results is {:?}",
+                result
+            );
+            None
         }
     }
 
-    fn get_upper_bound<'abbrev, 'unit>(
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<usize> {
-        entry
-            .attr_value(gimli::DW_AT_upper_bound)
-            .unwrap()
-            .and_then(|value| value.udata_value())
-            .map(|byte_size| byte_size as usize)
-    }
-
-    fn get_const_value<'abbrev, 'unit>(
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<isize> {
-        entry
-            .attr_value(gimli::DW_AT_const_value)
-            .unwrap()
-            .and_then(|value| value.sdata_value())
-            .map(|const_value| const_value as isize)
+    fn get_gnu_indexed_address<'input>(
+        context: &UnitContext<'input, '_>,
+        expression: &gimli::Expression<DwarfReader<'input>>,
+    ) -> Option<u64> {
+        use gimli::Reader;
+
+        let mut bytes = expression.0;
+        if bytes.read_u8().ok()? != DW_OP_GNU_ADDR_INDEX {
+            return None;
+        }
+        let index = bytes.read_uleb128().ok()? as usize;
+        context
+            .dwarf
+            .address(context.unit, gimli::DebugAddrIndex(index))
+            .ok()
     }
 
-    fn get_data_member_location<'abbrev, 'unit>(
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
+    fn get_const_value(value: gimli::read::AttributeValue<DwarfReader>) -> Option<isize> {
+        value.sdata_value().map(|const_value| const_value as isize)
+    }
+
+    // Constant forms are the offset from the beginning of the containing entity,
+    // and location expressions are evaluated with the address of the containing entity 0
+    fn get_data_member_location<'input>(
+        context: &UnitContext<'input, '_>,
+        info: &DwarfInfo,
+        value: gimli::read::AttributeValue<DwarfReader<'input>>,
     ) -> Option<usize> {
-        if let Some(gimli::read::AttributeValue::Udata(location)) =
-            entry.attr_value(gimli::DW_AT_data_member_location).unwrap()
-        {
-            Some(location as usize)
-        } else {
-            None
+        let expression = match value {
+            gimli::read::AttributeValue::Sdata(location) if location >= 0 => {
+                return Some(location as usize)
+            }
+            gimli::read::AttributeValue::Exprloc(expression) => expression,
+            value => return Self::get_udata(value),
+        };
+
+        let mut eval = expression.evaluation(context.unit.encoding());
+        eval.set_initial_value(0);
+        let result = eval.evaluate();
+        let location = match result {
+            Ok(gimli::EvaluationResult::Complete) => match eval.result().first() {
+                Some(gimli::Piece {
+                    location: gimli::Location::Address { address },
+                    ..
+                }) => Some(*address as usize),
+                _ => None,
+            },
+            _ => None,
+        };
+        if location.is_none() {
+            let offset: usize = info.offset().into();
+            info!(
+                "data_member_location which is not a simple expression is not supported: offset = {:#x}",
+                offset
+            );
         }
+        location
     }
 
-    fn get_declaration<'input, 'abbrev, 'unit>(
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<bool> {
-        if let Some(gimli::read::AttributeValue::Flag(flag)) =
-            entry.attr_value(gimli::DW_AT_declaration).unwrap()
-        {
+    fn get_flag(value: gimli::read::AttributeValue<DwarfReader>) -> Option<bool> {
+        if let gimli::read::AttributeValue::Flag(flag) = value {
             Some(flag)
         } else {
             None
         }
     }
 
-    fn get_specification<'input, 'abbrev, 'unit>(
-        header: &gimli::CompilationUnitHeader<
-            gimli::read::EndianSlice<'input, gimli::RunTimeEndian>,
-        >,
-        entry: &gimli::DebuggingInformationEntry<
-            'abbrev,
-            'unit,
-            gimli::read::EndianSlice<'abbrev, gimli::RunTimeEndian>,
-        >,
-    ) -> Option<Offset> {
-        if let Some(gimli::read::AttributeValue::UnitRef(offset)) =
-            entry.attr_value(gimli::DW_AT_specification).unwrap()
-        {
-            Some(Offset::new(offset.to_debug_info_offset(header).0))
-        } else {
-            None
+    fn get_encoding(value: gimli::read::AttributeValue<DwarfReader>) -> Option<BaseTypeEncoding> {
+        match value {
+            gimli::read::AttributeValue::Encoding(encoding) => match encoding {
+                gimli::DW_ATE_signed | gimli::DW_ATE_signed_char => Some(BaseTypeEncoding::Signed),
+                gimli::DW_ATE_unsigned | gimli::DW_ATE_unsigned_char | gimli::DW_ATE_UTF => {
+                    Some(BaseTypeEncoding::Unsigned)
+                }
+                gimli::DW_ATE_float => Some(BaseTypeEncoding::Float),
+                gimli::DW_ATE_boolean => Some(BaseTypeEncoding::Boolean),
+                _ => None,
+            },
+            _ => None,
         }
     }
-}
 
-impl IntoIterator for DwarfInfoIntoIterator {
-    type Item = DwarfInfo;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+    fn lookup_variable_in_object(
+        elf_path: &Path,
+        object: &object::File,
+        locator: &DebugFileLocator,
+        source: bool,
+        name: &str,
+    ) -> Vec<DwarfInfo<'static>> {
+        let endian = Self::endian(object);
+
+        let sup_path = locator.locate_supplementary(elf_path, object);
+        let sup_mmap = sup_path.as_ref().and_then(|sup_path| {
+            info!("load supplementary object file: {}", sup_path.display());
+            Self::map_object_file(sup_path)
+        });
+        let sup_object = sup_path
+            .as_ref()
+            .zip(sup_mmap.as_ref())
+            .and_then(|(sup_path, mmap)| Self::parse_object_file(sup_path, mmap));
+        let dwarf_cow = Self::load_dwarf(object, sup_object.as_ref(), "");
+        let sup_dwarf_cow = sup_object
+            .as_ref()
+            .map(|sup_object| Self::load_dwarf(sup_object, None, ""));
+        let borrow_section: &dyn for<'b> Fn(
+            &'b borrow::Cow<[u8]>,
+        )
+            -> gimli::EndianSlice<'b, gimli::RunTimeEndian> =
+            &|section| gimli::EndianSlice::new(section, endian);
+        let dwarf = dwarf_cow.borrow(&borrow_section);
+        let sup_dwarf = sup_dwarf_cow
+            .as_ref()
+            .map(|dwarf_cow| dwarf_cow.borrow(&borrow_section));
+
+        let sections = UnitSections {
+            info: Section::DebugInfo,
+            info_base: 0,
+            types: Some(Section::DebugTypes),
+            source,
+        };
+        let mut objects = vec![LookupObject::new(&dwarf, sections)];
+        if let Some(sup_dwarf) = sup_dwarf.as_ref() {
+            let sections = UnitSections {
+                info: Section::DebugInfoSup,
+                info_base: 0,
+                types: None,
+                source,
+            };
+            objects.push(LookupObject::new(sup_dwarf, sections));
+        }
 
-    fn into_iter(self) -> Self::IntoIter {
-        let file = fs::File::open(&self.elf_path).unwrap();
-        let mmap = unsafe { memmap::Mmap::map(&file).unwrap() };
-        let object = object::File::parse(&*mmap).unwrap();
-        let endian = if object.is_little_endian() {
+        // an index may not cover the units compiled without it
+        let units = name_index::units_by_name(object, name);
+        let mut infos = Self::scan_variables(&objects[0], units.as_ref(), name);
+        if infos.is_empty() && units.is_some() {
+            info!("{} is not found in the name index, scan all units", name);
+            infos = Self::scan_variables(&objects[0], None, name);
+        }
+
+        // collect the entries which the variables refer to transitively
+        let mut visited: HashSet<Offset> = infos.iter().map(DwarfInfo::offset).collect();
+        let mut pending: Vec<Offset> = infos.iter().flat_map(Self::references).collect();
+        while let Some(offset) = pending.pop() {
+            if !visited.insert(offset.clone()) {
+                continue;
+            }
+            match Self::info_at(&objects, &offset) {
+                Some(info) => {
+                    pending.append(&mut Self::references(&info));
+                    infos.push(info);
+                }
+                None => {
+                    let offset: usize = offset.into();
+                    warn!("referred entry is not found: offset = {:#x}", offset)
+                }
+            }
+        }
+        infos.into_iter().map(DwarfInfo::into_owned).collect()
+    }
+
+    // Scans the top level entries of the units for the variables named `name`,
+    // and the definitions which refer to their declarations.
+    fn scan_variables<'input>(
+        object: &LookupObject<'input, '_>,
+        units: Option<&Vec<usize>>,
+        name: &str,
+    ) -> Vec<DwarfInfo<'input>> {
+        object
+            .units
+            .iter()
+            .filter(|unit| unit.section == object.references.sections.info)
+            .filter(|unit| match units {
+                Some(units) => units.contains(&unit.unit_offset),
+                None => true,
+            })
+            .flat_map(|unit| {
+                let context = UnitContext::new(object.dwarf, unit, &object.references);
+                if Self::get_skeleton_unit(&context, unit.dwo_id).is_some() {
+                    info!(
+                        "split unit is not looked up: skeleton offset = {:#x}",
+                        unit.unit_offset
+                    );
+                    return Vec::new();
+                }
+                let variables: Vec<DwarfInfo> = UnitInfos::new(context)
+                    .filter(|info| info.tag == DwarfTag::DW_TAG_variable)
+                    .collect();
+                let declarations: Vec<Offset> = variables
+                    .iter()
+                    .filter(|info| info.name.as_deref() == Some(name))
+                    .map(DwarfInfo::offset)
+                    .collect();
+                variables
+                    .into_iter()
+                    .filter(|info| {
+                        info.name.as_deref() == Some(name)
+                            || matches!(&info.specification, Some(spec) if declarations.contains(spec))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn references(info: &DwarfInfo) -> Vec<Offset> {
+        let mut references: Vec<Offset> = info
+            .type_offset
+            .iter()
+            .chain(info.specification.iter())
+            .cloned()
+            .collect();
+        for child in info.children.iter() {
+            references.append(&mut Self::references(child));
+        }
+        references
+    }
+
+    // Reads the entry at the offset from the unit containing it
+    fn info_at<'input>(
+        objects: &[LookupObject<'input, '_>],
+        offset: &Offset,
+    ) -> Option<DwarfInfo<'input>> {
+        let (object, unit) = objects.iter().find_map(|object| {
+            object
+                .units
+                .iter()
+                .find(|unit| {
+                    unit.section == offset.section
+                        && unit.unit_offset <= offset.offset
+                        && offset.offset
+                            < unit.unit_offset + unit.unit.header.length_including_self()
+                })
+                .map(|unit| (object, unit))
+        })?;
+        let context = UnitContext::new(object.dwarf, unit, &object.references);
+        let mut entries = unit
+            .unit
+            .entries_at_offset(gimli::UnitOffset(offset.offset - unit.unit_offset))
+            .ok()?;
+        Self::next_info(&context, &mut entries)
+    }
+
+    // Maps an object file which the ELF refers to, which is skipped if it cannot be read
+    fn map_object_file(path: &Path) -> Option<memmap::Mmap> {
+        match fs::File::open(path).and_then(|file| unsafe { memmap::Mmap::map(&file) }) {
+            Ok(mmap) => Some(mmap),
+            Err(error) => {
+                warn!("object file is not loaded: {}: {}", path.display(), error);
+                None
+            }
+        }
+    }
+
+    fn parse_object_file<'data>(
+        path: &Path,
+        mmap: &'data memmap::Mmap,
+    ) -> Option<object::File<'data>> {
+        match object::File::parse(&mmap[..]) {
+            Ok(object) => Some(object),
+            Err(error) => {
+                warn!("object file is not loaded: {}: {}", path.display(), error);
+                None
+            }
+        }
+    }
+
+    fn endian(object: &object::File) -> gimli::RunTimeEndian {
+        if object.is_little_endian() {
             gimli::RunTimeEndian::Little
         } else {
             gimli::RunTimeEndian::Big
-        };
+        }
+    }
 
+    fn load_dwarf<'data>(
+        object: &object::File<'data>,
+        sup_object: Option<&object::File<'data>>,
+        suffix: &str,
+    ) -> gimli::Dwarf<borrow::Cow<'data, [u8]>> {
         let load_section = |id: gimli::SectionId| -> Result<borrow::Cow<[u8]>, gimli::Error> {
-            Ok(object
-                .section_data_by_name(id.name())
+            Ok(
+                compressed_section::section_data(object, &format!("{}{}", id.name(), suffix))
+                    .unwrap_or(borrow::Cow::Borrowed(&[][..])),
+            )
+        };
+        // Load a supplementary section, which is empty without a supplementary object file.
+        let load_section_sup = |id: gimli::SectionId| -> Result<borrow::Cow<[u8]>, gimli::Error> {
+            Ok(sup_object
+                .and_then(|sup_object| compressed_section::section_data(sup_object, id.name()))
                 .unwrap_or(borrow::Cow::Borrowed(&[][..])))
         };
-        // Load a supplementary section. We don't have a supplementary object file,
-        // so always return an empty slice.
-        let load_section_sup = |_| Ok(borrow::Cow::Borrowed(&[][..]));
 
         // Load all of the sections.
-        let dwarf_cow = gimli::Dwarf::load(&load_section, &load_section_sup).unwrap();
+        gimli::Dwarf::load(&load_section, &load_section_sup).unwrap()
+    }
+}
 
-        // Borrow a `Cow<[u8]>` to create an `EndianSlice`.
-        let borrow_section: &dyn for<'b> Fn(
-            &'b borrow::Cow<[u8]>,
-        )
-            -> gimli::EndianSlice<'b, gimli::RunTimeEndian> =
-            &|section| gimli::EndianSlice::new(&*section, endian);
+impl DwarfInfoIntoIterator {
+    // Passes the infos of each unit to `f` in parallel, and returns the results in order.
+    // The infos are read lazily and borrow the names from the mmapped object files,
+    // so only the entries being processed are kept in memory.
+    // A skeleton unit is passed with its split units.
+    pub fn map_units<T, F>(self, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: for<'input> Fn(&mut dyn Iterator<Item = DwarfInfo<'input>>) -> T + Sync,
+    {
+        self.map_units_while(|infos: &mut dyn Iterator<Item = DwarfInfo>| Some(f(infos)))
+    }
 
-        // Create `EndianSlice`s for all of the sections.
-        let dwarf = dwarf_cow.borrow(&borrow_section);
+    // Like `map_units`, but stops reading the rest of the units once `f` returns `None`
+    fn map_units_while<T, F>(self, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: for<'input> Fn(&mut dyn Iterator<Item = DwarfInfo<'input>>) -> Option<T> + Sync,
+    {
+        let (source, parallel) = (self.source, self.parallel);
+        self.with_debug_object(|elf_path, object, locator| {
+            Self::unit_infos_from_object(elf_path, object, locator, source, parallel, &f)
+        })
+    }
 
-        // Iterate over the compilation units.
-        let mut units = dwarf.units();
-        let mut infos = Vec::new();
-        while let Some(header) = units.next().unwrap() {
-            let unit = dwarf.unit(header).unwrap();
-            let mut entries = unit.entries();
-            let _ = entries.next_entry(); // skip compilatoin unit entry
-            while let Some(info) = Self::next_info(&header, &dwarf, unit.encoding(), &mut entries) {
-                infos.push(info);
+    // Finds the variables named `name` and the entries which their types refer to,
+    // reading only the units which define them instead of extracting all units.
+    // Split units are not looked up, so nothing is found for them.
+    pub fn lookup_variable(&self, name: &str) -> Vec<DwarfInfo<'static>> {
+        let source = self.source;
+        self.with_debug_object(|elf_path, object, locator| {
+            Self::lookup_variable_in_object(elf_path, object, locator, source, name)
+        })
+    }
+
+    // Whether the ELF or its debug file has a name index, where variables which
+    // are not looked up are not defined except in split units
+    pub fn has_name_index(&self) -> bool {
+        self.with_debug_object(|_, object, _| name_index::has_name_index(object))
+    }
+
+    fn with_debug_object<T>(
+        &self,
+        f: impl FnOnce(&Path, &object::File, &DebugFileLocator) -> T,
+    ) -> T {
+        let elf_path = Path::new(&self.elf_path);
+        let file = fs::File::open(elf_path).unwrap();
+        let mmap = unsafe { memmap::Mmap::map(&file).unwrap() };
+        let object = object::File::parse(&mmap).unwrap();
+        let debug_dirs = self.debug_dirs.iter().map(PathBuf::from).collect();
+        let locator = DebugFileLocator::new(debug_dirs);
+
+        // stripped executables refer to a separate debug file
+        if object.section_by_name(".debug_info").is_none() {
+            if let Some(debug_path) = locator.locate(elf_path, &object) {
+                info!("load separate debug file: {}", debug_path.display());
+                let mmap = Self::map_object_file(&debug_path);
+                let debug_object = mmap
+                    .as_ref()
+                    .and_then(|mmap| Self::parse_object_file(&debug_path, mmap));
+                if let Some(debug_object) = debug_object {
+                    return f(elf_path, &debug_object, &locator);
+                }
             }
         }
 
-        infos.into_iter()
+        f(elf_path, &object, &locator)
+    }
+
+    // Returns the infos grouped by units, which can be processed in parallel.
+    // A skeleton unit is grouped with its split units.
+    pub fn into_units(self) -> Vec<Vec<DwarfInfo<'static>>> {
+        self.map_units(|infos| infos.map(DwarfInfo::into_owned).collect())
+    }
+}
+
+// The number of units read ahead of the iteration
+const UNITS_READ_AHEAD: usize = 4;
+
+impl IntoIterator for DwarfInfoIntoIterator {
+    type Item = DwarfInfo<'static>;
+    type IntoIter = DwarfInfoIter;
+
+    // Streams the entries of the units in order, read by a thread only a few units ahead
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.parallel = false;
+        let (sender, receiver) = mpsc::sync_channel(UNITS_READ_AHEAD);
+        let reader = thread::spawn(move || {
+            // the rest is not read once the iteration is dropped
+            self.map_units_while(|infos| {
+                sender
+                    .send(infos.map(DwarfInfo::into_owned).collect::<Vec<_>>())
+                    .ok()
+            });
+        });
+        DwarfInfoIter {
+            units: receiver.into_iter(),
+            infos: Vec::new().into_iter(),
+            reader: Some(reader),
+        }
+    }
+}
+
+pub struct DwarfInfoIter {
+    units: mpsc::IntoIter<Vec<DwarfInfo<'static>>>,
+    infos: std::vec::IntoIter<DwarfInfo<'static>>,
+    reader: Option<thread::JoinHandle<()>>,
+}
+
+impl Iterator for DwarfInfoIter {
+    type Item = DwarfInfo<'static>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(info) = self.infos.next() {
+                return Some(info);
+            }
+            match self.units.next() {
+                Some(infos) => self.infos = infos.into_iter(),
+                None => {
+                    // panics of the reader, like unreadable files, are the iteration's
+                    if let Some(Err(payload)) = self.reader.take().map(thread::JoinHandle::join) {
+                        panic::resume_unwind(payload);
+                    }
+                    return None;
+                }
+            }
+        }
     }
 }
 
-pub struct DwarfInfoBuilder<OffsetP, TagP> {
+pub struct DwarfInfoBuilder<'input, OffsetP, TagP> {
     offset: OffsetP,
     tag: TagP,
-    name: Option<String>,
+    name: Option<borrow::Cow<'input, str>>,
     type_offset: Option<Offset>,
     byte_size: Option<usize>,
     bit_size: Option<usize>,
@@ -509,10 +1529,15 @@ pub struct DwarfInfoBuilder<OffsetP, TagP> {
     data_member_location: Option<usize>,
     declaration: Option<bool>,
     specification: Option<Offset>,
-    children: Vec<DwarfInfo>,
+    external: Option<bool>,
+    encoding: Option<BaseTypeEncoding>,
+    compilation_unit: Option<Arc<CompilationUnit>>,
+    decl_file: Option<String>,
+    decl_line: Option<usize>,
+    children: Vec<DwarfInfo<'input>>,
 }
 
-impl DwarfInfoBuilder<(), ()> {
+impl<'input> DwarfInfoBuilder<'input, (), ()> {
     pub fn new() -> Self {
         DwarfInfoBuilder {
             offset: (),
@@ -528,13 +1553,18 @@ impl DwarfInfoBuilder<(), ()> {
             data_member_location: None,
             declaration: None,
             specification: None,
+            external: None,
+            encoding: None,
+            compilation_unit: None,
+            decl_file: None,
+            decl_line: None,
             children: Vec::new(),
         }
     }
 }
 
-impl DwarfInfoBuilder<Offset, DwarfTag> {
-    pub fn build(self) -> DwarfInfo {
+impl<'input> DwarfInfoBuilder<'input, Offset, DwarfTag> {
+    pub fn build(self) -> DwarfInfo<'input> {
         DwarfInfo {
             offset: self.offset,
             tag: self.tag,
@@ -549,13 +1579,18 @@ impl DwarfInfoBuilder<Offset, DwarfTag> {
             data_member_location: self.data_member_location,
             declaration: self.declaration,
             specification: self.specification,
+            external: self.external,
+            encoding: self.encoding,
+            compilation_unit: self.compilation_unit,
+            decl_file: self.decl_file,
+            decl_line: self.decl_line,
             children: self.children,
         }
     }
 }
 
-impl<OffsetP> DwarfInfoBuilder<OffsetP, ()> {
-    pub fn tag(self, tag: DwarfTag) -> DwarfInfoBuilder<OffsetP, DwarfTag> {
+impl<'input, OffsetP> DwarfInfoBuilder<'input, OffsetP, ()> {
+    pub fn tag(self, tag: DwarfTag) -> DwarfInfoBuilder<'input, OffsetP, DwarfTag> {
         DwarfInfoBuilder {
             offset: self.offset,
             tag: tag,
@@ -570,13 +1605,18 @@ impl<OffsetP> DwarfInfoBuilder<OffsetP, ()> {
             data_member_location: self.data_member_location,
             declaration: self.declaration,
             specification: self.specification,
+            external: self.external,
+            encoding: self.encoding,
+            compilation_unit: self.compilation_unit,
+            decl_file: self.decl_file,
+            decl_line: self.decl_line,
             children: self.children,
         }
     }
 }
 
-impl<TagP> DwarfInfoBuilder<(), TagP> {
-    pub fn offset(self, offset: Offset) -> DwarfInfoBuilder<Offset, TagP> {
+impl<'input, TagP> DwarfInfoBuilder<'input, (), TagP> {
+    pub fn offset(self, offset: Offset) -> DwarfInfoBuilder<'input, Offset, TagP> {
         DwarfInfoBuilder {
             offset: offset,
             tag: self.tag,
@@ -591,13 +1631,18 @@ impl<TagP> DwarfInfoBuilder<(), TagP> {
             data_member_location: self.data_member_location,
             declaration: self.declaration,
             specification: self.specification,
+            external: self.external,
+            encoding: self.encoding,
+            compilation_unit: self.compilation_unit,
+            decl_file: self.decl_file,
+            decl_line: self.decl_line,
             children: self.children,
         }
     }
 }
 
-impl<OffsetP, TagP> DwarfInfoBuilder<OffsetP, TagP> {
-    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+impl<'input, OffsetP, TagP> DwarfInfoBuilder<'input, OffsetP, TagP> {
+    pub fn name<S: Into<borrow::Cow<'input, str>>>(mut self, name: S) -> Self {
         self.name = Some(name.into());
         self
     }
@@ -652,7 +1697,32 @@ impl<OffsetP, TagP> DwarfInfoBuilder<OffsetP, TagP> {
         self
     }
 
-    pub fn children(mut self, children: Vec<DwarfInfo>) -> Self {
+    pub fn external(mut self, external: bool) -> Self {
+        self.external = Some(external);
+        self
+    }
+
+    pub fn encoding(mut self, encoding: BaseTypeEncoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    pub fn compilation_unit(mut self, compilation_unit: Arc<CompilationUnit>) -> Self {
+        self.compilation_unit = Some(compilation_unit);
+        self
+    }
+
+    pub fn decl_file<S: Into<String>>(mut self, decl_file: S) -> Self {
+        self.decl_file = Some(decl_file.into());
+        self
+    }
+
+    pub fn decl_line(mut self, decl_line: usize) -> Self {
+        self.decl_line = Some(decl_line);
+        self
+    }
+
+    pub fn children(mut self, children: Vec<DwarfInfo<'input>>) -> Self {
         self.children = children;
         self
     }
@@ -0,0 +1,176 @@
+use gimli::Reader;
+use log::warn;
+use object::Object;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use super::compressed_section;
+
+const PACKAGE_SECTIONS: [gimli::SectionId; 11] = [
+    gimli::SectionId::DebugAbbrev,
+    gimli::SectionId::DebugInfo,
+    gimli::SectionId::DebugLine,
+    gimli::SectionId::DebugLoc,
+    gimli::SectionId::DebugLocLists,
+    gimli::SectionId::DebugMacinfo,
+    gimli::SectionId::DebugMacro,
+    gimli::SectionId::DebugRngLists,
+    gimli::SectionId::DebugStr,
+    gimli::SectionId::DebugStrOffsets,
+    gimli::SectionId::DebugTypes,
+];
+
+struct Contribution {
+    section: gimli::SectionId,
+    offset: usize,
+    size: usize,
+}
+
+// A DWARF package (.dwp) holding the split units of many .dwo files.
+// Each unit owns a contribution to every section, indexed by .debug_cu_index.
+pub struct DwarfPackage<'data> {
+    endian: gimli::RunTimeEndian,
+    sections: HashMap<gimli::SectionId, Cow<'data, [u8]>>,
+    units: HashMap<u64, Vec<Contribution>>,
+}
+
+impl<'data> DwarfPackage<'data> {
+    pub fn new(object: &object::File<'data>) -> Option<Self> {
+        let endian = if object.is_little_endian() {
+            gimli::RunTimeEndian::Little
+        } else {
+            gimli::RunTimeEndian::Big
+        };
+        let cu_index = compressed_section::section_data(object, ".debug_cu_index")?;
+        let units = match Self::parse_cu_index(gimli::EndianSlice::new(&cu_index, endian)) {
+            Ok(units) => units,
+            Err(err) => {
+                warn!("failed to parse .debug_cu_index: {}", err);
+                return None;
+            }
+        };
+        let sections = PACKAGE_SECTIONS
+            .iter()
+            .map(|id| {
+                let data = compressed_section::section_data(object, &format!("{}.dwo", id.name()))
+                    .unwrap_or(Cow::Borrowed(&[][..]));
+                (*id, data)
+            })
+            .collect();
+
+        Some(Self {
+            endian,
+            sections,
+            units,
+        })
+    }
+
+    // Returns the sections of the split unit with the given DWO id and
+    // the offset of the unit in .debug_info.dwo of the package.
+    pub fn split_dwarf(
+        &self,
+        dwo_id: u64,
+    ) -> Option<(
+        gimli::Dwarf<gimli::EndianSlice<'_, gimli::RunTimeEndian>>,
+        usize,
+    )> {
+        let contributions = self.units.get(&dwo_id)?;
+        let load_section = |id: gimli::SectionId| -> Result<_, gimli::Error> {
+            let data = self.sections.get(&id).map(|data| &data[..]).unwrap_or(&[]);
+            let data = match contributions
+                .iter()
+                .find(|contribution| contribution.section == id)
+            {
+                Some(contribution) => data
+                    .get(contribution.offset..contribution.offset + contribution.size)
+                    .unwrap_or(&[]),
+                None => data,
+            };
+            Ok(gimli::EndianSlice::new(data, self.endian))
+        };
+        let load_section_sup = |_| Ok(gimli::EndianSlice::new(&[][..], self.endian));
+        let dwarf = gimli::Dwarf::load(load_section, load_section_sup).ok()?;
+
+        let base = contributions
+            .iter()
+            .find(|contribution| contribution.section == gimli::SectionId::DebugInfo)
+            .map(|contribution| contribution.offset)
+            .unwrap_or(0);
+        Some((dwarf, base))
+    }
+
+    fn parse_cu_index(
+        mut input: gimli::EndianSlice<gimli::RunTimeEndian>,
+    ) -> gimli::Result<HashMap<u64, Vec<Contribution>>> {
+        // version 2 is the GNU extension for DWARF 4, which has 4 byte version field.
+        // version 5 has 2 byte version field followed by 2 byte padding.
+        let version = if input.clone().read_u32()? == 2 {
+            2
+        } else {
+            input.clone().read_u16()?
+        };
+        input.skip(4)?;
+        let section_count = input.read_u32()? as usize;
+        let unit_count = input.read_u32()? as usize;
+        let slot_count = input.read_u32()? as usize;
+
+        let mut signatures = Vec::with_capacity(slot_count);
+        for _ in 0..slot_count {
+            signatures.push(input.read_u64()?);
+        }
+        let mut rows = Vec::with_capacity(slot_count);
+        for _ in 0..slot_count {
+            rows.push(input.read_u32()? as usize);
+        }
+        let mut columns = Vec::with_capacity(section_count);
+        for _ in 0..section_count {
+            columns.push(Self::section_id(version, input.read_u32()?));
+        }
+        let mut offsets = Vec::with_capacity(unit_count * section_count);
+        for _ in 0..unit_count * section_count {
+            offsets.push(input.read_u32()? as usize);
+        }
+        let mut sizes = Vec::with_capacity(unit_count * section_count);
+        for _ in 0..unit_count * section_count {
+            sizes.push(input.read_u32()? as usize);
+        }
+
+        let mut units = HashMap::new();
+        for (signature, row) in signatures.into_iter().zip(rows) {
+            // row 0 means an empty slot of the hash table
+            if row == 0 {
+                continue;
+            }
+            let contributions = columns
+                .iter()
+                .enumerate()
+                .flat_map(|(column, section)| {
+                    let index = (row - 1) * section_count + column;
+                    Some(Contribution {
+                        section: (*section)?,
+                        offset: *offsets.get(index)?,
+                        size: *sizes.get(index)?,
+                    })
+                })
+                .collect();
+            units.insert(signature, contributions);
+        }
+        Ok(units)
+    }
+
+    fn section_id(version: u16, id: u32) -> Option<gimli::SectionId> {
+        match (version, id) {
+            (_, 1) => Some(gimli::SectionId::DebugInfo),
+            (2, 2) => Some(gimli::SectionId::DebugTypes),
+            (_, 3) => Some(gimli::SectionId::DebugAbbrev),
+            (_, 4) => Some(gimli::SectionId::DebugLine),
+            (2, 5) => Some(gimli::SectionId::DebugLoc),
+            (_, 5) => Some(gimli::SectionId::DebugLocLists),
+            (_, 6) => Some(gimli::SectionId::DebugStrOffsets),
+            (2, 7) => Some(gimli::SectionId::DebugMacinfo),
+            (2, 8) | (_, 7) => Some(gimli::SectionId::DebugMacro),
+            (_, 8) => Some(gimli::SectionId::DebugRngLists),
+            _ => None,
+        }
+    }
+}
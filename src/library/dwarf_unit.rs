@@ -0,0 +1,99 @@
+use gimli::{Reader, ReaderOffset};
+
+pub enum UnitType<Offset> {
+    // compilation and partial units, with the DWO id of skeleton and split units
+    Compilation {
+        dwo_id: Option<u64>,
+    },
+    Type {
+        signature: u64,
+        type_offset: gimli::UnitOffset<Offset>,
+    },
+}
+
+pub struct UnitHeader<R: Reader> {
+    pub header: gimli::CompilationUnitHeader<R>,
+    pub unit_type: UnitType<R::Offset>,
+}
+
+// An iterator over all units in .debug_info.
+// gimli supports only compilation units of DWARF 5, so the unit headers are parsed here
+// to reach partial, skeleton and type units as well.
+pub struct UnitHeaders<R: Reader> {
+    input: R,
+    offset: R::Offset,
+}
+
+impl<R: Reader> UnitHeaders<R> {
+    pub fn new(debug_info: &gimli::DebugInfo<R>) -> Self {
+        use gimli::Section;
+
+        Self {
+            input: debug_info.reader().clone(),
+            offset: R::Offset::from_u8(0),
+        }
+    }
+
+    fn next_header(&mut self) -> gimli::Result<UnitHeader<R>> {
+        let offset = gimli::DebugInfoOffset(self.offset);
+        let (unit_length, format) = self.input.read_initial_length()?;
+        let mut rest = self.input.split(unit_length)?;
+        self.offset += R::Offset::from_u8(format.initial_length_size()) + unit_length;
+
+        let version = rest.read_u16()?;
+        let (address_size, debug_abbrev_offset, unit_type) = match version {
+            2..=4 => {
+                let debug_abbrev_offset = gimli::DebugAbbrevOffset(rest.read_offset(format)?);
+                let address_size = rest.read_u8()?;
+                let unit_type = UnitType::Compilation { dwo_id: None };
+                (address_size, debug_abbrev_offset, unit_type)
+            }
+            5 => {
+                let unit_type = gimli::DwUt(rest.read_u8()?);
+                let address_size = rest.read_u8()?;
+                let debug_abbrev_offset = gimli::DebugAbbrevOffset(rest.read_offset(format)?);
+                let unit_type = match unit_type {
+                    gimli::DW_UT_compile | gimli::DW_UT_partial => {
+                        UnitType::Compilation { dwo_id: None }
+                    }
+                    gimli::DW_UT_skeleton | gimli::DW_UT_split_compile => UnitType::Compilation {
+                        dwo_id: Some(rest.read_u64()?),
+                    },
+                    gimli::DW_UT_type | gimli::DW_UT_split_type => UnitType::Type {
+                        signature: rest.read_u64()?,
+                        type_offset: gimli::UnitOffset(rest.read_offset(format)?),
+                    },
+                    _ => return Err(gimli::Error::UnsupportedUnitType),
+                };
+                (address_size, debug_abbrev_offset, unit_type)
+            }
+            _ => return Err(gimli::Error::UnknownVersion(u64::from(version))),
+        };
+
+        let encoding = gimli::Encoding {
+            format,
+            version,
+            address_size,
+        };
+        let header = gimli::UnitHeader::new(encoding, unit_length, debug_abbrev_offset, rest);
+        Ok(UnitHeader {
+            header: gimli::CompilationUnitHeader::new(header, offset),
+            unit_type,
+        })
+    }
+}
+
+impl<R: Reader> Iterator for UnitHeaders<R> {
+    type Item = gimli::Result<UnitHeader<R>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            return None;
+        }
+        let header = self.next_header();
+        if header.is_err() {
+            self.input.empty();
+        }
+        Some(header)
+    }
+}
@@ -0,0 +1,114 @@
+use object::{Object, ObjectSection, SectionFlags, SectionKind};
+use std::fs;
+
+use super::elf_reader::{ElfReader, PT_LOAD};
+use super::memory::{Memory, WritableMemory};
+
+const SHF_ALLOC: u64 = 0x2;
+
+// A loaded section, whose bytes are at the offset in the file unless it is like .bss
+struct FileSection {
+    name: String,
+    address: usize,
+    size: usize,
+    offset: Option<usize>,
+}
+
+// The bytes of an ELF file, addressed by the addresses of its loaded sections,
+// like the initial values of variables and constants
+pub struct ElfImage {
+    data: Vec<u8>,
+    sections: Vec<FileSection>,
+}
+
+impl ElfImage {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+        let sections = {
+            let object = object::File::parse(&data).map_err(|err| format!("{}: {}", path, err))?;
+            object
+                .sections()
+                .filter(|section| match section.flags() {
+                    SectionFlags::Elf { sh_flags } => sh_flags & SHF_ALLOC != 0,
+                    _ => false,
+                })
+                .filter_map(|section| {
+                    let offset = match section.kind() {
+                        SectionKind::UninitializedData | SectionKind::UninitializedTls => None,
+                        _ => section.file_range().map(|(offset, _)| offset as usize),
+                    };
+                    Some(FileSection {
+                        name: section.name()?.to_string(),
+                        address: section.address() as usize,
+                        size: section.size() as usize,
+                        offset,
+                    })
+                })
+                .collect()
+        };
+        Ok(Self { data, sections })
+    }
+
+    fn find_section(&self, address: usize, size: usize) -> Option<&FileSection> {
+        let end = address.checked_add(size)?;
+        self.sections.iter().find(|section| {
+            section.address <= address
+                && section
+                    .address
+                    .checked_add(section.size)
+                    .is_some_and(|section_end| end <= section_end)
+        })
+    }
+
+    // Where the bytes are in the file
+    fn file_range(&self, address: usize, size: usize) -> Result<(usize, usize), String> {
+        let section = self
+            .find_section(address, size)
+            .ok_or_else(|| format!("{:#010x} is in no section of the file", address))?;
+        match section.offset {
+            Some(offset) => {
+                let start = offset + address - section.address;
+                Ok((start, start + size))
+            }
+            None => Err(format!(
+                "{:#010x} is in {}, which has no bytes in the file",
+                address, section.name
+            )),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, &self.data).map_err(|err| format!("failed to write {}: {}", path, err))
+    }
+
+    // The bytes of the PT_LOAD segments at their load addresses, which are written to flash
+    pub fn load_segments(&self) -> Vec<(usize, &[u8])> {
+        let headers = ElfReader::new(&self.data)
+            .and_then(|reader| reader.program_headers())
+            .unwrap_or_default();
+        headers
+            .into_iter()
+            .filter(|header| header.p_type == PT_LOAD && header.file_size != 0)
+            .filter_map(|header| {
+                let end = header.offset.checked_add(header.file_size)?;
+                let data = self.data.get(header.offset..end)?;
+                Some((header.physical_address, data))
+            })
+            .collect()
+    }
+}
+
+impl Memory for ElfImage {
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>> {
+        let (start, end) = self.file_range(address, size).ok()?;
+        self.data.get(start..end).map(<[u8]>::to_vec)
+    }
+}
+
+impl WritableMemory for ElfImage {
+    fn write(&mut self, address: usize, bytes: &[u8]) -> Result<(), String> {
+        let (start, end) = self.file_range(address, bytes.len())?;
+        self.data[start..end].copy_from_slice(bytes);
+        Ok(())
+    }
+}
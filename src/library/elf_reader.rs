@@ -0,0 +1,138 @@
+use std::convert::TryInto;
+
+pub const PT_LOAD: u32 = 1;
+pub const PT_NOTE: u32 = 4;
+const NT_AUXV: u32 = 6;
+const AT_ENTRY: u64 = 9;
+
+pub struct ProgramHeader {
+    pub p_type: u32,
+    pub offset: usize,
+    pub virtual_address: usize,
+    // the load memory address, which differs from the virtual one for initialized data in flash
+    pub physical_address: usize,
+    pub file_size: usize,
+    pub memory_size: usize,
+}
+
+// Reads the headers which object does not provide, for both ELF32 and ELF64 in either byte order
+pub struct ElfReader<'data> {
+    data: &'data [u8],
+    is_64: bool,
+    little_endian: bool,
+}
+
+impl<'data> ElfReader<'data> {
+    pub fn new(data: &'data [u8]) -> Option<Self> {
+        if data.get(..4)? != b"\x7fELF" {
+            return None;
+        }
+        Some(Self {
+            data,
+            is_64: *data.get(4)? == 2,
+            little_endian: *data.get(5)? == 1,
+        })
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        self.data
+            .get(offset..offset.checked_add(N)?)?
+            .try_into()
+            .ok()
+    }
+
+    pub fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn u64(&self, offset: usize) -> Option<u64> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+
+    // A word of the class, 8 bytes for ELF64 and 4 bytes for ELF32
+    fn word(&self, offset: usize) -> Option<u64> {
+        if self.is_64 {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+
+    pub fn program_headers(&self) -> Option<Vec<ProgramHeader>> {
+        let (phoff, phentsize, phnum) = if self.is_64 {
+            (self.u64(0x20)? as usize, self.u16(0x36)?, self.u16(0x38)?)
+        } else {
+            (self.u32(0x1c)? as usize, self.u16(0x2a)?, self.u16(0x2c)?)
+        };
+        (0..phnum as usize)
+            .map(|i| {
+                let header = phoff.checked_add(i.checked_mul(phentsize as usize)?)?;
+                // the fields are in different orders in ELF32 and ELF64
+                let (offset, virtual_address, physical_address, file_size, memory_size) =
+                    if self.is_64 {
+                        (0x08, 0x10, 0x18, 0x20, 0x28)
+                    } else {
+                        (0x04, 0x08, 0x0c, 0x10, 0x14)
+                    };
+                let field = |offset: usize| self.word(header.checked_add(offset)?);
+                Some(ProgramHeader {
+                    p_type: self.u32(header)?,
+                    offset: field(offset)? as usize,
+                    virtual_address: field(virtual_address)? as usize,
+                    physical_address: field(physical_address)? as usize,
+                    file_size: field(file_size)? as usize,
+                    memory_size: field(memory_size)? as usize,
+                })
+            })
+            .collect()
+    }
+
+    // AT_ENTRY in NT_AUXV, which is the entry point of the executable where it is loaded.
+    // Notes beyond the file are not read, including ones whose sizes overflow.
+    pub fn auxv_entry(&self, header: &ProgramHeader) -> Option<usize> {
+        let end = header.offset.checked_add(header.file_size)?;
+        let mut note = header.offset;
+        while note.checked_add(12)? <= end {
+            let name_size = self.u32(note)? as usize;
+            let desc_size = self.u32(note + 4)? as usize;
+            let note_type = self.u32(note + 8)?;
+            let desc = (note + 12).checked_add(align4(name_size)?)?;
+            if note_type == NT_AUXV {
+                let word_size = if self.is_64 { 8 } else { 4 };
+                let desc_end = desc.checked_add(desc_size)?;
+                let mut entry = desc;
+                while entry.checked_add(2 * word_size)? <= desc_end {
+                    if self.word(entry)? == AT_ENTRY {
+                        return Some(self.word(entry + word_size)? as usize);
+                    }
+                    entry += 2 * word_size;
+                }
+            }
+            note = desc.checked_add(align4(desc_size)?)?;
+        }
+        None
+    }
+}
+
+fn align4(size: usize) -> Option<usize> {
+    Some(size.checked_add(3)? & !3)
+}
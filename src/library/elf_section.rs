@@ -0,0 +1,56 @@
+use object::{Object, ObjectSection, SectionFlags};
+use std::fs;
+
+const SHF_ALLOC: u64 = 0x2;
+
+// A section which occupies memory at run time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElfSection {
+    pub name: String,
+    pub address: usize,
+    pub size: usize,
+}
+
+impl ElfSection {
+    // The number of bytes of the section in `start..end`
+    pub fn overlap(&self, start: usize, end: usize) -> usize {
+        let overlap_start = self.address.max(start);
+        let overlap_end = self.address.saturating_add(self.size).min(end);
+        overlap_end.saturating_sub(overlap_start)
+    }
+}
+
+// The byte order of the values in the memory of the executable
+pub fn is_little_endian(elf_path: &str) -> Result<bool, String> {
+    with_object(elf_path, |object| object.is_little_endian())
+}
+
+// Sections with SHF_ALLOC, which are also in stripped executables
+pub fn loaded_sections(elf_path: &str) -> Result<Vec<ElfSection>, String> {
+    with_object(elf_path, loaded_sections_of)
+}
+
+fn loaded_sections_of(object: &object::File) -> Vec<ElfSection> {
+    object
+        .sections()
+        .filter(|section| match section.flags() {
+            SectionFlags::Elf { sh_flags } => sh_flags & SHF_ALLOC != 0,
+            _ => false,
+        })
+        .filter_map(|section| {
+            Some(ElfSection {
+                name: section.name()?.to_string(),
+                address: section.address() as usize,
+                size: section.size() as usize,
+            })
+        })
+        .collect()
+}
+
+fn with_object<T>(elf_path: &str, f: impl FnOnce(&object::File) -> T) -> Result<T, String> {
+    let file = fs::File::open(elf_path).map_err(|err| format!("{}: {}", elf_path, err))?;
+    let mmap =
+        unsafe { memmap::Mmap::map(&file) }.map_err(|err| format!("{}: {}", elf_path, err))?;
+    let object = object::File::parse(&mmap).map_err(|err| format!("{}: {}", elf_path, err))?;
+    Ok(f(&object))
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::str::FromStr;
+
+use super::elf_reader::{ElfReader, PT_LOAD};
+use super::intel_hex;
+use super::memory::Memory;
+use super::srecord;
+
+// An image to be written to flash, read from Intel HEX or Motorola S-record,
+// whose contiguous bytes are merged into a segment
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlashImage {
+    segments: Vec<(usize, Vec<u8>)>,
+}
+
+impl FlashImage {
+    pub fn new(records: Vec<(usize, Vec<u8>)>) -> Self {
+        // segments are sorted and neither overlap nor touch each other
+        let mut segments: Vec<(usize, Vec<u8>)> = Vec::new();
+        // records are merged in order, so later records overwrite the overlapping bytes of earlier ones
+        for (address, data) in records {
+            let end = address + data.len();
+            // the segments which overlap or touch the record
+            let first = segments.partition_point(|(start, bytes)| start + bytes.len() < address);
+            let last = segments.partition_point(|(start, _)| *start <= end);
+            let merged: Vec<(usize, Vec<u8>)> = segments.drain(first..last).collect();
+            let start = merged
+                .first()
+                .map_or(address, |(start, _)| address.min(*start));
+            let merged_end = merged
+                .last()
+                .map_or(end, |(start, bytes)| end.max(start + bytes.len()));
+            let mut bytes = vec![0; merged_end - start];
+            for (segment_start, segment) in merged {
+                let offset = segment_start - start;
+                bytes[offset..offset + segment.len()].copy_from_slice(&segment);
+            }
+            bytes[address - start..end - start].copy_from_slice(&data);
+            segments.insert(first, (start, bytes));
+        }
+        Self { segments }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {}", path, err))?
+            .parse()
+            .map_err(|message| format!("{}: {}", path, message))
+    }
+}
+
+impl FromStr for FlashImage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let records = match s.trim_start().chars().next() {
+            Some(':') => intel_hex::from_intel_hex(s)?,
+            Some('S') => srecord::from_srecord(s)?,
+            _ => return Err(String::from("neither Intel HEX nor S-record")),
+        };
+        Ok(Self::new(records))
+    }
+}
+
+impl Memory for FlashImage {
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>> {
+        self.segments.iter().find_map(|(start, data)| {
+            let offset = address.checked_sub(*start)?;
+            data.get(offset..offset.checked_add(size)?)
+                .map(<[u8]>::to_vec)
+        })
+    }
+}
+
+// Reads the bytes of variables at their load addresses, where images to be written to flash
+// have the initial values of .data, and at their addresses for the others like .rodata
+pub struct LoadAddressMemory<M> {
+    memory: M,
+    // the virtual and the physical addresses and the file size of the PT_LOAD segments
+    segments: Vec<(usize, usize, usize)>,
+}
+
+impl<M: Memory> LoadAddressMemory<M> {
+    pub fn new(memory: M, elf_path: &str) -> Result<Self, String> {
+        let data =
+            fs::read(elf_path).map_err(|err| format!("failed to read {}: {}", elf_path, err))?;
+        let headers = ElfReader::new(&data)
+            .and_then(|reader| reader.program_headers())
+            .ok_or_else(|| format!("{}: broken program headers", elf_path))?;
+        let segments = headers
+            .into_iter()
+            .filter(|header| header.p_type == PT_LOAD)
+            .map(|header| {
+                (
+                    header.virtual_address,
+                    header.physical_address,
+                    header.file_size,
+                )
+            })
+            .collect();
+        Ok(Self { memory, segments })
+    }
+}
+
+impl<M: Memory> Memory for LoadAddressMemory<M> {
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>> {
+        let end = address.checked_add(size)?;
+        let segment = self
+            .segments
+            .iter()
+            .find(|(virtual_address, _, file_size)| {
+                *virtual_address <= address
+                    && virtual_address
+                        .checked_add(*file_size)
+                        .is_some_and(|segment_end| end <= segment_end)
+            });
+        let load_address = match segment {
+            Some((virtual_address, physical_address, _)) => {
+                physical_address.checked_add(address - virtual_address)?
+            }
+            None => address,
+        };
+        self.memory.read(load_address, size)
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::Write;
+
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+// bytes per data record
+const RECORD_SIZE: usize = 16;
+
+// Intel HEX records of the bytes at the addresses, which must be below 4GiB
+pub fn to_intel_hex(segments: &[(usize, &[u8])]) -> Result<String, String> {
+    let mut hex = String::new();
+    let mut upper = None;
+    for (address, data) in segments {
+        // in u64 not to overflow on 32-bit targets
+        let end = (*address as u64).checked_add(data.len() as u64);
+        if !matches!(end, Some(end) if end <= 1 << 32) {
+            return Err(format!(
+                "{:#x} + {:#x} is beyond the addresses of Intel HEX",
+                address,
+                data.len()
+            ));
+        }
+        let mut offset = 0;
+        while offset < data.len() {
+            let address = address + offset;
+            if upper != Some(address >> 16) {
+                upper = Some(address >> 16);
+                let bytes = ((address >> 16) as u16).to_be_bytes();
+                write_record(&mut hex, 0, EXTENDED_LINEAR_ADDRESS, &bytes);
+            }
+            // records do not cross 64KiB boundaries
+            let size = RECORD_SIZE
+                .min(data.len() - offset)
+                .min(0x10000 - (address & 0xffff));
+            write_record(&mut hex, address as u16, DATA, &data[offset..offset + size]);
+            offset += size;
+        }
+    }
+    write_record(&mut hex, 0, END_OF_FILE, &[]);
+    Ok(hex)
+}
+
+// `:LLAAAATT` followed by the data and the checksum
+fn write_record(hex: &mut String, address: u16, record_type: u8, data: &[u8]) {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.push(record_type);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(sum.wrapping_neg());
+    hex.push(':');
+    for byte in bytes {
+        write!(hex, "{:02X}", byte).unwrap();
+    }
+    hex.push('\n');
+}
+
+// The bytes of the data records at their addresses, in the order of the records
+pub fn from_intel_hex(hex: &str) -> Result<Vec<(usize, Vec<u8>)>, String> {
+    let mut segments = Vec::new();
+    let mut base = 0;
+    for (i, line) in hex.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record =
+            parse_record(line).map_err(|message| format!("line {}: {}", i + 1, message))?;
+        let (address, record_type, data) = record;
+        match record_type {
+            DATA => segments.push((base + address as usize, data)),
+            END_OF_FILE => break,
+            EXTENDED_SEGMENT_ADDRESS if data.len() == 2 => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 4
+            }
+            EXTENDED_LINEAR_ADDRESS if data.len() == 2 => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 16
+            }
+            START_SEGMENT_ADDRESS | START_LINEAR_ADDRESS => (),
+            _ => return Err(format!("line {}: invalid record: {}", i + 1, line)),
+        }
+    }
+    Ok(segments)
+}
+
+// The address, the type and the data of `:LLAAAATT...CC`
+fn parse_record(line: &str) -> Result<(u16, u8, Vec<u8>), String> {
+    let bytes = line
+        .strip_prefix(':')
+        .and_then(parse_bytes)
+        .ok_or_else(|| format!("not an Intel HEX record: {}", line))?;
+    if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+        return Err(format!("invalid length of the record: {}", line));
+    }
+    if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+        return Err(format!("invalid checksum of the record: {}", line));
+    }
+    let address = u16::from_be_bytes([bytes[1], bytes[2]]);
+    Ok((address, bytes[3], bytes[4..bytes.len() - 1].to_vec()))
+}
+
+// Pairs of hexadecimal digits
+pub(crate) fn parse_bytes(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,317 @@
+use std::fs;
+use std::str::FromStr;
+
+// The input sections and the symbols in a map file written by GNU ld (`-Map`) or lld (`--Map`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkerMap {
+    pub sections: Vec<InputSection>,
+    pub symbols: Vec<MapSymbol>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputSection {
+    pub output_section: String,
+    pub name: String,
+    pub address: usize,
+    pub size: usize,
+    // `a.o`, or `libfoo.a(foo.o)` for archive members
+    pub object: String,
+}
+
+impl InputSection {
+    // Sections whose ends overflow the address space contain nothing
+    pub fn contains(&self, address: usize) -> bool {
+        self.address <= address
+            && self
+                .address
+                .checked_add(self.size)
+                .is_some_and(|end| address < end)
+    }
+
+    // `libfoo.a` for `libfoo.a(foo.o)`, or the object itself if it is not an archive member
+    pub fn archive(&self) -> &str {
+        match self
+            .object
+            .strip_suffix(')')
+            .and_then(|object| object.find('('))
+        {
+            Some(index) => &self.object[..index],
+            None => &self.object,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapSymbol {
+    pub name: String,
+    pub address: usize,
+    pub size: MapSymbolSize,
+    pub object: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MapSymbolSize {
+    // written by lld
+    Exact(usize),
+    // GNU ld does not write sizes, so this is the room until the next symbol or the end of the input section
+    AtMost(usize),
+}
+
+impl LinkerMap {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {}", path, err))?
+            .parse()
+            .map_err(|message| format!("{}: {}", path, message))
+    }
+
+    // The input section which has the address, ignoring empty sections
+    pub fn find_section(&self, address: usize) -> Option<&InputSection> {
+        self.sections
+            .iter()
+            .find(|section| section.contains(address))
+    }
+
+    pub fn find_symbols<'map>(
+        &'map self,
+        name: &'map str,
+    ) -> impl Iterator<Item = &'map MapSymbol> {
+        self.symbols
+            .iter()
+            .filter(move |symbol| symbol.name == name)
+    }
+}
+
+impl FromStr for LinkerMap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        for line in lines.by_ref() {
+            let line = line.trim_end();
+            if line == "Linker script and memory map" {
+                return Ok(GnuMapParser::default().parse(lines));
+            }
+            if line.trim_start().starts_with("VMA") || line.trim_start().starts_with("Address") {
+                return LldMapParser::new(line).map(|parser| parser.parse(lines));
+            }
+        }
+        Err(String::from("neither a GNU ld nor an lld map file"))
+    }
+}
+
+fn parse_hex(s: &str) -> Option<usize> {
+    usize::from_str_radix(s.trim_start_matches("0x"), 16).ok()
+}
+
+// Debug information and notes are not loaded, and their addresses overlap the loaded sections
+fn is_loaded(output_section: &str) -> bool {
+    ![
+        ".debug",
+        ".zdebug",
+        ".comment",
+        ".stab",
+        ".note.GNU-stack",
+        ".gnu.attributes",
+        ".ARM.attributes",
+    ]
+    .iter()
+    .any(|prefix| output_section.starts_with(prefix))
+}
+
+// ` .bss  0x0000000000004020  0xc libb.a(b.o)` for input sections, which may be wrapped after long names,
+// and `                0x0000000000004020                counter` for symbols in the last input section
+#[derive(Default)]
+struct GnuMapParser {
+    map: LinkerMap,
+    output_section: String,
+    // the name of an input section wrapped to the next line
+    wrapped: Option<String>,
+    // whether the following symbols are in the last input section
+    in_section: bool,
+    // the symbols in the last input section, whose sizes are known when it ends
+    pending: Vec<(String, usize)>,
+}
+
+impl GnuMapParser {
+    fn parse<'a>(mut self, lines: impl Iterator<Item = &'a str>) -> LinkerMap {
+        for line in lines {
+            let line = line.trim_end();
+            if line == "Cross Reference Table" {
+                break;
+            }
+            if let Some(name) = self.wrapped.take() {
+                self.input_section(name, line.split_whitespace().collect());
+            } else if line.is_empty() || line.starts_with("LOAD ") || line.starts_with("OUTPUT(") {
+                continue;
+            } else if !line.starts_with(' ') {
+                // output sections, whose addresses are not needed
+                self.finish_input_section();
+                self.output_section = line.split_whitespace().next().unwrap().to_string();
+            } else if line.starts_with("  ") {
+                self.symbol(line.split_whitespace().collect());
+            } else {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                match fields[0] {
+                    // patterns in the linker script and paddings
+                    name if name.starts_with('*') => self.finish_input_section(),
+                    name if fields.len() == 1 => self.wrapped = Some(name.to_string()),
+                    name => self.input_section(name.to_string(), fields[1..].to_vec()),
+                }
+            }
+        }
+        self.finish_input_section();
+        self.map
+    }
+
+    fn input_section(&mut self, name: String, fields: Vec<&str>) {
+        self.finish_input_section();
+        if fields.len() < 3
+            || self.output_section == "/DISCARD/"
+            || !is_loaded(&self.output_section)
+        {
+            return;
+        }
+        if let (Some(address), Some(size)) = (parse_hex(fields[0]), parse_hex(fields[1])) {
+            self.map.sections.push(InputSection {
+                output_section: self.output_section.clone(),
+                name,
+                address,
+                size,
+                object: fields[2..].join(" "),
+            });
+            self.in_section = true;
+        }
+    }
+
+    fn symbol(&mut self, fields: Vec<&str>) {
+        // assignments like `. = ALIGN (0x8)` and `_edata = .` are not symbols of objects
+        if !self.in_section || fields.len() != 2 || !fields[0].starts_with("0x") {
+            return;
+        }
+        if let Some(address) = parse_hex(fields[0]) {
+            self.pending.push((fields[1].to_string(), address));
+        }
+    }
+
+    fn finish_input_section(&mut self) {
+        if !std::mem::replace(&mut self.in_section, false) {
+            return;
+        }
+        let section = self.map.sections.last().unwrap();
+        let end = section.address.saturating_add(section.size);
+        let mut addresses: Vec<usize> = self.pending.iter().map(|(_, address)| *address).collect();
+        addresses.sort_unstable();
+        for (name, address) in self.pending.drain(..) {
+            // aliases at the same address share the room
+            let next = addresses
+                .iter()
+                .find(|next| **next > address)
+                .copied()
+                .unwrap_or(end);
+            self.map.symbols.push(MapSymbol {
+                name,
+                address,
+                size: MapSymbolSize::AtMost(next.saturating_sub(address)),
+                object: section.object.clone(),
+            });
+        }
+    }
+}
+
+// `VMA LMA Size Align Out In Symbol`, or `Address Size Align Out In Symbol` in older versions,
+// where output sections, input sections and symbols are indented to the columns of `Out`, `In` and `Symbol`
+struct LldMapParser {
+    numbers: usize,
+    in_column: usize,
+    symbol_column: usize,
+}
+
+impl LldMapParser {
+    fn new(header: &str) -> Result<Self, String> {
+        // the fields of the header and the columns where they start
+        let mut fields = Vec::new();
+        let mut start = None;
+        for (index, c) in header.char_indices().chain([(header.len(), ' ')]) {
+            match (start, c.is_whitespace()) {
+                (None, false) => start = Some(index),
+                (Some(field_start), true) => {
+                    fields.push((field_start, &header[field_start..index]));
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+        let position = |name: &str| {
+            fields
+                .iter()
+                .position(|(_, field)| *field == name)
+                .ok_or_else(|| format!("no {} column in the header", name))
+        };
+        Ok(Self {
+            numbers: position("Out")?,
+            in_column: fields[position("In")?].0,
+            symbol_column: fields[position("Symbol")?].0,
+        })
+    }
+
+    fn parse<'a>(&self, lines: impl Iterator<Item = &'a str>) -> LinkerMap {
+        let mut map = LinkerMap::default();
+        let mut output_section = String::new();
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() <= self.numbers {
+                continue;
+            }
+            let numbers: Option<Vec<usize>> = fields[..self.numbers]
+                .iter()
+                .map(|field| parse_hex(field))
+                .collect();
+            let numbers = match numbers {
+                Some(numbers) => numbers,
+                None => continue,
+            };
+            // the address is the first column and the size is the one before `Align`
+            let (address, size) = (numbers[0], numbers[self.numbers - 2]);
+            let mut text = line;
+            for _ in 0..self.numbers {
+                text = text.trim_start();
+                text = &text[text.find(' ').unwrap_or(text.len())..];
+            }
+            let text = text.trim_start();
+            let column = line.len() - text.len();
+            if column < self.in_column {
+                output_section = text.to_string();
+            } else if !is_loaded(&output_section) {
+                continue;
+            } else if column < self.symbol_column {
+                // `a.o:(.bss)`, `libb.a(b.o):(.bss)` or `<internal>:(.bss)`
+                let (object, name) = match text.rfind(":(") {
+                    Some(index) if text.ends_with(')') => {
+                        (&text[..index], &text[index + 2..text.len() - 1])
+                    }
+                    _ => continue,
+                };
+                map.sections.push(InputSection {
+                    output_section: output_section.clone(),
+                    name: name.to_string(),
+                    address,
+                    size,
+                    object: object.to_string(),
+                });
+            } else if !text.contains(' ') {
+                let object = match map.sections.last() {
+                    Some(section) => section.object.clone(),
+                    None => continue,
+                };
+                map.symbols.push(MapSymbol {
+                    name: text.to_string(),
+                    address,
+                    size: MapSymbolSize::Exact(size),
+                    object,
+                });
+            }
+        }
+        map
+    }
+}
@@ -0,0 +1,63 @@
+use std::fs;
+
+// Memory which the values of variables are read from
+pub trait Memory {
+    // None if some of the bytes are not in the memory
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>>;
+
+    // What is added to the addresses in the ELF to be the addresses in the memory,
+    // like where a position independent executable is loaded
+    fn load_bias(&self) -> usize {
+        0
+    }
+}
+
+// Memory which the values of variables are written to
+pub trait WritableMemory: Memory {
+    fn write(&mut self, address: usize, bytes: &[u8]) -> Result<(), String>;
+}
+
+// A raw dump of memory from the base address, like RAM pulled from a crashed device
+pub struct RawImage {
+    base: usize,
+    data: Vec<u8>,
+}
+
+impl RawImage {
+    pub fn new(base: usize, data: Vec<u8>) -> Self {
+        Self { base, data }
+    }
+
+    pub fn from_file(path: &str, base: usize) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+        Ok(Self::new(base, data))
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, &self.data).map_err(|err| format!("failed to write {}: {}", path, err))
+    }
+}
+
+impl Memory for RawImage {
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>> {
+        let start = address.checked_sub(self.base)?;
+        self.data
+            .get(start..start.checked_add(size)?)
+            .map(<[u8]>::to_vec)
+    }
+}
+
+impl WritableMemory for RawImage {
+    fn write(&mut self, address: usize, bytes: &[u8]) -> Result<(), String> {
+        let data = address
+            .checked_sub(self.base)
+            .and_then(|start| self.data.get_mut(start..start.checked_add(bytes.len())?))
+            .ok_or_else(|| format!("{:#010x} is not in the image", address))?;
+        data.copy_from_slice(bytes);
+        Ok(())
+    }
+}
@@ -1 +1,16 @@
+pub mod compressed_section;
+pub mod core_file;
+pub mod debug_file;
 pub mod dwarf;
+pub mod dwarf_package;
+pub mod dwarf_unit;
+pub mod elf_image;
+pub mod elf_reader;
+pub mod elf_section;
+pub mod flash_image;
+pub mod intel_hex;
+pub mod linker_map;
+pub mod memory;
+pub mod name_index;
+pub mod process_memory;
+pub mod srecord;
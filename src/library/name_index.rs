@@ -0,0 +1,343 @@
+use gimli::{Reader, ReaderOffset};
+use log::{info, warn};
+use object::Object;
+use std::collections::BTreeSet;
+
+use super::compressed_section;
+
+type IndexReader<'data> = gimli::read::EndianSlice<'data, gimli::RunTimeEndian>;
+
+// Whether the object file has any of the accelerated lookup sections
+pub fn has_name_index(object: &object::File) -> bool {
+    [
+        ".debug_names",
+        ".gdb_index",
+        ".debug_gnu_pubnames",
+        ".debug_pubnames",
+    ]
+    .iter()
+    .any(|name| object.section_by_name(name).is_some())
+}
+
+// Finds the offsets in .debug_info of the units which define a global variable,
+// with the accelerated lookup sections.
+// Returns None if the object file has no index, where all units should be scanned.
+pub fn units_by_name(object: &object::File, name: &str) -> Option<Vec<usize>> {
+    let endian = if object.is_little_endian() {
+        gimli::RunTimeEndian::Little
+    } else {
+        gimli::RunTimeEndian::Big
+    };
+
+    let units = if let Some(data) = compressed_section::section_data(object, ".debug_names") {
+        info!("lookup {} in .debug_names", name);
+        let debug_str = compressed_section::section_data(object, ".debug_str")?;
+        debug_names(
+            IndexReader::new(&data, endian),
+            IndexReader::new(&debug_str, endian),
+            name,
+        )
+    } else if let Some(data) = compressed_section::section_data(object, ".gdb_index") {
+        info!("lookup {} in .gdb_index", name);
+        // .gdb_index is always little endian
+        gdb_index(IndexReader::new(&data, gimli::RunTimeEndian::Little), name)
+    } else if let Some(data) = compressed_section::section_data(object, ".debug_gnu_pubnames") {
+        info!("lookup {} in .debug_gnu_pubnames", name);
+        pubnames(IndexReader::new(&data, endian), name, true)
+    } else if let Some(data) = compressed_section::section_data(object, ".debug_pubnames") {
+        info!("lookup {} in .debug_pubnames", name);
+        pubnames(IndexReader::new(&data, endian), name, false)
+    } else {
+        return None;
+    };
+
+    match units {
+        Ok(units) => Some(units.into_iter().collect()),
+        Err(err) => {
+            warn!("failed to read name index: {}", err);
+            None
+        }
+    }
+}
+
+// .debug_pubnames, and .debug_gnu_pubnames which has a flag byte in each entry
+fn pubnames(mut input: IndexReader, name: &str, gnu: bool) -> gimli::Result<BTreeSet<usize>> {
+    let mut units = BTreeSet::new();
+    while !input.is_empty() {
+        let (length, format) = input.read_initial_length()?;
+        let mut rest = input.split(length)?;
+        let _version = rest.read_u16()?;
+        let unit_offset = rest.read_offset(format)?;
+        let _unit_length = rest.read_offset(format)?;
+        loop {
+            let die_offset = rest.read_offset(format)?;
+            if die_offset == 0 {
+                break;
+            }
+            if gnu {
+                let _flags = rest.read_u8()?;
+            }
+            if rest.read_null_terminated_slice()?.slice() == name.as_bytes() {
+                units.insert(unit_offset);
+            }
+        }
+    }
+    Ok(units)
+}
+
+const GDB_INDEX_SYMBOL_KIND_VARIABLE: u32 = 2;
+
+fn gdb_index(input: IndexReader, name: &str) -> gimli::Result<BTreeSet<usize>> {
+    let mut header = input;
+    let version = header.read_u32()?;
+    if version < 7 {
+        warn!("unsupported .gdb_index version: {}", version);
+        return Err(gimli::Error::UnknownVersion(u64::from(version)));
+    }
+    let cu_list_offset = header.read_u32()? as usize;
+    let types_cu_list_offset = header.read_u32()? as usize;
+    let _address_area_offset = header.read_u32()? as usize;
+    let symbol_table_offset = header.read_u32()? as usize;
+    let constant_pool_offset = header.read_u32()? as usize;
+
+    let mut cu_list = range(input, cu_list_offset, types_cu_list_offset)?;
+    let mut cu_offsets = Vec::new();
+    while !cu_list.is_empty() {
+        cu_offsets.push(cu_list.read_u64()? as usize);
+        let _length = cu_list.read_u64()?;
+    }
+
+    let symbol_table = range(input, symbol_table_offset, constant_pool_offset)?;
+    let constant_pool = range_from(input, constant_pool_offset)?;
+    let slots = symbol_table.len() / 8;
+    let mut units = BTreeSet::new();
+    if slots == 0 {
+        return Ok(units);
+    }
+
+    let hash = gdb_index_hash(name);
+    let mut index = hash as usize & (slots - 1);
+    let step = (hash.wrapping_mul(17) as usize & (slots - 1)) | 1;
+    for _ in 0..slots {
+        let mut slot = range_from(symbol_table, index * 8)?;
+        let name_offset = slot.read_u32()? as usize;
+        let vector_offset = slot.read_u32()? as usize;
+        if name_offset == 0 && vector_offset == 0 {
+            break;
+        }
+        let mut symbol = range_from(constant_pool, name_offset)?;
+        if symbol.read_null_terminated_slice()?.slice() == name.as_bytes() {
+            let mut vector = range_from(constant_pool, vector_offset)?;
+            for _ in 0..vector.read_u32()? {
+                let value = vector.read_u32()?;
+                let cu_index = (value & 0x00ff_ffff) as usize;
+                let kind = (value >> 28) & 0x7;
+                // type units are listed after the compilation units
+                if kind == GDB_INDEX_SYMBOL_KIND_VARIABLE && cu_index < cu_offsets.len() {
+                    units.insert(cu_offsets[cu_index]);
+                }
+            }
+            break;
+        }
+        index = (index + step) & (slots - 1);
+    }
+    Ok(units)
+}
+
+// mapped_index_string_hash of gdb for version 5 and later
+fn gdb_index_hash(name: &str) -> u32 {
+    name.bytes().fold(0u32, |hash, c| {
+        hash.wrapping_mul(67)
+            .wrapping_add(u32::from(c.to_ascii_lowercase()))
+            .wrapping_sub(113)
+    })
+}
+
+const DW_IDX_COMPILE_UNIT: u64 = 1;
+
+struct NameAbbreviation {
+    code: u64,
+    tag: gimli::DwTag,
+    attributes: Vec<(u64, gimli::DwForm)>,
+}
+
+fn debug_names(
+    mut input: IndexReader,
+    debug_str: IndexReader,
+    name: &str,
+) -> gimli::Result<BTreeSet<usize>> {
+    let mut units = BTreeSet::new();
+    while !input.is_empty() {
+        let (length, format) = input.read_initial_length()?;
+        let rest = input.split(length)?;
+        debug_names_unit(rest, format, debug_str, name, &mut units)?;
+    }
+    Ok(units)
+}
+
+// A name index of DWARF 5, which can be one of several indexes in .debug_names
+fn debug_names_unit(
+    mut input: IndexReader,
+    format: gimli::Format,
+    debug_str: IndexReader,
+    name: &str,
+    units: &mut BTreeSet<usize>,
+) -> gimli::Result<()> {
+    let version = input.read_u16()?;
+    if version != 5 {
+        return Err(gimli::Error::UnknownVersion(u64::from(version)));
+    }
+    let _padding = input.read_u16()?;
+    let comp_unit_count = input.read_u32()? as usize;
+    let local_type_unit_count = input.read_u32()? as usize;
+    let foreign_type_unit_count = input.read_u32()? as usize;
+    let bucket_count = input.read_u32()? as usize;
+    let name_count = input.read_u32()? as usize;
+    let abbrev_table_size = input.read_u32()? as usize;
+    let augmentation_string_size = input.read_u32()? as usize;
+    input.skip(augmentation_string_size)?;
+
+    let mut cu_offsets = Vec::with_capacity(comp_unit_count);
+    for _ in 0..comp_unit_count {
+        cu_offsets.push(input.read_offset(format)?);
+    }
+    input.skip(local_type_unit_count * format.word_size() as usize)?;
+    input.skip(foreign_type_unit_count * 8)?;
+    let buckets = input.split(bucket_count * 4)?;
+    let hashes = if bucket_count > 0 {
+        input.split(name_count * 4)?
+    } else {
+        input.split(0)?
+    };
+    let string_offsets = input.split(name_count * format.word_size() as usize)?;
+    let entry_offsets = input.split(name_count * format.word_size() as usize)?;
+    let abbreviations = name_abbreviations(input.split(abbrev_table_size)?)?;
+    let entry_pool = input;
+
+    let read_name = |index: usize| -> gimli::Result<IndexReader> {
+        let mut offsets = range_from(string_offsets, index * format.word_size() as usize)?;
+        let mut string = range_from(debug_str, offsets.read_offset(format)?)?;
+        string.read_null_terminated_slice()
+    };
+    // names are 1-origin in the hash table
+    let candidates: Vec<usize> = if bucket_count == 0 {
+        (1..=name_count).collect()
+    } else {
+        let hash = debug_names_hash(name);
+        let mut bucket = range_from(buckets, (hash as usize % bucket_count) * 4)?;
+        let first = bucket.read_u32()? as usize;
+        let mut candidates = Vec::new();
+        if first > 0 {
+            for index in first..=name_count {
+                let mut hashes = range_from(hashes, (index - 1) * 4)?;
+                let name_hash = hashes.read_u32()?;
+                if name_hash as usize % bucket_count != hash as usize % bucket_count {
+                    break;
+                }
+                if name_hash == hash {
+                    candidates.push(index);
+                }
+            }
+        }
+        candidates
+    };
+
+    for index in candidates {
+        if read_name(index - 1)?.slice() != name.as_bytes() {
+            continue;
+        }
+        let mut offsets = range_from(entry_offsets, (index - 1) * format.word_size() as usize)?;
+        let mut entries = range_from(entry_pool, offsets.read_offset(format)?)?;
+        loop {
+            let code = entries.read_uleb128()?;
+            if code == 0 {
+                break;
+            }
+            let abbreviation = match abbreviations.iter().find(|abbrev| abbrev.code == code) {
+                Some(abbreviation) => abbreviation,
+                None => return Err(gimli::Error::UnknownAbbreviation),
+            };
+            // a single compilation unit is implied without DW_IDX_compile_unit
+            let mut cu_index = if comp_unit_count == 1 { Some(0) } else { None };
+            for (index, form) in abbreviation.attributes.iter() {
+                let value = read_index_value(&mut entries, *form, format)?;
+                if *index == DW_IDX_COMPILE_UNIT {
+                    cu_index = Some(value as usize);
+                }
+            }
+            if abbreviation.tag != gimli::DW_TAG_variable {
+                continue;
+            }
+            if let Some(offset) = cu_index.and_then(|cu_index| cu_offsets.get(cu_index)) {
+                units.insert(*offset);
+            }
+        }
+        break;
+    }
+    Ok(())
+}
+
+fn name_abbreviations(mut input: IndexReader) -> gimli::Result<Vec<NameAbbreviation>> {
+    let mut abbreviations = Vec::new();
+    loop {
+        let code = input.read_uleb128()?;
+        if code == 0 {
+            break;
+        }
+        let tag = gimli::DwTag(input.read_uleb128()? as u16);
+        let mut attributes = Vec::new();
+        loop {
+            let index = input.read_uleb128()?;
+            let form = gimli::DwForm(input.read_uleb128()? as u16);
+            if index == 0 && form.0 == 0 {
+                break;
+            }
+            attributes.push((index, form));
+        }
+        abbreviations.push(NameAbbreviation {
+            code,
+            tag,
+            attributes,
+        });
+    }
+    Ok(abbreviations)
+}
+
+// The forms allowed for the index attributes are constant, flag and reference classes
+fn read_index_value(
+    input: &mut IndexReader,
+    form: gimli::DwForm,
+    format: gimli::Format,
+) -> gimli::Result<u64> {
+    match form {
+        gimli::DW_FORM_flag_present => Ok(1),
+        gimli::DW_FORM_data1 | gimli::DW_FORM_ref1 | gimli::DW_FORM_flag => {
+            input.read_u8().map(u64::from)
+        }
+        gimli::DW_FORM_data2 | gimli::DW_FORM_ref2 => input.read_u16().map(u64::from),
+        gimli::DW_FORM_data4 | gimli::DW_FORM_ref4 => input.read_u32().map(u64::from),
+        gimli::DW_FORM_data8 | gimli::DW_FORM_ref8 | gimli::DW_FORM_ref_sig8 => input.read_u64(),
+        gimli::DW_FORM_udata | gimli::DW_FORM_ref_udata => input.read_uleb128(),
+        gimli::DW_FORM_sdata => input.read_sleb128().map(|value| value as u64),
+        gimli::DW_FORM_sec_offset => input.read_offset(format).map(|offset| offset.into_u64()),
+        _ => Err(gimli::Error::UnknownForm),
+    }
+}
+
+// Slices the section with bounds checking
+fn range_from(mut input: IndexReader, offset: usize) -> gimli::Result<IndexReader> {
+    input.skip(offset)?;
+    Ok(input)
+}
+
+fn range(input: IndexReader, start: usize, end: usize) -> gimli::Result<IndexReader> {
+    let mut input = range_from(input, start)?;
+    input.split(end.saturating_sub(start))
+}
+
+// The hash function of .debug_names, which is defined by DJB
+fn debug_names_hash(name: &str) -> u32 {
+    name.bytes().fold(5381u32, |hash, c| {
+        hash.wrapping_mul(33).wrapping_add(u32::from(c))
+    })
+}
@@ -0,0 +1,119 @@
+use log::warn;
+use object::{Object, ObjectSegment};
+use std::fs;
+use std::os::unix::fs::FileExt;
+
+use super::memory::{Memory, WritableMemory};
+
+const PAGE_SIZE: usize = 0x1000;
+
+// The memory of a running process, read and written through `/proc/PID/mem`
+pub struct ProcessMemory {
+    mem: fs::File,
+    // where position independent executables are loaded, which is added to the addresses in DWARF
+    load_bias: usize,
+}
+
+impl ProcessMemory {
+    pub fn attach(pid: u32, elf_path: &str) -> Result<Self, String> {
+        Self::open(pid, elf_path, false)
+    }
+
+    pub fn attach_writable(pid: u32, elf_path: &str) -> Result<Self, String> {
+        Self::open(pid, elf_path, true)
+    }
+
+    fn open(pid: u32, elf_path: &str, writable: bool) -> Result<Self, String> {
+        let mem_path = format!("/proc/{}/mem", pid);
+        let mem = fs::OpenOptions::new()
+            .read(true)
+            .write(writable)
+            .open(&mem_path)
+            .map_err(|err| format!("{}: {}", mem_path, err))?;
+        let load_bias = Self::find_load_bias(pid, elf_path)?;
+        Ok(Self { mem, load_bias })
+    }
+
+    // The first mapping of the executable in `/proc/PID/maps` is its lowest PT_LOAD segment
+    fn find_load_bias(pid: u32, elf_path: &str) -> Result<usize, String> {
+        let lowest_address = {
+            let file = fs::File::open(elf_path).map_err(|err| format!("{}: {}", elf_path, err))?;
+            let mmap = unsafe { memmap::Mmap::map(&file) }
+                .map_err(|err| format!("{}: {}", elf_path, err))?;
+            let object =
+                object::File::parse(&mmap).map_err(|err| format!("{}: {}", elf_path, err))?;
+            let lowest_address = object.segments().map(|segment| segment.address()).min();
+            lowest_address.unwrap_or(0) as usize & !(PAGE_SIZE - 1)
+        };
+
+        let maps_path = format!("/proc/{}/maps", pid);
+        let maps =
+            fs::read_to_string(&maps_path).map_err(|err| format!("{}: {}", maps_path, err))?;
+        let mappings: Vec<(usize, &str)> = maps.lines().filter_map(parse_mapping).collect();
+        let start = |path: &str| {
+            mappings
+                .iter()
+                .find(|(_, mapped_path)| *mapped_path == path)
+                .map(|(start, _)| *start)
+        };
+
+        let elf_path = fs::canonicalize(elf_path)
+            .map_err(|err| format!("{}: {}", elf_path, err))?
+            .to_string_lossy()
+            .into_owned();
+        if let Some(start) = start(&elf_path) {
+            return Ok(start.wrapping_sub(lowest_address));
+        }
+        // the ELF may be an unstripped copy of the executable
+        let exe_path = format!("/proc/{}/exe", pid);
+        let exe_path = fs::read_link(&exe_path)
+            .map_err(|err| format!("{}: {}", exe_path, err))?
+            .to_string_lossy()
+            .into_owned();
+        match start(&exe_path) {
+            Some(start) => {
+                warn!(
+                    "{} is not mapped in process {}, assuming it is {}",
+                    elf_path, pid, exe_path
+                );
+                Ok(start.wrapping_sub(lowest_address))
+            }
+            None => Err(format!("{} is not mapped in process {}", exe_path, pid)),
+        }
+    }
+}
+
+// `55d0c8a00000-55d0c8a01000 r--p 00000000 fd:01 1234  /path/to/elf`, only for the mappings at offset 0
+fn parse_mapping(line: &str) -> Option<(usize, &str)> {
+    let mut fields = line.splitn(6, ' ');
+    let range = fields.next()?;
+    let offset = fields.nth(1)?;
+    let path = fields.nth(2)?.trim_start();
+    if usize::from_str_radix(offset, 16).ok()? != 0 {
+        return None;
+    }
+    let start = range.split('-').next()?;
+    Some((usize::from_str_radix(start, 16).ok()?, path))
+}
+
+impl Memory for ProcessMemory {
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>> {
+        let mut buffer = vec![0; size];
+        let address = address.wrapping_add(self.load_bias);
+        self.mem.read_exact_at(&mut buffer, address as u64).ok()?;
+        Some(buffer)
+    }
+
+    fn load_bias(&self) -> usize {
+        self.load_bias
+    }
+}
+
+impl WritableMemory for ProcessMemory {
+    fn write(&mut self, address: usize, bytes: &[u8]) -> Result<(), String> {
+        let address = address.wrapping_add(self.load_bias);
+        self.mem
+            .write_all_at(bytes, address as u64)
+            .map_err(|err| format!("failed to write {:#010x}: {}", address, err))
+    }
+}
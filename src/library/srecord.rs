@@ -0,0 +1,47 @@
+use super::intel_hex::parse_bytes;
+
+// The bytes of the S1, S2 and S3 records of Motorola S-record at their addresses,
+// in the order of the records
+pub fn from_srecord(srecord: &str) -> Result<Vec<(usize, Vec<u8>)>, String> {
+    let mut segments = Vec::new();
+    for (i, line) in srecord.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record =
+            parse_record(line).map_err(|message| format!("line {}: {}", i + 1, message))?;
+        if let Some(segment) = record {
+            segments.push(segment);
+        }
+    }
+    Ok(segments)
+}
+
+// `STCC` followed by the address, the data and the checksum, where the type T decides the size of the address.
+// None for the records without data, like headers, counts and start addresses.
+fn parse_record(line: &str) -> Result<Option<(usize, Vec<u8>)>, String> {
+    let invalid = || format!("not an S-record: {}", line);
+    let record_type = line.strip_prefix('S').and_then(|line| line.chars().next());
+    let address_size = match record_type.ok_or_else(invalid)? {
+        '1' => 2,
+        '2' => 3,
+        '3' => 4,
+        '0' | '5' | '6' | '7' | '8' | '9' => return Ok(None),
+        _ => return Err(invalid()),
+    };
+    let bytes = parse_bytes(&line[2..]).ok_or_else(invalid)?;
+    if bytes.len() < address_size + 2 || bytes.len() != bytes[0] as usize + 1 {
+        return Err(format!("invalid length of the record: {}", line));
+    }
+    if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xff {
+        return Err(format!("invalid checksum of the record: {}", line));
+    }
+    let address = bytes[1..=address_size]
+        .iter()
+        .fold(0, |address, byte| address << 8 | *byte as usize);
+    Ok(Some((
+        address,
+        bytes[address_size + 1..bytes.len() - 1].to_vec(),
+    )))
+}
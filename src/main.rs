@@ -1,18 +1,836 @@
 use env_logger::Env;
-use std::env;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, fs, process, thread};
 
-use troll::infrastructure::fromelf::stdout::FromElfStdOut;
+use troll::domain::budget::Budget;
+use troll::domain::calibration::{Calibration, Parameter};
+use troll::domain::expression::Expression;
+use troll::domain::linker_map_matcher::ObjectGrouping;
+use troll::domain::union_rules::UnionRules;
+use troll::domain::variable_path::VariablePath;
+use troll::infrastructure::fromelf::stdout::{
+    BudgetStdOut, ExpressionStdOut, FromElfStdOut, ImageMismatchStdOut, LinkerMapStdOut,
+    ObjectGroupStdOut, OdrStdOut, ReportFormat, SampleFormat, SampleStdOut, TypeStdOut,
+};
+use troll::library::core_file::CoreFile;
+use troll::library::elf_image::ElfImage;
+use troll::library::elf_section;
+use troll::library::flash_image::{FlashImage, LoadAddressMemory};
+use troll::library::intel_hex;
+use troll::library::linker_map::LinkerMap;
+use troll::library::memory::{Memory, RawImage, WritableMemory};
+use troll::library::process_memory::ProcessMemory;
+use troll::usecase::check_budget::CheckBudgetUsecase;
+use troll::usecase::check_linker_map::CheckLinkerMapUsecase;
+use troll::usecase::check_odr::CheckOdrUsecase;
+use troll::usecase::decode_global_variables::DecodeGlobalVariablesUsecase;
 use troll::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+use troll::usecase::evaluate_expression::EvaluateExpressionUsecase;
+use troll::usecase::group_global_variables::GroupGlobalVariablesUsecase;
+use troll::usecase::list_types::ListTypesUsecase;
+use troll::usecase::lookup_variable::LookupVariableUsecase;
+use troll::usecase::sample_variables::SampleVariablesUsecase;
+use troll::usecase::set_variable::SetVariableUsecase;
+use troll::usecase::verify_image::VerifyImageUsecase;
 
 fn main() {
     env_logger::from_env(Env::default().default_filter_or("warn")).init();
-    for path in env::args().skip(1) {
-        dump_global_variables(path);
+
+    let mut elf_paths = Vec::new();
+    let mut debug_dirs = Vec::new();
+    let mut variable_paths = Vec::new();
+    let mut expressions = Vec::new();
+    let mut source = false;
+    let mut map_path = None;
+    let mut grouping = None;
+    let mut budget_path = None;
+    let mut format = None;
+    let mut image_path = None;
+    let mut base = None;
+    let mut core_path = None;
+    let mut pid = None;
+    let mut interval = None;
+    let mut count = None;
+    let mut params_path = None;
+    let mut output_path = None;
+    let mut hex_path = None;
+    let mut pointer_depth = 0;
+    let mut unions_path = None;
+    let mut args = env::args().skip(1).peekable();
+    let command = args.next_if(|arg| {
+        [
+            "types",
+            "check-odr",
+            "check-map",
+            "check-budget",
+            "check-image",
+            "sample",
+            "set",
+            "patch",
+        ]
+        .contains(&arg.as_str())
+    });
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--debug-dir" => match args.next() {
+                Some(debug_dir) => debug_dirs.push(debug_dir),
+                None => usage_error("--debug-dir requires a directory"),
+            },
+            "--var" => match args.next().map(|path| path.parse::<VariablePath>()) {
+                Some(Ok(path)) => variable_paths.push(path),
+                Some(Err(message)) => usage_error(&message),
+                None => usage_error("--var requires a variable path"),
+            },
+            "--eval" => match args.next() {
+                Some(expression) => expressions.push(expression),
+                None => usage_error("--eval requires an expression"),
+            },
+            "--source" => source = true,
+            "--map" => match args.next() {
+                Some(path) => map_path = Some(path),
+                None => usage_error("--map requires a map file"),
+            },
+            "--group-by" => match args.next().as_deref() {
+                Some("object") => grouping = Some(ObjectGrouping::Object),
+                Some("archive") => grouping = Some(ObjectGrouping::Archive),
+                _ => usage_error("--group-by requires object or archive"),
+            },
+            "--budget" => match args.next() {
+                Some(path) => budget_path = Some(path),
+                None => usage_error("--budget requires a budget file"),
+            },
+            "--image" => match args.next() {
+                Some(path) => image_path = Some(path),
+                None => usage_error("--image requires a memory dump"),
+            },
+            "--base" => match args.next().as_deref().map(parse_address) {
+                Some(Some(address)) => base = Some(address),
+                _ => usage_error("--base requires an address"),
+            },
+            "--core" => match args.next() {
+                Some(path) => core_path = Some(path),
+                None => usage_error("--core requires a core file"),
+            },
+            "--pid" => match args.next().map(|pid| pid.parse()) {
+                Some(Ok(id)) => pid = Some(id),
+                _ => usage_error("--pid requires a process ID"),
+            },
+            "--interval" => match args.next().map(|interval| interval.parse()) {
+                Some(Ok(milliseconds)) => interval = Some(Duration::from_millis(milliseconds)),
+                _ => usage_error("--interval requires milliseconds"),
+            },
+            "--count" => match args.next().map(|count| count.parse()) {
+                Some(Ok(samples)) => count = Some(samples),
+                _ => usage_error("--count requires a number of samples"),
+            },
+            "--params" => match args.next() {
+                Some(path) => params_path = Some(path),
+                None => usage_error("--params requires a parameter file"),
+            },
+            "--output" => match args.next() {
+                Some(path) => output_path = Some(path),
+                None => usage_error("--output requires an ELF"),
+            },
+            "--hex" => match args.next() {
+                Some(path) => hex_path = Some(path),
+                None => usage_error("--hex requires an Intel HEX file"),
+            },
+            "--follow-pointers" => match args.next().map(|depth| depth.parse()) {
+                Some(Ok(depth)) => pointer_depth = depth,
+                _ => usage_error("--follow-pointers requires a depth"),
+            },
+            "--unions" => match args.next() {
+                Some(path) => unions_path = Some(path),
+                None => usage_error("--unions requires a union rules file"),
+            },
+            "--format" => match args.next() {
+                Some(name) => format = Some(name),
+                None => usage_error("--format requires a format"),
+            },
+            _ => elf_paths.push(arg),
+        }
+    }
+
+    // options are refused in the modes which would ignore them
+    let mode = command.as_deref().unwrap_or("");
+    let options: [(&str, bool, &[&str]); 7] = [
+        ("--format", format.is_some(), &["check-budget", "sample"]),
+        ("--interval", interval.is_some(), &["sample"]),
+        ("--count", count.is_some(), &["sample"]),
+        ("--budget", budget_path.is_some(), &["check-budget"]),
+        ("--params", params_path.is_some(), &["patch"]),
+        ("--output", output_path.is_some(), &["patch"]),
+        ("--hex", hex_path.is_some(), &["patch"]),
+    ];
+    for (option, given, modes) in options {
+        if given && !modes.contains(&mode) {
+            usage_error(&format!("{} requires {}", option, modes.join(" or ")));
+        }
+    }
+    if grouping.is_some() && map_path.is_none() {
+        usage_error("--group-by requires --map");
+    }
+    let mut memory_sources = Vec::new();
+    match (image_path, base) {
+        (Some(image_path), base) => memory_sources.push(MemorySource::Image(image_path, base)),
+        (None, Some(_)) => usage_error("--base requires --image"),
+        (None, None) => (),
+    }
+    memory_sources.extend(core_path.map(MemorySource::Core));
+    memory_sources.extend(pid.map(MemorySource::Process));
+    if memory_sources.len() > 1 {
+        usage_error("--image, --core and --pid cannot be used together");
+    }
+    let memory_source = memory_sources.pop();
+    if pointer_depth > 0 && memory_source.is_none() {
+        usage_error("--follow-pointers requires --image, --core or --pid");
+    }
+    if unions_path.is_some() && memory_source.is_none() {
+        usage_error("--unions requires --image, --core or --pid");
+    }
+    let union_rules = unions_path
+        .map(|path| read_union_rules(&path))
+        .unwrap_or_default();
+    let linker_map = map_path.map(|path| match LinkerMap::from_file(&path) {
+        Ok(linker_map) => linker_map,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            process::exit(1);
+        }
+    });
+
+    if command.as_deref() == Some("types") {
+        for path in elf_paths {
+            list_types(path, debug_dirs.clone(), source);
+        }
+    } else if command.as_deref() == Some("check-odr") {
+        let mut consistent = true;
+        for path in elf_paths {
+            consistent &= check_odr(path, debug_dirs.clone());
+        }
+        if !consistent {
+            process::exit(1);
+        }
+    } else if command.as_deref() == Some("check-map") {
+        let linker_map = match linker_map {
+            Some(linker_map) => linker_map,
+            None => usage_error("check-map requires --map"),
+        };
+        let mut consistent = true;
+        for path in elf_paths {
+            consistent &= check_linker_map(path, debug_dirs.clone(), &linker_map);
+        }
+        if !consistent {
+            process::exit(1);
+        }
+    } else if command.as_deref() == Some("check-budget") {
+        let budget = match budget_path {
+            Some(budget_path) => read_budget(&budget_path),
+            None => usage_error("check-budget requires --budget"),
+        };
+        let format = match format.as_deref() {
+            Some("text") | None => ReportFormat::Text,
+            Some("json") => ReportFormat::Json,
+            _ => usage_error("--format requires text or json"),
+        };
+        let mut within = true;
+        for path in elf_paths {
+            within &= check_budget(path, debug_dirs.clone(), &budget, format);
+        }
+        if !within {
+            process::exit(1);
+        }
+    } else if command.as_deref() == Some("check-image") {
+        let memory_source = match memory_source {
+            Some(memory_source @ MemorySource::Image(..)) => memory_source,
+            _ => usage_error("check-image requires --image"),
+        };
+        let mut consistent = true;
+        for path in elf_paths {
+            let image = memory_source.open_flash(&path);
+            consistent &= verify_image(path, debug_dirs.clone(), image.as_ref());
+        }
+        if !consistent {
+            process::exit(1);
+        }
+    } else if command.as_deref() == Some("sample") {
+        let format = match format.as_deref() {
+            Some("csv") | None => SampleFormat::Csv,
+            Some("json") => SampleFormat::Json,
+            _ => usage_error("--format requires csv or json"),
+        };
+        let memory_source = match memory_source {
+            Some(memory_source @ MemorySource::Process(_)) => memory_source,
+            _ => usage_error("sample requires --pid"),
+        };
+        if variable_paths.is_empty() {
+            usage_error("sample requires --var");
+        }
+        let path = match elf_paths.as_slice() {
+            [path] => path.clone(),
+            _ => usage_error("sample requires an ELF"),
+        };
+        let memory = memory_source.open(&path);
+        sample_variables(
+            path,
+            debug_dirs,
+            &variable_paths,
+            memory.as_ref(),
+            interval.unwrap_or(Duration::from_secs(1)),
+            count,
+            format,
+        );
+    } else if command.as_deref() == Some("set") {
+        let (assignments, elf_paths): (Vec<String>, Vec<String>) =
+            elf_paths.into_iter().partition(|arg| arg.contains('='));
+        if assignments.is_empty() {
+            usage_error("set requires PATH=VALUE");
+        }
+        let parameters: Vec<Parameter> = assignments
+            .iter()
+            .map(|assignment| match assignment.parse::<Parameter>() {
+                Ok(parameter) => parameter,
+                Err(message) => usage_error(&message),
+            })
+            .collect();
+        let path = match elf_paths.as_slice() {
+            [path] => path.clone(),
+            _ => usage_error("set requires an ELF"),
+        };
+        let set = match memory_source {
+            // the image is saved only if all the values are set
+            Some(MemorySource::Image(image_path, Some(base))) => {
+                let mut image = RawImage::from_file(&image_path, base).unwrap_or_else(|message| {
+                    eprintln!("error: {}", message);
+                    process::exit(1);
+                });
+                let set = set_variables(path, debug_dirs, source, &parameters, &mut image);
+                if let Some(Err(message)) = set.then(|| image.save(&image_path)) {
+                    eprintln!("error: {}", message);
+                    process::exit(1);
+                }
+                set
+            }
+            Some(MemorySource::Process(pid)) => {
+                let mut process =
+                    ProcessMemory::attach_writable(pid, &path).unwrap_or_else(|message| {
+                        eprintln!("error: {}", message);
+                        process::exit(1);
+                    });
+                set_variables(path, debug_dirs, source, &parameters, &mut process)
+            }
+            _ => usage_error("set requires --image and --base, or --pid"),
+        };
+        if !set {
+            process::exit(1);
+        }
+    } else if command.as_deref() == Some("patch") {
+        let calibration = match params_path {
+            Some(params_path) => read_calibration(&params_path),
+            None => usage_error("patch requires --params"),
+        };
+        let output_path = match output_path {
+            Some(output_path) => output_path,
+            None => usage_error("patch requires --output"),
+        };
+        let path = match elf_paths.as_slice() {
+            [path] => path.clone(),
+            _ => usage_error("patch requires an ELF"),
+        };
+        // the original is left unchanged
+        let canonical = |path: &str| fs::canonicalize(path).ok();
+        if canonical(&path).is_some() && canonical(&path) == canonical(&output_path) {
+            usage_error("--output must not be the ELF");
+        }
+        let patched = patch_elf(
+            path,
+            debug_dirs,
+            source,
+            &calibration,
+            &output_path,
+            hex_path.as_deref(),
+        );
+        if !patched {
+            process::exit(1);
+        }
+    } else if variable_paths.is_empty() && expressions.is_empty() {
+        for path in elf_paths {
+            if let Some(memory_source) = &memory_source {
+                let memory = memory_source.open(&path);
+                decode_global_variables(
+                    path,
+                    debug_dirs.clone(),
+                    source,
+                    memory.as_ref(),
+                    pointer_depth,
+                    &union_rules,
+                );
+                continue;
+            }
+            match &linker_map {
+                Some(linker_map) => group_global_variables(
+                    path,
+                    debug_dirs.clone(),
+                    source,
+                    grouping.unwrap_or(ObjectGrouping::Object),
+                    linker_map,
+                ),
+                None => dump_global_variables(path, debug_dirs.clone(), source),
+            }
+        }
+    } else {
+        let mut found = true;
+        for path in elf_paths {
+            let memory = memory_source
+                .as_ref()
+                .map(|memory_source| memory_source.open(&path));
+            for variable_path in variable_paths.iter() {
+                found &= lookup_variable(
+                    path.clone(),
+                    debug_dirs.clone(),
+                    source,
+                    variable_path,
+                    memory.as_deref(),
+                    pointer_depth,
+                    &union_rules,
+                );
+            }
+            for expression in expressions.iter() {
+                found &= evaluate_expression(
+                    path.clone(),
+                    debug_dirs.clone(),
+                    expression,
+                    memory.as_deref(),
+                );
+            }
+        }
+        if !found {
+            process::exit(1);
+        }
+    }
+}
+
+// Where the values of variables are read from, where images without base addresses
+// are Intel HEX or S-record
+enum MemorySource {
+    Image(String, Option<usize>),
+    Core(String),
+    Process(u32),
+}
+
+impl MemorySource {
+    fn open(&self, elf_path: &str) -> Box<dyn Memory> {
+        let memory: Result<Box<dyn Memory>, String> = match self {
+            MemorySource::Image(path, Some(base)) => {
+                RawImage::from_file(path, *base).map(|image| Box::new(image) as Box<dyn Memory>)
+            }
+            // flash images have the initial values of .data at their load addresses
+            MemorySource::Image(path, None) => FlashImage::from_file(path)
+                .and_then(|image| LoadAddressMemory::new(image, elf_path))
+                .map(|image| Box::new(image) as Box<dyn Memory>),
+            MemorySource::Core(path) => {
+                CoreFile::open(path, elf_path).map(|core| Box::new(core) as Box<dyn Memory>)
+            }
+            MemorySource::Process(pid) => ProcessMemory::attach(*pid, elf_path)
+                .map(|process| Box::new(process) as Box<dyn Memory>),
+        };
+        match memory {
+            Ok(memory) => memory,
+            Err(message) => {
+                eprintln!("error: {}", message);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+impl MemorySource {
+    // Opens images as flash images even if they are raw dumps
+    fn open_flash(&self, elf_path: &str) -> Box<dyn Memory> {
+        let memory = match self {
+            MemorySource::Image(path, Some(base)) => RawImage::from_file(path, *base)
+                .and_then(|image| LoadAddressMemory::new(image, elf_path)),
+            _ => return self.open(elf_path),
+        };
+        match memory {
+            Ok(memory) => Box::new(memory),
+            Err(message) => {
+                eprintln!("error: {}", message);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn dump_global_variables(elf_path: String, debug_dirs: Vec<String>, source: bool) {
+    let mut usecase = DumpGlobalVariablesUsecase::new()
+        .debug_dirs(debug_dirs)
+        .source(source);
+    let global_variables = usecase.dump_global_variables(elf_path.clone());
+    FromElfStdOut::new(global_variables, source, is_little_endian(&elf_path)).print();
+}
+
+fn decode_global_variables(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    source: bool,
+    memory: &dyn Memory,
+    pointer_depth: usize,
+    union_rules: &UnionRules,
+) {
+    let global_variables = DecodeGlobalVariablesUsecase::new()
+        .debug_dirs(debug_dirs)
+        .source(source)
+        .pointer_depth(pointer_depth)
+        .union_rules(union_rules.clone())
+        .decode_global_variables(elf_path.clone(), memory);
+    match global_variables {
+        Ok(global_variables) => {
+            FromElfStdOut::new(global_variables, source, is_little_endian(&elf_path)).print()
+        }
+        Err(message) => eprintln!("error: {}", message),
+    }
+}
+
+fn group_global_variables(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    source: bool,
+    grouping: ObjectGrouping,
+    linker_map: &LinkerMap,
+) {
+    let groups = GroupGlobalVariablesUsecase::new()
+        .debug_dirs(debug_dirs)
+        .source(source)
+        .grouping(grouping)
+        .group_global_variables(elf_path.clone(), linker_map);
+    ObjectGroupStdOut::new(groups, source, is_little_endian(&elf_path)).print();
+}
+
+fn lookup_variable(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    source: bool,
+    variable_path: &VariablePath,
+    memory: Option<&dyn Memory>,
+    pointer_depth: usize,
+    union_rules: &UnionRules,
+) -> bool {
+    let variable_view = match memory {
+        Some(memory) => DecodeGlobalVariablesUsecase::new()
+            .debug_dirs(debug_dirs)
+            .source(source)
+            .pointer_depth(pointer_depth)
+            .union_rules(union_rules.clone())
+            .decode_variable(elf_path.clone(), variable_path, memory),
+        None => LookupVariableUsecase::new()
+            .debug_dirs(debug_dirs)
+            .source(source)
+            .lookup_variable(elf_path.clone(), variable_path),
+    };
+    match variable_view {
+        Ok(variable_view) => {
+            FromElfStdOut::new(vec![variable_view], source, is_little_endian(&elf_path)).print();
+            true
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            false
+        }
+    }
+}
+
+// Evaluates the expression, where the values of objects are read from the memory if given
+fn evaluate_expression(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    source: &str,
+    memory: Option<&dyn Memory>,
+) -> bool {
+    let result = source.parse::<Expression>().and_then(|expression| {
+        let mut usecase = EvaluateExpressionUsecase::new().debug_dirs(debug_dirs);
+        match memory {
+            Some(memory) => usecase.decode_expression(elf_path.clone(), &expression, memory),
+            None => usecase
+                .evaluate_expression(elf_path.clone(), &expression)
+                .map(|value| (value, None)),
+        }
+    });
+    match result {
+        Ok((value, decoded)) => {
+            let little_endian = is_little_endian(&elf_path);
+            ExpressionStdOut::new(String::from(source), value, decoded, little_endian).print();
+            true
+        }
+        Err(message) => {
+            eprintln!("error: {}: {}", elf_path, message);
+            false
+        }
+    }
+}
+
+fn list_types(elf_path: String, debug_dirs: Vec<String>, source: bool) {
+    let types = ListTypesUsecase::new()
+        .debug_dirs(debug_dirs)
+        .source(source)
+        .list_types(elf_path);
+    TypeStdOut::new(types, source).print();
+}
+
+// Prints the types and the variables defined differently, and returns whether there is none
+fn check_odr(elf_path: String, debug_dirs: Vec<String>) -> bool {
+    let violations = CheckOdrUsecase::new()
+        .debug_dirs(debug_dirs)
+        .check_odr(elf_path);
+    let consistent = violations.is_empty();
+    OdrStdOut::new(violations).print();
+    consistent
+}
+
+// Prints the variables which disagree with the map file, and returns whether there is none
+fn check_linker_map(elf_path: String, debug_dirs: Vec<String>, linker_map: &LinkerMap) -> bool {
+    let mismatches = CheckLinkerMapUsecase::new()
+        .debug_dirs(debug_dirs)
+        .check_linker_map(elf_path, linker_map);
+    let consistent = mismatches.is_empty();
+    LinkerMapStdOut::new(mismatches).print();
+    consistent
+}
+
+// Prints the variables which differ from the ELF, and returns whether there is none
+fn verify_image(elf_path: String, debug_dirs: Vec<String>, image: &dyn Memory) -> bool {
+    let mismatches = VerifyImageUsecase::new()
+        .debug_dirs(debug_dirs)
+        .verify_image(elf_path, image);
+    match mismatches {
+        Ok(mismatches) => {
+            let consistent = mismatches.is_empty();
+            ImageMismatchStdOut::new(mismatches).print();
+            consistent
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            false
+        }
+    }
+}
+
+fn read_budget(path: &str) -> Budget {
+    let budget = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|budget| budget.parse());
+    match budget {
+        Ok(budget) => budget,
+        Err(message) => {
+            eprintln!("error: {}: {}", path, message);
+            process::exit(1);
+        }
+    }
+}
+
+// Prints the usages of the budget, and returns whether all of them are within the limits
+fn check_budget(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    budget: &Budget,
+    format: ReportFormat,
+) -> bool {
+    let usages = CheckBudgetUsecase::new()
+        .debug_dirs(debug_dirs)
+        .check_budget(elf_path, budget);
+    let usages = match usages {
+        Ok(usages) => usages,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return false;
+        }
+    };
+    let within = usages.iter().all(|usage| !usage.exceeded());
+    BudgetStdOut::new(usages, format).print();
+    within
+}
+
+// Prints the values of the variables every interval until the count of samples,
+// or until none of them can be read, like when the process exits
+fn sample_variables(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    variable_paths: &[VariablePath],
+    memory: &dyn Memory,
+    interval: Duration,
+    count: Option<usize>,
+    format: SampleFormat,
+) {
+    let sampler = SampleVariablesUsecase::new()
+        .debug_dirs(debug_dirs)
+        .sampler(elf_path, variable_paths);
+    let sampler = match sampler {
+        Ok(sampler) => sampler,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            process::exit(1);
+        }
+    };
+    let names = sampler.names().into_iter().map(String::from).collect();
+    let sample_stdout = SampleStdOut::new(names, format);
+    sample_stdout.print_header();
+
+    // sleeps until the next multiple of the interval not to drift
+    let mut next = Instant::now();
+    let mut samples = 0;
+    while count.map_or(true, |count| samples < count) {
+        if let Some(duration) = next.checked_duration_since(Instant::now()) {
+            thread::sleep(duration);
+        }
+        let values = sampler.sample(memory);
+        if values.iter().all(Option::is_none) {
+            break;
+        }
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        sample_stdout.print(timestamp.as_secs_f64(), &values);
+        samples += 1;
+        // the next sample may be too far to be a time
+        next = match next.checked_add(interval) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+}
+
+// Sets the values in order, and stops at the first one which cannot be set
+fn set_variables<M: WritableMemory>(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    source: bool,
+    parameters: &[Parameter],
+    memory: &mut M,
+) -> bool {
+    let mut usecase = SetVariableUsecase::new()
+        .debug_dirs(debug_dirs)
+        .source(source);
+    let little_endian = is_little_endian(&elf_path);
+    for parameter in parameters {
+        match usecase.set_variable(elf_path.clone(), &parameter.path, &parameter.value, memory) {
+            Ok(variable_view) => {
+                FromElfStdOut::new(vec![variable_view], source, little_endian).print()
+            }
+            Err(message) => {
+                eprintln!("error: {}", message);
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn read_calibration(path: &str) -> Calibration {
+    let calibration = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|calibration| calibration.parse());
+    match calibration {
+        Ok(calibration) => calibration,
+        Err(message) => {
+            eprintln!("error: {}: {}", path, message);
+            process::exit(1);
+        }
+    }
+}
+
+fn read_union_rules(path: &str) -> UnionRules {
+    let union_rules = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|union_rules| union_rules.parse());
+    match union_rules {
+        Ok(union_rules) => union_rules,
+        Err(message) => {
+            eprintln!("error: {}: {}", path, message);
+            process::exit(1);
+        }
+    }
+}
+
+// Writes the ELF with the values patched, and the Intel HEX of it if requested,
+// only if all the values are set
+fn patch_elf(
+    elf_path: String,
+    debug_dirs: Vec<String>,
+    source: bool,
+    calibration: &Calibration,
+    output_path: &str,
+    hex_path: Option<&str>,
+) -> bool {
+    let mut image = match ElfImage::from_file(&elf_path) {
+        Ok(image) => image,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return false;
+        }
+    };
+    let permissions = fs::metadata(&elf_path).unwrap().permissions();
+    if !set_variables(
+        elf_path,
+        debug_dirs,
+        source,
+        &calibration.parameters,
+        &mut image,
+    ) {
+        return false;
+    }
+    let hex = hex_path.map(|_| intel_hex::to_intel_hex(&image.load_segments()));
+    let written = image
+        .save(output_path)
+        .and_then(|_| {
+            fs::set_permissions(output_path, permissions)
+                .map_err(|err| format!("{}: {}", output_path, err))
+        })
+        .and_then(|_| match (hex_path, hex) {
+            (Some(hex_path), Some(hex)) => fs::write(hex_path, hex?)
+                .map_err(|err| format!("failed to write {}: {}", hex_path, err)),
+            _ => Ok(()),
+        });
+    match written {
+        Ok(()) => true,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            false
+        }
+    }
+}
+
+// The byte order of the ELF, which exits if the ELF cannot be read
+fn is_little_endian(elf_path: &str) -> bool {
+    match elf_section::is_little_endian(elf_path) {
+        Ok(little_endian) => little_endian,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            process::exit(1);
+        }
+    }
+}
+
+// `0x20000000` or `536870912`
+fn parse_address(s: &str) -> Option<usize> {
+    match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
     }
 }
 
-fn dump_global_variables(elf_path: String) {
-    let mut usecase = DumpGlobalVariablesUsecase::new();
-    let global_variables = usecase.dump_global_variables(elf_path);
-    FromElfStdOut::new(global_variables).print();
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    eprintln!("usage: troll [--debug-dir DIR]... [--source] [--var PATH]... [--eval EXPRESSION]... ELF...");
+    eprintln!(
+        "       troll [--debug-dir DIR]... [--source] --map MAP [--group-by object|archive] ELF..."
+    );
+    eprintln!("       troll [--debug-dir DIR]... [--source] (--image DUMP --base ADDRESS | --image HEX | --core CORE | --pid PID) [--follow-pointers DEPTH] [--unions RULES] [--var PATH]... [--eval EXPRESSION]... ELF...");
+    eprintln!("       troll types [--debug-dir DIR]... [--source] ELF...");
+    eprintln!("       troll check-odr [--debug-dir DIR]... ELF...");
+    eprintln!("       troll check-map [--debug-dir DIR]... --map MAP ELF...");
+    eprintln!("       troll check-budget [--debug-dir DIR]... --budget BUDGET [--format text|json] ELF...");
+    eprintln!("       troll check-image [--debug-dir DIR]... --image HEX [--base ADDRESS] ELF...");
+    eprintln!("       troll set [--debug-dir DIR]... [--source] (--image DUMP --base ADDRESS | --pid PID) PATH=VALUE... ELF");
+    eprintln!("       troll patch [--debug-dir DIR]... [--source] --params PARAMETERS --output ELF [--hex HEX] ELF");
+    eprintln!("       troll sample [--debug-dir DIR]... --pid PID --var PATH... [--interval MILLISECONDS] [--count N] [--format csv|json] ELF");
+    eprintln!("--unions rules are paths from whole variables, which do not apply to the pointees followed by --follow-pointers");
+    process::exit(1);
 }
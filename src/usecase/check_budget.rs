@@ -0,0 +1,41 @@
+use crate::domain::budget::{Budget, BudgetUsage};
+use crate::library::elf_section;
+use crate::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+
+pub struct CheckBudgetUsecase {
+    debug_dirs: Vec<String>,
+}
+
+impl CheckBudgetUsecase {
+    pub fn new() -> Self {
+        Self {
+            debug_dirs: Vec::new(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Measures the sections and the variables limited by the budget,
+    // reading the compilation units only if some rules limit them
+    pub fn check_budget(
+        &mut self,
+        elf_path: String,
+        budget: &Budget,
+    ) -> Result<Vec<BudgetUsage>, String> {
+        let sections = elf_section::loaded_sections(&elf_path)?;
+        let variable_views = DumpGlobalVariablesUsecase::new()
+            .debug_dirs(self.debug_dirs.clone())
+            .source(budget.has_units())
+            .dump_global_variables(elf_path);
+        Ok(budget.check(&sections, &variable_views))
+    }
+}
+
+impl Default for CheckBudgetUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,38 @@
+use crate::domain::linker_map_matcher::{LinkerMapMatcher, LinkerMapMismatch};
+use crate::library::linker_map::LinkerMap;
+use crate::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+
+pub struct CheckLinkerMapUsecase {
+    debug_dirs: Vec<String>,
+}
+
+impl CheckLinkerMapUsecase {
+    pub fn new() -> Self {
+        Self {
+            debug_dirs: Vec::new(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Finds the variables whose addresses or sizes disagree with the map file of the link
+    pub fn check_linker_map(
+        &mut self,
+        elf_path: String,
+        linker_map: &LinkerMap,
+    ) -> Vec<LinkerMapMismatch> {
+        let variable_views = DumpGlobalVariablesUsecase::new()
+            .debug_dirs(self.debug_dirs.clone())
+            .dump_global_variables(elf_path);
+        LinkerMapMatcher::new(linker_map).check(&variable_views)
+    }
+}
+
+impl Default for CheckLinkerMapUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,58 @@
+use crate::domain::global_variables_extractor::GlobalVariablesExtractor;
+use crate::domain::odr_checker::{OdrChecker, OdrViolation};
+use crate::domain::type_entry_repository::TypeEntryRepository;
+use crate::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use crate::library::dwarf;
+
+pub struct CheckOdrUsecase {
+    type_entry_repository: TypeEntryRepository,
+    variable_declaration_repository: VariableDeclarationEntryRepository,
+    debug_dirs: Vec<String>,
+}
+
+impl CheckOdrUsecase {
+    pub fn new() -> Self {
+        Self {
+            type_entry_repository: TypeEntryRepository::new(),
+            variable_declaration_repository: VariableDeclarationEntryRepository::new(),
+            debug_dirs: Vec::new(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Finds the types and the variables defined differently in compilation units,
+    // reading the sources to tell where they are defined
+    pub fn check_odr(&mut self, elf_path: String) -> Vec<OdrViolation> {
+        let units = self
+            .debug_dirs
+            .iter()
+            .fold(
+                dwarf::DwarfInfoIntoIterator::new(elf_path),
+                |iter, debug_dir| iter.debug_dir(debug_dir.clone()),
+            )
+            .source(true)
+            .map_units(|infos| GlobalVariablesExtractor::extract_unit(infos));
+
+        let mut global_variables_extractor = GlobalVariablesExtractor::new(
+            &mut self.type_entry_repository,
+            &mut self.variable_declaration_repository,
+        );
+        let global_variables = global_variables_extractor.merge(units);
+
+        OdrChecker::new(
+            &self.type_entry_repository,
+            &self.variable_declaration_repository,
+        )
+        .check(&global_variables)
+    }
+}
+
+impl Default for CheckOdrUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
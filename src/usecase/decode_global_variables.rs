@@ -0,0 +1,121 @@
+use crate::domain::global_variable_view::GlobalVariableView;
+use crate::domain::pointer_follower::PointerFollower;
+use crate::domain::union_rules::UnionRules;
+use crate::domain::value_decoder::ValueDecoder;
+use crate::domain::variable_path::VariablePath;
+use crate::library::elf_section;
+use crate::library::memory::Memory;
+use crate::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+use crate::usecase::lookup_variable::LookupVariableUsecase;
+
+pub struct DecodeGlobalVariablesUsecase {
+    debug_dirs: Vec<String>,
+    source: bool,
+    pointer_depth: usize,
+    union_rules: UnionRules,
+}
+
+impl DecodeGlobalVariablesUsecase {
+    pub fn new() -> Self {
+        Self {
+            debug_dirs: Vec::new(),
+            source: false,
+            pointer_depth: 0,
+            union_rules: UnionRules::default(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    pub fn source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
+    // Expands the variables pointers point to in the memory up to the depth,
+    // where 0 leaves pointers as addresses
+    pub fn pointer_depth(mut self, pointer_depth: usize) -> Self {
+        self.pointer_depth = pointer_depth;
+        self
+    }
+
+    // Shows only the active members of unions matching the rules, instead of all the members
+    pub fn union_rules(mut self, union_rules: UnionRules) -> Self {
+        self.union_rules = union_rules;
+        self
+    }
+
+    // Decodes the values of the variables in the memory,
+    // dropping the variables none of whose members are in it
+    pub fn decode_global_variables(
+        &mut self,
+        elf_path: String,
+        memory: &dyn Memory,
+    ) -> Result<Vec<GlobalVariableView>, String> {
+        let little_endian = elf_section::is_little_endian(&elf_path)?;
+        let mut usecase = DumpGlobalVariablesUsecase::new()
+            .debug_dirs(self.debug_dirs.clone())
+            .source(self.source);
+        let mut variable_views = usecase.dump_global_variables(elf_path);
+        let value_decoder = ValueDecoder::new(memory, little_endian);
+        variable_views.retain_mut(|variable_view| value_decoder.decode(variable_view));
+        let pointer_follower = PointerFollower::new(
+            usecase.global_variable_view_factory(),
+            memory,
+            little_endian,
+            self.pointer_depth,
+        );
+        // inactive members are dropped before their pointers are followed
+        for variable_view in variable_views.iter_mut() {
+            self.union_rules.apply(variable_view);
+            pointer_follower.follow(variable_view);
+        }
+        Ok(variable_views)
+    }
+
+    // Decodes the value of the variable the path resolves to, which is kept without values
+    // if it is not in the memory
+    pub fn decode_variable(
+        &mut self,
+        elf_path: String,
+        path: &VariablePath,
+        memory: &dyn Memory,
+    ) -> Result<GlobalVariableView, String> {
+        let little_endian = elf_section::is_little_endian(&elf_path)?;
+        let mut usecase = LookupVariableUsecase::new()
+            .debug_dirs(self.debug_dirs.clone())
+            .source(self.source);
+        // the rules are paths from whole variables, so the part is taken after they are applied
+        let whole = VariablePath {
+            name: path.name.clone(),
+            elements: Vec::new(),
+        };
+        let mut variable_view = usecase.lookup_variable(elf_path.clone(), &whole)?;
+        ValueDecoder::new(memory, little_endian).decode(&mut variable_view);
+        let decoded = variable_view.clone();
+        self.union_rules.apply(&mut variable_view);
+        // an inactive member given explicitly is shown anyway
+        let mut variable_view = variable_view
+            .find_by_path(&path.elements)
+            .or_else(|| decoded.find_by_path(&path.elements))
+            .ok_or_else(|| format!("{} is not found in {}", path, elf_path))?;
+        variable_view.name = path.to_string();
+        PointerFollower::new(
+            usecase.global_variable_view_factory(),
+            memory,
+            little_endian,
+            self.pointer_depth,
+        )
+        .follow(&mut variable_view);
+        Ok(variable_view)
+    }
+}
+
+impl Default for DecodeGlobalVariablesUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,10 +4,13 @@ use crate::domain::global_variables_extractor::GlobalVariablesExtractor;
 use crate::domain::type_entry_repository::TypeEntryRepository;
 use crate::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
 use crate::library::dwarf;
+use rayon::prelude::*;
 
 pub struct DumpGlobalVariablesUsecase {
     type_entry_repository: TypeEntryRepository,
     variable_declaration_repository: VariableDeclarationEntryRepository,
+    debug_dirs: Vec<String>,
+    source: bool,
 }
 
 impl DumpGlobalVariablesUsecase {
@@ -15,25 +18,64 @@ impl DumpGlobalVariablesUsecase {
         Self {
             type_entry_repository: TypeEntryRepository::new(),
             variable_declaration_repository: VariableDeclarationEntryRepository::new(),
+            debug_dirs: Vec::new(),
+            source: false,
         }
     }
 
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Reads the compilation units and declaration files and lines
+    pub fn source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
     pub fn dump_global_variables(&mut self, elf_path: String) -> Vec<GlobalVariableView> {
-        let iter = dwarf::DwarfInfoIntoIterator::new(elf_path).into_iter();
+        // units are streamed into the extractor without keeping all entries in memory
+        let units = self
+            .debug_dirs
+            .iter()
+            .fold(
+                dwarf::DwarfInfoIntoIterator::new(elf_path),
+                |iter, debug_dir| iter.debug_dir(debug_dir.clone()),
+            )
+            .source(self.source)
+            .map_units(|infos| GlobalVariablesExtractor::extract_unit(infos));
 
         let mut global_variables_extractor = GlobalVariablesExtractor::new(
             &mut self.type_entry_repository,
             &mut self.variable_declaration_repository,
         );
-        let global_variables = global_variables_extractor.extract(iter);
+        let global_variables = global_variables_extractor.merge(units);
 
         let global_variable_view_factory = GlobalVariableViewFactory::new(
             &self.type_entry_repository,
             &self.variable_declaration_repository,
         );
-        global_variables
-            .into_iter()
-            .flat_map(|variable| global_variable_view_factory.from_global_variable(variable))
-            .collect()
+        let mut variable_views: Vec<GlobalVariableView> = global_variables
+            .into_par_iter()
+            .filter_map(|variable| global_variable_view_factory.from_global_variable(variable))
+            .collect();
+        GlobalVariableView::qualify_names(&mut variable_views);
+        variable_views
+    }
+
+    // Builds views of the types extracted by the last dump, like the variables pointers point to
+    pub fn global_variable_view_factory(&self) -> GlobalVariableViewFactory<'_, '_> {
+        GlobalVariableViewFactory::new(
+            &self.type_entry_repository,
+            &self.variable_declaration_repository,
+        )
+    }
+
+    pub fn into_repositories(self) -> (TypeEntryRepository, VariableDeclarationEntryRepository) {
+        (
+            self.type_entry_repository,
+            self.variable_declaration_repository,
+        )
     }
 }
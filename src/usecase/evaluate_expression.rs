@@ -0,0 +1,114 @@
+use crate::domain::expression::Expression;
+use crate::domain::expression_evaluator::{ExpressionEvaluator, ExpressionValue};
+use crate::domain::global_variable_view::GlobalVariableViewBuilder;
+use crate::domain::global_variables_extractor::GlobalVariablesExtractor;
+use crate::domain::type_entry_repository::TypeEntryRepository;
+use crate::domain::value::Value;
+use crate::domain::value_decoder::ValueDecoder;
+use crate::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use crate::domain::variable_path::split_qualified_name;
+use crate::library::memory::Memory;
+use crate::library::{dwarf, elf_section};
+use log::info;
+
+pub struct EvaluateExpressionUsecase {
+    type_entry_repository: TypeEntryRepository,
+    variable_declaration_repository: VariableDeclarationEntryRepository,
+    debug_dirs: Vec<String>,
+}
+
+impl EvaluateExpressionUsecase {
+    pub fn new() -> Self {
+        Self {
+            type_entry_repository: TypeEntryRepository::new(),
+            variable_declaration_repository: VariableDeclarationEntryRepository::new(),
+            debug_dirs: Vec::new(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Evaluates the expression, and decodes the value of the object it designates in the memory
+    pub fn decode_expression(
+        &mut self,
+        elf_path: String,
+        expression: &Expression,
+        memory: &dyn Memory,
+    ) -> Result<(ExpressionValue, Option<Value>), String> {
+        let little_endian = elf_section::is_little_endian(&elf_path)?;
+        let value = self.evaluate_expression(elf_path, expression)?;
+        let decoded = match &value {
+            ExpressionValue::Object {
+                address,
+                size,
+                bit_size,
+                bit_offset,
+                type_view,
+            } => {
+                let mut variable_view = GlobalVariableViewBuilder::new()
+                    .name(expression.to_string())
+                    .address(address.clone())
+                    .size(*size)
+                    .type_view(type_view.clone())
+                    .bit_size(*bit_size)
+                    .bit_offset(*bit_offset)
+                    .build();
+                ValueDecoder::new(memory, little_endian).decode(&mut variable_view);
+                variable_view.value
+            }
+            ExpressionValue::Pointer { .. } | ExpressionValue::Integer(_) => None,
+        };
+        Ok((value, decoded))
+    }
+
+    pub fn evaluate_expression(
+        &mut self,
+        elf_path: String,
+        expression: &Expression,
+    ) -> Result<ExpressionValue, String> {
+        let iter = self.debug_dirs.iter().fold(
+            dwarf::DwarfInfoIntoIterator::new(elf_path.clone()),
+            |iter, debug_dir| iter.debug_dir(debug_dir.clone()),
+        );
+        // only the variable and its types are extracted if the expression refers to a variable
+        let infos = match expression.variable_name() {
+            Some(name) => iter.lookup_variable(split_qualified_name(name).1),
+            None => Vec::new(),
+        };
+
+        let mut global_variables_extractor = GlobalVariablesExtractor::new(
+            &mut self.type_entry_repository,
+            &mut self.variable_declaration_repository,
+        );
+        let global_variables = if infos.is_empty() {
+            info!("extract all variables and types to evaluate {}", expression);
+            let units = self
+                .debug_dirs
+                .iter()
+                .fold(
+                    dwarf::DwarfInfoIntoIterator::new(elf_path),
+                    |iter, debug_dir| iter.debug_dir(debug_dir.clone()),
+                )
+                .map_units(|infos| GlobalVariablesExtractor::extract_unit(infos));
+            global_variables_extractor.merge(units)
+        } else {
+            global_variables_extractor.extract(infos.into_iter())
+        };
+
+        ExpressionEvaluator::new(
+            &self.type_entry_repository,
+            &self.variable_declaration_repository,
+            &global_variables,
+        )
+        .evaluate(expression)
+    }
+}
+
+impl Default for EvaluateExpressionUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
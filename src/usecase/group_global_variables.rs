@@ -0,0 +1,54 @@
+use crate::domain::linker_map_matcher::{LinkerMapMatcher, ObjectGroup, ObjectGrouping};
+use crate::library::linker_map::LinkerMap;
+use crate::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+
+pub struct GroupGlobalVariablesUsecase {
+    debug_dirs: Vec<String>,
+    source: bool,
+    grouping: ObjectGrouping,
+}
+
+impl GroupGlobalVariablesUsecase {
+    pub fn new() -> Self {
+        Self {
+            debug_dirs: Vec::new(),
+            source: false,
+            grouping: ObjectGrouping::Object,
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    pub fn source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
+    // Groups by archive members, or by whole archives
+    pub fn grouping(mut self, grouping: ObjectGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    // Dumps the variables grouped by the objects which the map file attributes them to
+    pub fn group_global_variables(
+        &mut self,
+        elf_path: String,
+        linker_map: &LinkerMap,
+    ) -> Vec<ObjectGroup> {
+        let variable_views = DumpGlobalVariablesUsecase::new()
+            .debug_dirs(self.debug_dirs.clone())
+            .source(self.source)
+            .dump_global_variables(elf_path);
+        LinkerMapMatcher::new(linker_map).group(variable_views, self.grouping)
+    }
+}
+
+impl Default for GroupGlobalVariablesUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,89 @@
+use crate::domain::global_variable_view_factory::GlobalVariableViewFactory;
+use crate::domain::global_variables_extractor::GlobalVariablesExtractor;
+use crate::domain::type_deduplicator::{CanonicalTypeView, TypeDeduplicator};
+use crate::domain::type_entry::TypeEntryKind;
+use crate::domain::type_entry_repository::TypeEntryRepository;
+use crate::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use crate::library::dwarf;
+
+pub struct ListTypesUsecase {
+    type_entry_repository: TypeEntryRepository,
+    variable_declaration_repository: VariableDeclarationEntryRepository,
+    debug_dirs: Vec<String>,
+    source: bool,
+}
+
+impl ListTypesUsecase {
+    pub fn new() -> Self {
+        Self {
+            type_entry_repository: TypeEntryRepository::new(),
+            variable_declaration_repository: VariableDeclarationEntryRepository::new(),
+            debug_dirs: Vec::new(),
+            source: false,
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Reads the declaration files and lines of the types
+    pub fn source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
+    // Lists the named kinds of types in all compilation units, where the types declared
+    // in several units are merged into one if they are identical
+    pub fn list_types(&mut self, elf_path: String) -> Vec<CanonicalTypeView> {
+        let units = self
+            .debug_dirs
+            .iter()
+            .fold(
+                dwarf::DwarfInfoIntoIterator::new(elf_path),
+                |iter, debug_dir| iter.debug_dir(debug_dir.clone()),
+            )
+            .source(self.source)
+            .map_units(|infos| GlobalVariablesExtractor::extract_unit(infos));
+        GlobalVariablesExtractor::new(
+            &mut self.type_entry_repository,
+            &mut self.variable_declaration_repository,
+        )
+        .merge(units);
+
+        let global_variable_view_factory = GlobalVariableViewFactory::new(
+            &self.type_entry_repository,
+            &self.variable_declaration_repository,
+        );
+        TypeDeduplicator::new(&self.type_entry_repository)
+            .deduplicate()
+            .into_iter()
+            // pointers, arrays and qualifiers are shown as the types of variables
+            .filter(|canonical_type| {
+                matches!(
+                    canonical_type.entry.kind,
+                    TypeEntryKind::TypeDef { .. }
+                        | TypeEntryKind::BaseType { .. }
+                        | TypeEntryKind::EnumType { .. }
+                        | TypeEntryKind::StructureType { .. }
+                        | TypeEntryKind::UnionType { .. }
+                )
+            })
+            .filter_map(|canonical_type| {
+                Some(CanonicalTypeView {
+                    type_view: global_variable_view_factory
+                        .type_view_from_type_entry(&canonical_type.entry.id())?,
+                    source: canonical_type.entry.source,
+                    offsets: canonical_type.offsets,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for ListTypesUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
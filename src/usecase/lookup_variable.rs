@@ -0,0 +1,137 @@
+use crate::domain::global_variable_view::GlobalVariableView;
+use crate::domain::global_variable_view_factory::GlobalVariableViewFactory;
+use crate::domain::global_variables_extractor::GlobalVariablesExtractor;
+use crate::domain::type_entry_repository::TypeEntryRepository;
+use crate::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use crate::domain::variable_path::{split_qualified_name, VariablePath};
+use crate::library::dwarf;
+use crate::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+use log::info;
+
+pub struct LookupVariableUsecase {
+    type_entry_repository: TypeEntryRepository,
+    variable_declaration_repository: VariableDeclarationEntryRepository,
+    debug_dirs: Vec<String>,
+    source: bool,
+}
+
+impl LookupVariableUsecase {
+    pub fn new() -> Self {
+        Self {
+            type_entry_repository: TypeEntryRepository::new(),
+            variable_declaration_repository: VariableDeclarationEntryRepository::new(),
+            debug_dirs: Vec::new(),
+            source: false,
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Reads the compilation units and declaration files and lines
+    pub fn source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
+    // Resolves the path to the address, size and type,
+    // extracting only the variable and its types
+    pub fn lookup_variable(
+        &mut self,
+        elf_path: String,
+        path: &VariablePath,
+    ) -> Result<GlobalVariableView, String> {
+        let iter = self
+            .debug_dirs
+            .iter()
+            .fold(
+                dwarf::DwarfInfoIntoIterator::new(elf_path.clone()),
+                |iter, debug_dir| iter.debug_dir(debug_dir.clone()),
+            )
+            .source(self.source);
+        let infos = iter.lookup_variable(path.unqualified_name());
+
+        let variable_views = if infos.is_empty() && !iter.has_name_index() {
+            // e.g. variables in split units
+            info!(
+                "{} is not found by lookup, extract all variables",
+                path.unqualified_name()
+            );
+            let mut usecase = DumpGlobalVariablesUsecase::new()
+                .debug_dirs(self.debug_dirs.clone())
+                .source(self.source);
+            let variable_views = usecase.dump_global_variables(elf_path.clone());
+            // kept to build views of the types later
+            (
+                self.type_entry_repository,
+                self.variable_declaration_repository,
+            ) = usecase.into_repositories();
+            variable_views
+        } else {
+            let mut global_variables_extractor = GlobalVariablesExtractor::new(
+                &mut self.type_entry_repository,
+                &mut self.variable_declaration_repository,
+            );
+            let global_variables = global_variables_extractor.extract(infos.into_iter());
+
+            let global_variable_view_factory = GlobalVariableViewFactory::new(
+                &self.type_entry_repository,
+                &self.variable_declaration_repository,
+            );
+            let mut variable_views: Vec<GlobalVariableView> = global_variables
+                .into_iter()
+                .filter_map(|variable| global_variable_view_factory.from_global_variable(variable))
+                .collect();
+            GlobalVariableView::qualify_names(&mut variable_views);
+            variable_views
+        };
+
+        // variables local to compilation units can be qualified even if the names are unique
+        let matches = |variable_view: &GlobalVariableView| {
+            variable_view.name == path.name
+                || matches!(split_qualified_name(&path.name), (Some(unit), name)
+                    if variable_view.unqualified_name() == name && variable_view.is_local_to(unit))
+        };
+        if !variable_views.iter().any(matches) {
+            // static variables with the same name are qualified and need to be told apart
+            let candidates: Vec<&str> = variable_views
+                .iter()
+                .filter(|variable_view| {
+                    matches!(split_qualified_name(&variable_view.name), (Some(_), name)
+                        if name == path.name)
+                })
+                .map(|variable_view| variable_view.name.as_str())
+                .collect();
+            if candidates.len() > 1 {
+                return Err(format!(
+                    "{} is ambiguous: {}",
+                    path.name,
+                    candidates.join(", ")
+                ));
+            }
+        }
+        let mut variable_view = variable_views
+            .into_iter()
+            .filter(matches)
+            .find_map(|variable_view| variable_view.find_by_path(&path.elements))
+            .ok_or_else(|| format!("{} is not found in {}", path, elf_path))?;
+        variable_view.name = path.to_string();
+        Ok(variable_view)
+    }
+
+    // Builds views of the types extracted by the last lookup, like the variables pointers point to
+    pub fn global_variable_view_factory(&self) -> GlobalVariableViewFactory<'_, '_> {
+        GlobalVariableViewFactory::new(
+            &self.type_entry_repository,
+            &self.variable_declaration_repository,
+        )
+    }
+}
+
+impl Default for LookupVariableUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
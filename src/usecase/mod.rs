@@ -1 +1,12 @@
+pub mod check_budget;
+pub mod check_linker_map;
+pub mod check_odr;
+pub mod decode_global_variables;
 pub mod dump_global_variables;
+pub mod evaluate_expression;
+pub mod group_global_variables;
+pub mod list_types;
+pub mod lookup_variable;
+pub mod sample_variables;
+pub mod set_variable;
+pub mod verify_image;
@@ -0,0 +1,38 @@
+use crate::domain::sampler::Sampler;
+use crate::domain::variable_path::VariablePath;
+use crate::library::elf_section;
+use crate::usecase::lookup_variable::LookupVariableUsecase;
+
+pub struct SampleVariablesUsecase {
+    debug_dirs: Vec<String>,
+}
+
+impl SampleVariablesUsecase {
+    pub fn new() -> Self {
+        Self {
+            debug_dirs: Vec::new(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Resolves the paths once, so that sampling only reads the memory
+    pub fn sampler(&mut self, elf_path: String, paths: &[VariablePath]) -> Result<Sampler, String> {
+        let mut usecase = LookupVariableUsecase::new().debug_dirs(self.debug_dirs.clone());
+        let variable_views = paths
+            .iter()
+            .map(|path| usecase.lookup_variable(elf_path.clone(), path))
+            .collect::<Result<_, _>>()?;
+        let little_endian = elf_section::is_little_endian(&elf_path)?;
+        Ok(Sampler::new(variable_views, little_endian))
+    }
+}
+
+impl Default for SampleVariablesUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
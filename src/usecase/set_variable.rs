@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use crate::domain::global_variable_view::GlobalVariableView;
+use crate::domain::value_decoder::ValueDecoder;
+use crate::domain::value_encoder::ValueEncoder;
+use crate::domain::variable_path::VariablePath;
+use crate::library::elf_section;
+use crate::library::memory::WritableMemory;
+use crate::usecase::lookup_variable::LookupVariableUsecase;
+
+pub struct SetVariableUsecase {
+    debug_dirs: Vec<String>,
+    source: bool,
+    // the endianness and the lookup of the last ELF, reused to set several values to it
+    elf: Option<(String, bool, LookupVariableUsecase)>,
+    // the variables looked up in the last ELF by their names, without reading DWARF again
+    variable_views: HashMap<String, GlobalVariableView>,
+}
+
+impl SetVariableUsecase {
+    pub fn new() -> Self {
+        Self {
+            debug_dirs: Vec::new(),
+            source: false,
+            elf: None,
+            variable_views: HashMap::new(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    pub fn source(mut self, source: bool) -> Self {
+        self.source = source;
+        self
+    }
+
+    // Writes the value to the variable the path resolves to,
+    // and returns the variable with the value read back from the memory.
+    // The variables looked up are kept to set the other values to the same ELF.
+    pub fn set_variable<M: WritableMemory>(
+        &mut self,
+        elf_path: String,
+        path: &VariablePath,
+        value: &str,
+        memory: &mut M,
+    ) -> Result<GlobalVariableView, String> {
+        if !matches!(&self.elf, Some((path, ..)) if *path == elf_path) {
+            let little_endian = elf_section::is_little_endian(&elf_path)?;
+            let usecase = LookupVariableUsecase::new()
+                .debug_dirs(self.debug_dirs.clone())
+                .source(self.source);
+            self.elf = Some((elf_path.clone(), little_endian, usecase));
+            self.variable_views.clear();
+        }
+        let (_, little_endian, usecase) = self.elf.as_mut().unwrap();
+        let little_endian = *little_endian;
+        if !self.variable_views.contains_key(&path.name) {
+            let whole = VariablePath {
+                name: path.name.clone(),
+                elements: Vec::new(),
+            };
+            let variable_view = usecase.lookup_variable(elf_path.clone(), &whole)?;
+            self.variable_views.insert(path.name.clone(), variable_view);
+        }
+        let mut variable_view = self.variable_views[&path.name]
+            .clone()
+            .find_by_path(&path.elements)
+            .ok_or_else(|| format!("{} is not found in {}", path, elf_path))?;
+        variable_view.name = path.to_string();
+        let write = ValueEncoder::new(memory, little_endian).encode(&variable_view, value)?;
+        memory.write(write.address, &write.bytes)?;
+        ValueDecoder::new(memory, little_endian).decode(&mut variable_view);
+        Ok(variable_view)
+    }
+}
+
+impl Default for SetVariableUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::domain::image_verifier::{ImageMismatch, ImageVerifier};
+use crate::library::elf_image::ElfImage;
+use crate::library::elf_section;
+use crate::library::memory::Memory;
+use crate::usecase::dump_global_variables::DumpGlobalVariablesUsecase;
+
+pub struct VerifyImageUsecase {
+    debug_dirs: Vec<String>,
+}
+
+impl VerifyImageUsecase {
+    pub fn new() -> Self {
+        Self {
+            debug_dirs: Vec::new(),
+        }
+    }
+
+    pub fn debug_dirs(mut self, debug_dirs: Vec<String>) -> Self {
+        self.debug_dirs = debug_dirs;
+        self
+    }
+
+    // Compares the initial values of the variables in the ELF with the image
+    pub fn verify_image(
+        &mut self,
+        elf_path: String,
+        image: &dyn Memory,
+    ) -> Result<Vec<ImageMismatch>, String> {
+        let little_endian = elf_section::is_little_endian(&elf_path)?;
+        let elf_image = ElfImage::from_file(&elf_path)?;
+        let variable_views = DumpGlobalVariablesUsecase::new()
+            .debug_dirs(self.debug_dirs.clone())
+            .dump_global_variables(elf_path);
+        Ok(ImageVerifier::new(&elf_image, image, little_endian).verify(variable_views))
+    }
+}
+
+impl Default for VerifyImageUsecase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,186 @@
+extern crate troll;
+
+use std::sync::Arc;
+use troll::domain::budget::*;
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::source_location::SourceLocation;
+use troll::library::dwarf::{CompilationUnit, Location};
+use troll::library::elf_section::ElfSection;
+
+fn view(unit: &str, name: &str, address: Option<usize>, size: usize) -> GlobalVariableView {
+    let compilation_unit = Arc::new(CompilationUnit {
+        name: Some(String::from(unit)),
+        comp_dir: None,
+        producer: None,
+    });
+    GlobalVariableViewBuilder::new()
+        .name(name)
+        .address(address.map(|address| Address::new(Location::new(address))))
+        .size(size)
+        .type_view(TypeView::new_base_type_view("int"))
+        .source(SourceLocation::new(Some(compilation_unit), None, None))
+        .build()
+}
+
+fn section(name: &str, address: usize, size: usize) -> ElfSection {
+    ElfSection {
+        name: String::from(name),
+        address,
+        size,
+    }
+}
+
+#[test]
+fn parse_budget() {
+    let budget = "
+        # RAM
+        .bss <= 48KiB
+        region RAM 0x20000000..0x20010000 <= 0x10000  # the whole RAM
+        unit main.c <= 512
+        g_log_buffer <= 4KiB
+        g_*_buffer <= 1MiB
+    "
+    .parse::<Budget>()
+    .unwrap();
+
+    let expected = vec![
+        BudgetRule {
+            target: BudgetTarget::Section(String::from(".bss")),
+            limit: 48 * 1024,
+        },
+        BudgetRule {
+            target: BudgetTarget::Region {
+                name: String::from("RAM"),
+                start: 0x2000_0000,
+                end: 0x2001_0000,
+            },
+            limit: 0x10000,
+        },
+        BudgetRule {
+            target: BudgetTarget::Unit(String::from("main.c")),
+            limit: 512,
+        },
+        BudgetRule {
+            target: BudgetTarget::Variable(String::from("g_log_buffer")),
+            limit: 4096,
+        },
+        BudgetRule {
+            target: BudgetTarget::Variable(String::from("g_*_buffer")),
+            limit: 1024 * 1024,
+        },
+    ];
+    assert_eq!(budget.rules, expected);
+}
+
+#[test]
+fn parse_invalid_budget() {
+    assert_eq!(
+        ".bss <= 1KiB\n.data < 1KiB\n".parse::<Budget>(),
+        Err(String::from("line 2: no `<=` in the rule: .data < 1KiB"))
+    );
+    assert_eq!(
+        ".bss <= 1kb".parse::<Budget>(),
+        Err(String::from("line 1: invalid size unit: 1kb"))
+    );
+    assert_eq!(
+        "region RAM 0x1000 <= 1KiB".parse::<Budget>(),
+        Err(String::from("line 1: invalid region range: 0x1000"))
+    );
+    assert_eq!(
+        ".bss <= 99999999999G".parse::<Budget>(),
+        Err(String::from("line 1: too large size: 99999999999G"))
+    );
+}
+
+#[test]
+fn check_budget() {
+    let budget = "
+        .bss <= 0x100
+        region RAM 0x1000..0x2000 <= 0x200
+        unit a.c <= 8
+        g_*_buffer <= 0x40
+    "
+    .parse::<Budget>()
+    .unwrap();
+    let sections = vec![
+        section(".data", 0x1000, 0x80),
+        section(".bss", 0x1080, 0x100),
+        section(".rodata", 0x1f80, 0x100),
+    ];
+    let views = vec![
+        view("a.c", "g_log_buffer", Some(0x1080), 0x20),
+        view("a.c", "g_rx_buffer", Some(0x10a0), 0x30),
+        view("b.c", "counter", Some(0x10d0), 4),
+        view("b.c", "g_extern_buffer", None, 0x100),
+    ];
+
+    let used: Vec<(usize, bool)> = budget
+        .check(&sections, &views)
+        .iter()
+        .map(|usage| (usage.used, usage.exceeded()))
+        .collect();
+    assert_eq!(
+        used,
+        vec![(0x100, false), (0x200, false), (0x50, true), (0x50, true)]
+    );
+
+    // a section at the end of the address space
+    let top = section(".top", usize::MAX - 0xf, 0x20);
+    assert_eq!(top.overlap(usize::MAX - 0x7, usize::MAX), 0x7);
+}
+
+#[test]
+fn check_budget_of_static_variables() {
+    let budget = "
+        counter <= 4
+        b.c:counter <= 4
+        *.c:g_* <= 0x100
+    "
+    .parse::<Budget>()
+    .unwrap();
+    assert!(budget.has_units());
+    let static_view = |unit, name, address, size| GlobalVariableView {
+        external: false,
+        ..view(unit, name, Some(address), size)
+    };
+    let mut views = vec![
+        static_view("a.c", "counter", 0x1000, 4),
+        static_view("b.c", "counter", 0x1004, 4),
+        static_view("b.c", "g_rx_buffer", 0x1010, 0x30),
+    ];
+    GlobalVariableView::qualify_names(&mut views);
+
+    let used: Vec<usize> = budget
+        .check(&[], &views)
+        .iter()
+        .map(|usage| usage.used)
+        .collect();
+    assert_eq!(used, vec![8, 4, 0x30]);
+}
+
+#[test]
+fn check_budget_of_variable_patterns() {
+    let budget = "
+        g_*_buffer <= 0x100
+        g_?x_* <= 0x100
+        *a*a*a*a*a*a*a*a*b <= 0x100
+        *buffer* <= 0x100
+    "
+    .parse::<Budget>()
+    .unwrap();
+    let long_name = "a".repeat(64);
+    let views = vec![
+        view("a.c", "g_rx_buffer", Some(0x1000), 0x10),
+        view("a.c", "g_tx_buffer_2", Some(0x1010), 0x20),
+        view("a.c", "g_buffer", Some(0x1030), 0x40),
+        view("a.c", &long_name, Some(0x1070), 0x80),
+    ];
+
+    let used: Vec<usize> = budget
+        .check(&[], &views)
+        .iter()
+        .map(|usage| usage.used)
+        .collect();
+    assert_eq!(used, vec![0x10, 0x30, 0, 0x70]);
+}
@@ -0,0 +1,41 @@
+extern crate troll;
+
+use troll::domain::calibration::*;
+
+#[test]
+fn parse_calibration() {
+    let calibration: Calibration = "\
+# tuned on the bench
+gains.kp = -7
+hoges[1].hoge=0x10  # hexadecimal
+
+mode = FAST
+"
+    .parse()
+    .unwrap();
+    let parameters: Vec<(String, &str)> = calibration
+        .parameters
+        .iter()
+        .map(|parameter| (parameter.path.to_string(), parameter.value.as_str()))
+        .collect();
+    assert_eq!(
+        parameters,
+        vec![
+            (String::from("gains.kp"), "-7"),
+            (String::from("hoges[1].hoge"), "0x10"),
+            (String::from("mode"), "FAST"),
+        ]
+    );
+}
+
+#[test]
+fn parse_invalid_calibration() {
+    assert_eq!(
+        "a = 1\nb 2\n".parse::<Calibration>(),
+        Err(String::from("line 2: no `=` in the parameter: b 2"))
+    );
+    assert_eq!(
+        "a =\n".parse::<Calibration>(),
+        Err(String::from("line 1: no value in the parameter: a ="))
+    );
+}
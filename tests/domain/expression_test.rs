@@ -0,0 +1,279 @@
+extern crate troll;
+
+use troll::domain::expression::*;
+use troll::domain::expression_evaluator::*;
+use troll::domain::global_variable::*;
+use troll::domain::global_variable_view::TypeView;
+use troll::domain::source_location::SourceLocation;
+use troll::domain::type_entry::*;
+use troll::domain::type_entry_repository::TypeEntryRepository;
+use troll::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use troll::domain::variable_path::*;
+use troll::library::dwarf::{CompilationUnit, Location, Offset};
+
+use std::sync::Arc;
+
+#[test]
+fn parse_expression() {
+    let path = |name: &str, elements: Vec<VariablePathElement>| VariablePath {
+        name: String::from(name),
+        elements,
+    };
+    let cases = vec![
+        (
+            "hoges[1].array[0]",
+            Expression::Variable(path(
+                "hoges",
+                vec![
+                    VariablePathElement::Index(1),
+                    VariablePathElement::Member(String::from("array")),
+                    VariablePathElement::Index(0),
+                ],
+            )),
+        ),
+        (
+            "&cfg.mode",
+            Expression::AddressOf(path(
+                "cfg",
+                vec![VariablePathElement::Member(String::from("mode"))],
+            )),
+        ),
+        (
+            "sizeof(Hoge)",
+            Expression::SizeOf(SizeOfOperand::Path(path("Hoge", Vec::new()))),
+        ),
+        (
+            "sizeof(a.c:counter)",
+            Expression::SizeOf(SizeOfOperand::Path(path("a.c:counter", Vec::new()))),
+        ),
+        (
+            "&src/sub-1.c:cfg.mode",
+            Expression::AddressOf(path(
+                "src/sub-1.c:cfg",
+                vec![VariablePathElement::Member(String::from("mode"))],
+            )),
+        ),
+        (
+            "&C:\\src\\sub.c:cfg.mode",
+            Expression::AddressOf(path(
+                "C:\\src\\sub.c:cfg",
+                vec![VariablePathElement::Member(String::from("mode"))],
+            )),
+        ),
+        (
+            "sizeof(unsigned int)",
+            Expression::SizeOf(SizeOfOperand::Type(TypeName::Named(String::from(
+                "unsigned int",
+            )))),
+        ),
+        (
+            "offsetof(struct hoge, array[0x2])",
+            Expression::OffsetOf {
+                type_name: TypeName::Structure(String::from("hoge")),
+                member: vec![
+                    VariablePathElement::Member(String::from("array")),
+                    VariablePathElement::Index(2),
+                ],
+            },
+        ),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(Ok(expected), source.parse::<Expression>(), "{}", source);
+    }
+}
+
+#[test]
+fn parse_invalid_expression() {
+    for source in &[
+        "",
+        "&",
+        "sizeof",
+        "sizeof()",
+        "sizeof(struct)",
+        "offsetof(struct hoge)",
+        "offsetof(struct hoge, [0])",
+        "&cfg.mode)",
+        "*p",
+        "&a.c:",
+    ] {
+        assert!(source.parse::<Expression>().is_err(), "{}", source);
+    }
+}
+
+// struct hoge { int mode; unsigned char flag:1; int array[3]; };
+// typedef struct hoge Hoge;
+// Hoge hoges[2];
+// static int counter; in a.c
+fn evaluate_test(source: &str, expected: Result<ExpressionValue, String>) {
+    let defined_types = vec![
+        TypeEntry::new_base_type_entry(TypeEntryId::new(Offset::new(10)), String::from("int"), 4),
+        TypeEntry::new_base_type_entry(
+            TypeEntryId::new(Offset::new(11)),
+            String::from("unsigned char"),
+            1,
+        ),
+        TypeEntry::new_array_type_entry(
+            TypeEntryId::new(Offset::new(20)),
+            TypeEntryId::new(Offset::new(10)),
+            Some(2),
+        ),
+        TypeEntry::new_structure_type_entry(
+            TypeEntryId::new(Offset::new(30)),
+            Some(String::from("hoge")),
+            20,
+            vec![
+                StructureTypeMemberEntry::from(
+                    MemberEntryBuilder::new_structure()
+                        .name("mode")
+                        .location(0)
+                        .type_ref(TypeEntryId::new(Offset::new(10)))
+                        .build(),
+                ),
+                StructureTypeMemberEntry::from(
+                    MemberEntryBuilder::new_structure()
+                        .name("flag")
+                        .location(4)
+                        .type_ref(TypeEntryId::new(Offset::new(11)))
+                        .bit_size(Some(1))
+                        .bit_offset(Some(7))
+                        .build(),
+                ),
+                StructureTypeMemberEntry::from(
+                    MemberEntryBuilder::new_structure()
+                        .name("array")
+                        .location(8)
+                        .type_ref(TypeEntryId::new(Offset::new(20)))
+                        .build(),
+                ),
+            ],
+        ),
+        TypeEntry::new_typedef_entry(
+            TypeEntryId::new(Offset::new(40)),
+            String::from("Hoge"),
+            TypeEntryId::new(Offset::new(30)),
+        ),
+        TypeEntry::new_array_type_entry(
+            TypeEntryId::new(Offset::new(50)),
+            TypeEntryId::new(Offset::new(40)),
+            Some(1),
+        ),
+    ];
+    let mut type_entry_repository = TypeEntryRepository::new();
+    for defined_type in defined_types {
+        type_entry_repository.save(defined_type);
+    }
+    let variable_declaration_repository = VariableDeclarationEntryRepository::new();
+    let unit = Arc::new(CompilationUnit {
+        name: Some(String::from("a.c")),
+        comp_dir: None,
+        producer: None,
+    });
+    let global_variables = vec![
+        GlobalVariable::new_variable(
+            Some(Address::new(Location::new(0x1000))),
+            String::from("hoges"),
+            TypeEntryId::new(Offset::new(50)),
+        ),
+        GlobalVariable::new_variable(
+            Some(Address::new(Location::new(0x2000))),
+            String::from("counter"),
+            TypeEntryId::new(Offset::new(10)),
+        )
+        .with_external(false)
+        .with_source(SourceLocation::new(Some(unit), None, None)),
+    ];
+
+    let evaluator = ExpressionEvaluator::new(
+        &type_entry_repository,
+        &variable_declaration_repository,
+        &global_variables,
+    );
+    let got = source
+        .parse::<Expression>()
+        .and_then(|expression| evaluator.evaluate(&expression));
+    assert_eq!(expected, got, "{}", source);
+}
+
+#[test]
+fn evaluate_variable() {
+    evaluate_test(
+        "hoges[1].array[2]",
+        Ok(ExpressionValue::Object {
+            address: Some(Address::new(Location::new(0x1024))),
+            size: 4,
+            bit_size: None,
+            bit_offset: None,
+            type_view: TypeView::new_base_type_view("int"),
+        }),
+    );
+    evaluate_test(
+        "hoges[1].flag",
+        Ok(ExpressionValue::Object {
+            address: Some(Address::new(Location::new(0x1018))),
+            size: 1,
+            bit_size: Some(1),
+            bit_offset: Some(7),
+            type_view: TypeView::new_base_type_view("unsigned char"),
+        }),
+    );
+    evaluate_test("hoges[2]", Err(String::from("index out of bounds: [2]")));
+    evaluate_test("hoges[0].none", Err(String::from("no such member: none")));
+}
+
+#[test]
+fn evaluate_address_of() {
+    evaluate_test(
+        "&hoges[1].mode",
+        Ok(ExpressionValue::Pointer {
+            address: Some(Address::new(Location::new(0x1014))),
+            type_view: TypeView::new_pointer_type_view(TypeView::new_base_type_view("int")),
+        }),
+    );
+    evaluate_test(
+        "&a.c:counter",
+        Ok(ExpressionValue::Pointer {
+            address: Some(Address::new(Location::new(0x2000))),
+            type_view: TypeView::new_pointer_type_view(TypeView::new_base_type_view("int")),
+        }),
+    );
+    evaluate_test(
+        "&b.c:counter",
+        Err(String::from("no such variable: b.c:counter")),
+    );
+    evaluate_test(
+        "&hoges[0].flag",
+        Err(String::from(
+            "cannot take address of bit-field: hoges[0].flag",
+        )),
+    );
+}
+
+#[test]
+fn evaluate_size_of() {
+    evaluate_test("sizeof(Hoge)", Ok(ExpressionValue::Integer(20)));
+    evaluate_test("sizeof(struct hoge)", Ok(ExpressionValue::Integer(20)));
+    evaluate_test("sizeof(hoges)", Ok(ExpressionValue::Integer(40)));
+    evaluate_test("sizeof(a.c:counter)", Ok(ExpressionValue::Integer(4)));
+    evaluate_test("sizeof hoges[0].array", Ok(ExpressionValue::Integer(12)));
+    evaluate_test("sizeof(unsigned char)", Ok(ExpressionValue::Integer(1)));
+    evaluate_test(
+        "sizeof(struct fuga)",
+        Err(String::from("no such type: struct fuga")),
+    );
+}
+
+#[test]
+fn evaluate_offset_of() {
+    evaluate_test(
+        "offsetof(struct hoge, array)",
+        Ok(ExpressionValue::Integer(8)),
+    );
+    evaluate_test("offsetof(Hoge, array[1])", Ok(ExpressionValue::Integer(12)));
+    evaluate_test(
+        "offsetof(Hoge, flag)",
+        Err(String::from(
+            "cannot take offset of bit-field: offsetof(Hoge, flag)",
+        )),
+    );
+}
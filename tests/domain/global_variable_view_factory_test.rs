@@ -1,13 +1,15 @@
 extern crate troll;
 
+use std::sync::Arc;
 use troll::domain::global_variable::*;
 use troll::domain::global_variable_view::*;
 use troll::domain::global_variable_view_factory::*;
+use troll::domain::source_location::SourceLocation;
 use troll::domain::type_entry::*;
 use troll::domain::type_entry_repository::TypeEntryRepository;
 use troll::domain::variable_declaration_entry::*;
 use troll::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
-use troll::library::dwarf::{Location, Offset};
+use troll::library::dwarf::{CompilationUnit, Location, Offset};
 
 fn init() {
     let _ = env_logger::builder().is_test(true).try_init();
@@ -106,6 +108,7 @@ fn from_global_variable_pointer() {
         .type_view(TypeView::new_pointer_type_view(
             TypeView::new_base_type_view("int"),
         ))
+        .pointee_type_ref(TypeEntryId::new(Offset::new(71)))
         .build();
 
     from_global_variable_test(defined_types, Vec::new(), global_variable, expected_view);
@@ -339,10 +342,10 @@ fn from_global_variable_structure() {
                 StructureTypeMemberEntry::from(
                     MemberEntryBuilder::new_structure()
                         .name("pohe")
-                        .location(4)
+                        .location(5)
                         .type_ref(TypeEntryId::new(Offset::new(115)))
                         .bit_size(Some(1))
-                        .bit_offset(Some(23))
+                        .bit_offset(Some(0))
                         .build(),
                 ),
             ],
@@ -382,10 +385,10 @@ fn from_global_variable_structure() {
                 .build(),
             GlobalVariableViewBuilder::new()
                 .name("pohe")
-                .address(Some(Address::new(Location::new(16436))))
+                .address(Some(Address::new(Location::new(16437))))
                 .size(4)
                 .bit_size(Some(1))
-                .bit_offset(Some(23))
+                .bit_offset(Some(0))
                 .type_view(TypeView::new_base_type_view("unsigned int"))
                 .build(),
         ])
@@ -413,7 +416,7 @@ fn from_global_variable_union() {
                         .name("price")
                         .type_ref(TypeEntryId::new(Offset::new(93)))
                         .bit_size(Some(8))
-                        .bit_offset(Some(24))
+                        .bit_offset(Some(0))
                         .build(),
                 ),
             ],
@@ -445,7 +448,7 @@ fn from_global_variable_union() {
                 .address(Some(Address::new(Location::new(16428))))
                 .size(4)
                 .bit_size(Some(8))
-                .bit_offset(Some(24))
+                .bit_offset(Some(0))
                 .type_view(TypeView::new_base_type_view("int"))
                 .build(),
         ])
@@ -454,6 +457,71 @@ fn from_global_variable_union() {
     from_global_variable_test(defined_types, Vec::new(), global_variable, expected_view);
 }
 
+#[test]
+fn from_global_variable_union_member_location() {
+    let defined_types = vec![
+        TypeEntry::new_union_type_entry(
+            TypeEntryId::new(Offset::new(45)),
+            Some(String::from("word")),
+            4,
+            vec![
+                UnionTypeMemberEntry::from(
+                    MemberEntryBuilder::new_union()
+                        .name("value")
+                        .type_ref(TypeEntryId::new(Offset::new(86)))
+                        .build(),
+                ),
+                UnionTypeMemberEntry::from(
+                    MemberEntryBuilder::new_union()
+                        .name("high")
+                        .location(2)
+                        .type_ref(TypeEntryId::new(Offset::new(93)))
+                        .build(),
+                ),
+            ],
+        ),
+        TypeEntry::new_base_type_entry(
+            TypeEntryId::new(Offset::new(86)),
+            String::from("unsigned int"),
+            4,
+        ),
+        TypeEntry::new_base_type_entry(
+            TypeEntryId::new(Offset::new(93)),
+            String::from("short unsigned int"),
+            2,
+        ),
+    ];
+
+    let global_variable = GlobalVariable::new_variable(
+        Some(Address::new(Location::new(16428))),
+        String::from("word"),
+        TypeEntryId::new(Offset::new(45)),
+    );
+
+    let expected_view = GlobalVariableViewBuilder::new()
+        .name("word")
+        .address(Some(Address::new(Location::new(16428))))
+        .size(4)
+        .type_view(TypeView::new_union_type_view(Some("word")))
+        .children(vec![
+            GlobalVariableViewBuilder::new()
+                .name("value")
+                .address(Some(Address::new(Location::new(16428))))
+                .size(4)
+                .type_view(TypeView::new_base_type_view("unsigned int"))
+                .build(),
+            GlobalVariableViewBuilder::new()
+                .name("high")
+                .address(Some(Address::new(Location::new(16430))))
+                .size(2)
+                .type_view(TypeView::new_base_type_view("short unsigned int"))
+                .build(),
+        ])
+        .build();
+
+    from_global_variable_test(defined_types, Vec::new(), global_variable, expected_view);
+}
+
 #[test]
 fn from_global_variable_anonymous_union_structure() {
     let defined_types = vec![
@@ -576,6 +644,7 @@ fn from_global_variable_function_pointer() {
         .type_view(TypeView::new_pointer_type_view(
             TypeView::new_function_type_view(),
         ))
+        .pointee_type_ref(TypeEntryId::new(Offset::new(45)))
         .build();
 
     from_global_variable_test(defined_types, Vec::new(), global_variable, expected_view);
@@ -681,6 +750,7 @@ fn from_global_variable_complex_structure() {
                         .type_view(TypeView::new_pointer_type_view(
                             TypeView::new_base_type_view("int"),
                         ))
+                        .pointee_type_ref(TypeEntryId::new(Offset::new(161)))
                         .build(),
                     GlobalVariableViewBuilder::new()
                         .name("array")
@@ -761,6 +831,7 @@ fn from_global_variable_complex_structure() {
                         .type_view(TypeView::new_pointer_type_view(
                             TypeView::new_base_type_view("int"),
                         ))
+                        .pointee_type_ref(TypeEntryId::new(Offset::new(161)))
                         .build(),
                     GlobalVariableViewBuilder::new()
                         .name("array")
@@ -896,3 +967,71 @@ fn from_global_variable_volatile() {
 
     from_global_variable_test(defined_types, Vec::new(), global_variable, expected_view);
 }
+
+#[test]
+fn from_global_variable_source() {
+    let unit = Arc::new(CompilationUnit {
+        name: Some(String::from("main.c")),
+        comp_dir: Some(String::from("/src")),
+        producer: Some(String::from("GNU C17")),
+    });
+    let source = |file: &str, line| {
+        SourceLocation::new(Some(unit.clone()), Some(String::from(file)), Some(line))
+    };
+
+    let defined_types = vec![
+        TypeEntry::new_base_type_entry(TypeEntryId::new(Offset::new(65)), String::from("int"), 4),
+        TypeEntry::new_typedef_entry(
+            TypeEntryId::new(Offset::new(72)),
+            String::from("Count"),
+            TypeEntryId::new(Offset::new(65)),
+        )
+        .with_source(source("inc/types.h", 3)),
+    ];
+    let variable_decs = vec![VariableDeclarationEntry::new(
+        VariableDeclarationEntryId::new(Offset::new(80)),
+        String::from("count"),
+        TypeEntryId::new(Offset::new(72)),
+    )
+    .with_source(source("inc/types.h", 5))];
+
+    // the definition falls back to its declaration if it has no DW_AT_decl_file
+    let global_variables = vec![
+        GlobalVariable::new_variable_with_spec(
+            Some(Address::new(Location::new(16400))),
+            VariableDeclarationEntryId::new(Offset::new(80)),
+        )
+        .with_source(SourceLocation::new(Some(unit.clone()), None, None)),
+        GlobalVariable::new_variable_with_spec(
+            Some(Address::new(Location::new(16400))),
+            VariableDeclarationEntryId::new(Offset::new(80)),
+        )
+        .with_source(source("main.c", 7)),
+    ];
+
+    let type_source = source("inc/types.h", 3);
+    let expected_view = |source| {
+        GlobalVariableViewBuilder::new()
+            .name("count")
+            .address(Some(Address::new(Location::new(16400))))
+            .size(4)
+            .type_view(TypeView::new_typedef_type_view(
+                "Count",
+                TypeView::new_base_type_view("int"),
+            ))
+            .source(source)
+            .type_source(type_source.clone())
+            .build()
+    };
+    let expected_views = vec![
+        expected_view(source("inc/types.h", 5)),
+        expected_view(source("main.c", 7)),
+    ];
+
+    from_global_variables_test(
+        defined_types,
+        variable_decs,
+        global_variables,
+        expected_views,
+    );
+}
@@ -1,12 +1,16 @@
 extern crate troll;
 
+use std::sync::Arc;
 use troll::domain::global_variable::*;
 use troll::domain::global_variables_extractor::*;
+use troll::domain::source_location::SourceLocation;
 use troll::domain::type_entry::*;
 use troll::domain::type_entry_repository::TypeEntryRepository;
 use troll::domain::variable_declaration_entry::*;
 use troll::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
-use troll::library::dwarf::{DwarfInfo, DwarfInfoBuilder, DwarfTag, Location, Offset};
+use troll::library::dwarf::{
+    BaseTypeEncoding, CompilationUnit, DwarfInfo, DwarfInfoBuilder, DwarfTag, Location, Offset,
+};
 
 fn init() {
     let _ = env_logger::builder().is_test(true).try_init();
@@ -51,6 +55,7 @@ fn extract_const() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(72))
+            .external(true)
             .location(Location::new(8196))
             .build(),
         DwarfInfoBuilder::new()
@@ -58,6 +63,7 @@ fn extract_const() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(72))
@@ -78,7 +84,8 @@ fn extract_const() {
         TypeEntryId::new(Offset::new(72)),
     )];
     let expected_types = vec![
-        TypeEntry::new_base_type_entry(TypeEntryId::new(Offset::new(65)), String::from("int"), 4),
+        TypeEntry::new_base_type_entry(TypeEntryId::new(Offset::new(65)), String::from("int"), 4)
+            .with_encoding(Some(BaseTypeEncoding::Signed)),
         TypeEntry::new_const_type_entry(
             TypeEntryId::new(Offset::new(72)),
             TypeEntryId::new(Offset::new(65)),
@@ -96,6 +103,7 @@ fn extract_pointer() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("p")
             .type_offset(Offset::new(65))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -155,6 +163,7 @@ fn extract_typedef() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("a")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -224,6 +233,7 @@ fn extract_array() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("hoges")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -297,6 +307,7 @@ fn extract_enum() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("ab")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -372,6 +383,7 @@ fn extract_anonymous_enum() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("ab")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -450,8 +462,8 @@ fn extract_structure() {
                     .type_offset(Offset::new(115))
                     .byte_size(4)
                     .bit_size(1)
-                    .bit_offset(23)
-                    .data_member_location(4)
+                    .bit_offset(0)
+                    .data_member_location(5)
                     .build(),
             ])
             .build(),
@@ -478,6 +490,7 @@ fn extract_structure() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("hoge")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -516,10 +529,10 @@ fn extract_structure() {
                 StructureTypeMemberEntry::from(
                     MemberEntryBuilder::new_structure()
                         .name("pohe")
-                        .location(4)
+                        .location(5)
                         .type_ref(TypeEntryId::new(Offset::new(115)))
                         .bit_size(Some(1))
-                        .bit_offset(Some(23))
+                        .bit_offset(Some(0))
                         .build(),
                 ),
             ],
@@ -558,7 +571,8 @@ fn extract_union() {
                     .type_offset(Offset::new(93))
                     .byte_size(4)
                     .bit_size(8)
-                    .bit_offset(24)
+                    .bit_offset(0)
+                    .data_member_location(0)
                     .build(),
             ])
             .build(),
@@ -579,6 +593,7 @@ fn extract_union() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("book")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -612,7 +627,7 @@ fn extract_union() {
                         .name("price")
                         .type_ref(TypeEntryId::new(Offset::new(93)))
                         .bit_size(Some(8))
-                        .bit_offset(Some(24))
+                        .bit_offset(Some(0))
                         .build(),
                 ),
             ],
@@ -650,6 +665,7 @@ fn extract_anonymous_union_structure() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("a")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -682,6 +698,7 @@ fn extract_anonymous_union_structure() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("ab")
             .type_offset(Offset::new(93))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -781,6 +798,7 @@ fn extract_function_pointer() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("sub2")
             .type_offset(Offset::new(101))
+            .external(true)
             .location(Location::new(16424))
             .build(),
         DwarfInfoBuilder::new()
@@ -950,6 +968,7 @@ fn extract_complex_structure() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("hoge")
             .type_offset(Offset::new(184))
+            .external(true)
             .location(Location::new(16480))
             .build(),
         DwarfInfoBuilder::new()
@@ -1046,6 +1065,7 @@ fn extract_extern() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(55))
+            .external(true)
             .declaration(true)
             .build(),
         DwarfInfoBuilder::new()
@@ -1065,6 +1085,7 @@ fn extract_extern() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(136))
+            .external(true)
             .declaration(true)
             .build(),
         DwarfInfoBuilder::new()
@@ -1105,6 +1126,46 @@ fn extract_extern() {
     extract_test(infos, expected_variables, expected_types, expected_decs);
 }
 
+#[test]
+fn extract_static() {
+    let unit = Arc::new(CompilationUnit {
+        name: Some(String::from("a.c")),
+        comp_dir: None,
+        producer: None,
+    });
+    let infos = vec![
+        DwarfInfoBuilder::new()
+            .offset(Offset::new(45))
+            .tag(DwarfTag::DW_TAG_variable)
+            .name("counter")
+            .type_offset(Offset::new(65))
+            .location(Location::new(16400))
+            .compilation_unit(unit.clone())
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(Offset::new(65))
+            .tag(DwarfTag::DW_TAG_base_type)
+            .byte_size(4)
+            .name("int")
+            .build(),
+    ];
+
+    let expected_variables = vec![GlobalVariable::new_variable(
+        Some(Address::new(Location::new(16400))),
+        String::from("counter"),
+        TypeEntryId::new(Offset::new(65)),
+    )
+    .with_external(false)
+    .with_source(SourceLocation::new(Some(unit), None, None))];
+    let expected_types = vec![TypeEntry::new_base_type_entry(
+        TypeEntryId::new(Offset::new(65)),
+        String::from("int"),
+        4,
+    )];
+
+    extract_test(infos, expected_variables, expected_types, Vec::new());
+}
+
 #[test]
 fn extract_volatile() {
     let infos = vec![
@@ -1113,6 +1174,7 @@ fn extract_volatile() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(72))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -1150,3 +1212,107 @@ fn extract_volatile() {
 
     extract_test(infos, expected_variables, expected_types, Vec::new());
 }
+
+#[test]
+fn extract_units_many_compilation_units() {
+    init();
+
+    let units = vec![
+        vec![
+            DwarfInfoBuilder::new()
+                .offset(Offset::new(45))
+                .tag(DwarfTag::DW_TAG_variable)
+                .name("c")
+                .type_offset(Offset::new(55))
+                .external(true)
+                .declaration(true)
+                .build(),
+            DwarfInfoBuilder::new()
+                .offset(Offset::new(55))
+                .tag(DwarfTag::DW_TAG_base_type)
+                .byte_size(4)
+                .name("int")
+                .build(),
+            DwarfInfoBuilder::new()
+                .offset(Offset::new(62))
+                .tag(DwarfTag::DW_TAG_variable)
+                .name("a")
+                .type_offset(Offset::new(55))
+                .external(true)
+                .location(Location::new(16424))
+                .build(),
+        ],
+        vec![
+            DwarfInfoBuilder::new()
+                .offset(Offset::new(126))
+                .tag(DwarfTag::DW_TAG_variable)
+                .name("c")
+                .type_offset(Offset::new(136))
+                .external(true)
+                .declaration(true)
+                .build(),
+            DwarfInfoBuilder::new()
+                .offset(Offset::new(136))
+                .tag(DwarfTag::DW_TAG_base_type)
+                .byte_size(4)
+                .name("int")
+                .build(),
+            DwarfInfoBuilder::new()
+                .offset(Offset::new(143))
+                .tag(DwarfTag::DW_TAG_variable)
+                .specification(Offset::new(126))
+                .location(Location::new(16428))
+                .build(),
+        ],
+    ];
+
+    let expected_variables = vec![
+        GlobalVariable::new_variable(
+            Some(Address::new(Location::new(16424))),
+            String::from("a"),
+            TypeEntryId::new(Offset::new(55)),
+        ),
+        GlobalVariable::new_variable_with_spec(
+            Some(Address::new(Location::new(16428))),
+            VariableDeclarationEntryId::new(Offset::new(126)),
+        ),
+    ];
+    let expected_types = vec![
+        TypeEntry::new_base_type_entry(TypeEntryId::new(Offset::new(55)), String::from("int"), 4),
+        TypeEntry::new_base_type_entry(TypeEntryId::new(Offset::new(136)), String::from("int"), 4),
+    ];
+    let expected_decs = vec![
+        VariableDeclarationEntry::new(
+            VariableDeclarationEntryId::new(Offset::new(45)),
+            String::from("c"),
+            TypeEntryId::new(Offset::new(55)),
+        ),
+        VariableDeclarationEntry::new(
+            VariableDeclarationEntryId::new(Offset::new(126)),
+            String::from("c"),
+            TypeEntryId::new(Offset::new(136)),
+        ),
+    ];
+
+    let mut type_entry_repository = TypeEntryRepository::new();
+    let mut variable_declaration_repository = VariableDeclarationEntryRepository::new();
+    let mut global_variables_extractor = GlobalVariablesExtractor::new(
+        &mut type_entry_repository,
+        &mut variable_declaration_repository,
+    );
+
+    let got_variables = global_variables_extractor.extract_units(units);
+    assert_eq!(expected_variables, got_variables);
+    for expected_type in expected_types {
+        let got_type = type_entry_repository
+            .find_by_id(&expected_type.id())
+            .map(TypeEntry::clone);
+        assert_eq!(Some(expected_type), got_type);
+    }
+    for expected_dec in expected_decs {
+        let got_dec = variable_declaration_repository
+            .find_by_id(&expected_dec.id)
+            .map(VariableDeclarationEntry::clone);
+        assert_eq!(Some(expected_dec), got_dec);
+    }
+}
@@ -0,0 +1,89 @@
+extern crate troll;
+
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::image_verifier::*;
+use troll::domain::value::Value;
+use troll::library::dwarf::{BaseTypeEncoding, Location};
+use troll::library::memory::RawImage;
+
+fn view(name: &str, address: usize, size: usize, type_view: TypeView) -> GlobalVariableView {
+    GlobalVariableViewBuilder::new()
+        .name(name)
+        .address(Some(Address::new(Location::new(address))))
+        .size(size)
+        .type_view(type_view)
+        .build()
+}
+
+fn int() -> TypeView {
+    TypeView::new_encoded_base_type_view("int", Some(BaseTypeEncoding::Signed))
+}
+
+fn unsigned_char() -> TypeView {
+    TypeView::new_encoded_base_type_view("unsigned char", Some(BaseTypeEncoding::Unsigned))
+}
+
+fn bit_field(view: GlobalVariableView, bit_offset: usize, bit_size: usize) -> GlobalVariableView {
+    GlobalVariableView {
+        bit_offset: Some(bit_offset),
+        bit_size: Some(bit_size),
+        ..view
+    }
+}
+
+#[test]
+fn verify_image() {
+    let gains = GlobalVariableViewBuilder::new()
+        .name("gains")
+        .address(Some(Address::new(Location::new(0x1000))))
+        .size(8)
+        .type_view(TypeView::new_structure_type_view(Some("gains")))
+        .children(vec![
+            view("kp", 0x1000, 4, int()),
+            bit_field(view("shift", 0x1004, 1, unsigned_char()), 0, 3),
+            bit_field(view("mode", 0x1004, 1, unsigned_char()), 3, 1),
+        ])
+        .build();
+    let variable_views = vec![
+        gains,
+        view("threshold", 0x1008, 4, int()),
+        // not in the ELF, like .bss
+        view("scratch", 0x2000, 4, int()),
+    ];
+
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&10i32.to_le_bytes());
+    expected.extend_from_slice(&[0x02, 0, 0, 0]);
+    expected.extend_from_slice(&100i32.to_le_bytes());
+    let mut actual = expected.clone();
+    actual[0] = 0xf9;
+    actual[1..4].copy_from_slice(&[0xff; 3]);
+    // only mode is changed in the shared byte
+    actual[4] = 0x0a;
+    let elf = RawImage::new(0x1000, expected);
+    // threshold is not in the image
+    let image = RawImage::new(0x1000, actual[..8].to_vec());
+
+    let mismatches = ImageVerifier::new(&elf, &image, true).verify(variable_views);
+    assert_eq!(
+        mismatches,
+        vec![
+            ImageMismatch {
+                name: String::from("gains.kp"),
+                expected: Some(Value::Signed(10)),
+                actual: Some(Value::Signed(-7)),
+            },
+            ImageMismatch {
+                name: String::from("gains.mode"),
+                expected: Some(Value::Unsigned(0)),
+                actual: Some(Value::Unsigned(1)),
+            },
+            ImageMismatch {
+                name: String::from("threshold"),
+                expected: Some(Value::Signed(100)),
+                actual: None,
+            },
+        ]
+    );
+}
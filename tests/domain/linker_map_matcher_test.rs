@@ -0,0 +1,183 @@
+extern crate troll;
+
+use std::sync::Arc;
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::linker_map_matcher::*;
+use troll::domain::source_location::SourceLocation;
+use troll::library::dwarf::{CompilationUnit, Location};
+use troll::library::linker_map::*;
+
+fn section(address: usize, size: usize, object: &str) -> InputSection {
+    InputSection {
+        output_section: String::from(".bss"),
+        name: String::from(".bss"),
+        address,
+        size,
+        object: String::from(object),
+    }
+}
+
+fn symbol(name: &str, address: usize, size: MapSymbolSize, object: &str) -> MapSymbol {
+    MapSymbol {
+        name: String::from(name),
+        address,
+        size,
+        object: String::from(object),
+    }
+}
+
+fn view(name: &str, address: usize, size: usize) -> GlobalVariableView {
+    GlobalVariableViewBuilder::new()
+        .name(name)
+        .address(Some(Address::new(Location::new(address))))
+        .size(size)
+        .type_view(TypeView::new_base_type_view("int"))
+        .build()
+}
+
+fn static_view(unit: &str, name: &str, address: usize, size: usize) -> GlobalVariableView {
+    let compilation_unit = Arc::new(CompilationUnit {
+        name: Some(String::from(unit)),
+        comp_dir: None,
+        producer: None,
+    });
+    let mut view = view(&format!("{}:{}", unit, name), address, size);
+    view.external = false;
+    view.source = SourceLocation::new(Some(compilation_unit), None, None);
+    view
+}
+
+// a.o has value and big, and libb.a(b.o) has shared and b.c:counter,
+// and there is a static counter in a.c too
+fn linker_map() -> LinkerMap {
+    LinkerMap {
+        sections: vec![
+            section(0x4000, 0x10, "a.o"),
+            section(0x4010, 0x10, "libb.a(b.o)"),
+        ],
+        symbols: vec![
+            symbol("value", 0x4000, MapSymbolSize::AtMost(0x8), "a.o"),
+            symbol("big", 0x4008, MapSymbolSize::AtMost(0x8), "a.o"),
+            symbol("shared", 0x4010, MapSymbolSize::Exact(0x4), "libb.a(b.o)"),
+            symbol("counter", 0x4014, MapSymbolSize::Exact(0x4), "libb.a(b.o)"),
+        ],
+    }
+}
+
+#[test]
+fn check_consistent_variables() {
+    let linker_map = linker_map();
+    let views = vec![
+        view("value", 0x4000, 4),
+        view("big", 0x4008, 8),
+        view("shared", 0x4010, 4),
+        static_view("b.c", "counter", 0x4014, 4),
+        // local to a.o, and not in the map
+        static_view("a.c", "counter", 0x4004, 4),
+    ];
+
+    assert_eq!(LinkerMapMatcher::new(&linker_map).check(&views), Vec::new());
+}
+
+#[test]
+fn check_variables() {
+    let linker_map = linker_map();
+    let views = vec![
+        view("value", 0x4000, 12),
+        view("big", 0x400c, 8),
+        view("shared", 0x4010, 8),
+        view("lost", 0x5000, 4),
+    ];
+
+    let expected = vec![
+        LinkerMapMismatch {
+            name: String::from("value"),
+            differences: vec![String::from("size: 0xc > 0x8 until the next symbol")],
+            object: Some(String::from("a.o")),
+        },
+        LinkerMapMismatch {
+            name: String::from("big"),
+            differences: vec![
+                String::from("address: 0x0000400c != 0x00004008"),
+                String::from("size: 0x8 overruns .bss of a.o by 0x4"),
+            ],
+            object: Some(String::from("a.o")),
+        },
+        LinkerMapMismatch {
+            name: String::from("shared"),
+            differences: vec![String::from("size: 0x8 != 0x4")],
+            object: Some(String::from("libb.a(b.o)")),
+        },
+        LinkerMapMismatch {
+            name: String::from("lost"),
+            differences: vec![String::from("address: 0x00005000 is in no input section")],
+            object: None,
+        },
+    ];
+    assert_eq!(LinkerMapMatcher::new(&linker_map).check(&views), expected);
+}
+
+#[test]
+fn group_by_object() {
+    let linker_map = linker_map();
+    let views = vec![
+        view("shared", 0x4010, 4),
+        view("big", 0x4008, 8),
+        view("lost", 0x5000, 4),
+        view("value", 0x4000, 4),
+    ];
+    let matcher = LinkerMapMatcher::new(&linker_map);
+
+    let expected = vec![
+        ObjectGroup {
+            object: Some(String::from("a.o")),
+            size: 12,
+            variable_views: vec![view("value", 0x4000, 4), view("big", 0x4008, 8)],
+        },
+        ObjectGroup {
+            object: Some(String::from("libb.a(b.o)")),
+            size: 4,
+            variable_views: vec![view("shared", 0x4010, 4)],
+        },
+        ObjectGroup {
+            object: None,
+            size: 4,
+            variable_views: vec![view("lost", 0x5000, 4)],
+        },
+    ];
+    assert_eq!(
+        matcher.group(views.clone(), ObjectGrouping::Object),
+        expected
+    );
+
+    let archives: Vec<Option<String>> = matcher
+        .group(views, ObjectGrouping::Archive)
+        .into_iter()
+        .map(|group| group.object)
+        .collect();
+    assert_eq!(
+        archives,
+        vec![
+            Some(String::from("a.o")),
+            Some(String::from("libb.a")),
+            None
+        ]
+    );
+}
+
+#[test]
+fn check_variables_at_end_of_address_space() {
+    let linker_map = LinkerMap {
+        sections: vec![section(usize::MAX - 0xf, 0x20, "a.o")],
+        symbols: Vec::new(),
+    };
+    let views = vec![view("top", usize::MAX - 0x3, 8)];
+
+    let expected = vec![LinkerMapMismatch {
+        name: String::from("top"),
+        differences: vec![String::from("size: 0x8 overflows the address space")],
+        object: None,
+    }];
+    assert_eq!(LinkerMapMatcher::new(&linker_map).check(&views), expected);
+}
@@ -1,2 +1,15 @@
+pub mod budget_test;
+pub mod calibration_test;
+pub mod expression_test;
 pub mod global_variable_view_factory_test;
 pub mod global_variables_extractor_test;
+pub mod image_verifier_test;
+pub mod linker_map_matcher_test;
+pub mod odr_checker_test;
+pub mod pointer_follower_test;
+pub mod sampler_test;
+pub mod type_deduplicator_test;
+pub mod union_rules_test;
+pub mod value_decoder_test;
+pub mod value_encoder_test;
+pub mod variable_path_test;
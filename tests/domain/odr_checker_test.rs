@@ -0,0 +1,186 @@
+extern crate troll;
+
+use std::sync::Arc;
+use troll::domain::global_variable::*;
+use troll::domain::odr_checker::*;
+use troll::domain::source_location::SourceLocation;
+use troll::domain::type_entry::*;
+use troll::domain::type_entry_repository::TypeEntryRepository;
+use troll::domain::variable_declaration_entry::*;
+use troll::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use troll::library::dwarf::{CompilationUnit, Location, Offset};
+
+fn id(offset: usize) -> TypeEntryId {
+    TypeEntryId::new(Offset::new(offset))
+}
+
+fn source(unit: &str, line: usize) -> SourceLocation {
+    let compilation_unit = Arc::new(CompilationUnit {
+        name: Some(String::from(unit)),
+        comp_dir: None,
+        producer: None,
+    });
+    SourceLocation::new(Some(compilation_unit), Some(String::from(unit)), Some(line))
+}
+
+fn member(name: &str, location: usize, type_ref: TypeEntryId) -> StructureTypeMemberEntry {
+    StructureTypeMemberEntry::new(String::from(name), location, type_ref, None, None)
+}
+
+// the types of a unit at `base`:
+// struct config { int mode; T flags; }; typedef struct { int x; ... } Point; struct opaque *;
+fn unit_types(base: usize, unit: &str, flags_type: &str, point_members: usize) -> Vec<TypeEntry> {
+    let flags_size = if flags_type == "int" { 4 } else { 8 };
+    vec![
+        TypeEntry::new_base_type_entry(id(base), String::from("int"), 4),
+        TypeEntry::new_base_type_entry(id(base + 1), String::from(flags_type), flags_size),
+        TypeEntry::new_structure_type_entry(
+            id(base + 10),
+            Some(String::from("config")),
+            4 + flags_size,
+            vec![
+                member("mode", 0, id(base)),
+                member("flags", 4, id(base + 1)),
+            ],
+        )
+        .with_source(source(unit, 1)),
+        TypeEntry::new_structure_type_entry(
+            id(base + 20),
+            None,
+            4 * point_members,
+            ["x", "y"]
+                .iter()
+                .take(point_members)
+                .enumerate()
+                .map(|(i, name)| member(name, 4 * i, id(base)))
+                .collect(),
+        ),
+        TypeEntry::new_typedef_entry(id(base + 30), String::from("Point"), id(base + 20))
+            .with_source(source(unit, 2)),
+        // a pointer to an incomplete structure
+        TypeEntry::new_pointer_type_entry(id(base + 40), 8, Some(id(base + 99))),
+        TypeEntry::new_structure_type_entry(
+            id(base + 50),
+            Some(String::from("holder")),
+            8,
+            vec![member("p", 0, id(base + 40))],
+        )
+        .with_source(source(unit, 3)),
+    ]
+}
+
+fn check_test(
+    defined_types: Vec<TypeEntry>,
+    variable_decs: Vec<VariableDeclarationEntry>,
+    global_variables: Vec<GlobalVariable>,
+    expected: Vec<OdrViolation>,
+) {
+    let mut type_entry_repository = TypeEntryRepository::new();
+    for defined_type in defined_types {
+        type_entry_repository.save(defined_type);
+    }
+    let mut variable_declaration_repository = VariableDeclarationEntryRepository::new();
+    for variable_dec in variable_decs {
+        variable_declaration_repository.save(variable_dec);
+    }
+
+    let got = OdrChecker::new(&type_entry_repository, &variable_declaration_repository)
+        .check(&global_variables);
+    assert_eq!(expected, got);
+}
+
+#[test]
+fn check_consistent_types() {
+    let defined_types = [
+        unit_types(100, "a.c", "int", 1),
+        unit_types(200, "b.c", "int", 1),
+    ]
+    .concat();
+
+    check_test(defined_types, Vec::new(), Vec::new(), Vec::new());
+}
+
+#[test]
+fn check_types() {
+    let defined_types = [
+        unit_types(100, "a.c", "int", 1),
+        unit_types(200, "b.c", "long int", 2),
+        unit_types(300, "c.c", "int", 1),
+    ]
+    .concat();
+
+    let expected = vec![
+        OdrViolation {
+            name: String::from("struct config"),
+            differences: vec![
+                String::from("size: 8 != 12"),
+                String::from("member flags type: int != long int"),
+            ],
+            definitions: vec![
+                vec![source("a.c", 1), source("c.c", 1)],
+                vec![source("b.c", 1)],
+            ],
+        },
+        OdrViolation {
+            name: String::from("typedef Point"),
+            differences: vec![
+                String::from("size: 4 != 8"),
+                String::from("member y: only in some definitions"),
+            ],
+            definitions: vec![
+                vec![source("a.c", 2), source("c.c", 2)],
+                vec![source("b.c", 2)],
+            ],
+        },
+    ];
+    check_test(defined_types, Vec::new(), Vec::new(), expected);
+}
+
+#[test]
+fn check_variables() {
+    let defined_types = [
+        unit_types(100, "a.c", "int", 1),
+        unit_types(200, "b.c", "long int", 1),
+    ]
+    .concat();
+    // extern int counter; in a.c and long counter; in b.c
+    let variable_decs = vec![VariableDeclarationEntry::new(
+        VariableDeclarationEntryId::new(Offset::new(150)),
+        String::from("counter"),
+        id(100),
+    )
+    .with_source(source("a.c", 5))];
+    let global_variables = vec![
+        GlobalVariable::new_variable(
+            Some(Address::new(Location::new(0x4010))),
+            String::from("counter"),
+            id(201),
+        )
+        .with_source(source("b.c", 5)),
+        // static variables can have the same name
+        GlobalVariable::new_variable(
+            Some(Address::new(Location::new(0x4018))),
+            String::from("counter"),
+            id(100),
+        )
+        .with_external(false)
+        .with_source(source("c.c", 5)),
+    ];
+
+    let expected = vec![
+        OdrViolation {
+            name: String::from("struct config"),
+            differences: vec![
+                String::from("size: 8 != 12"),
+                String::from("member flags type: int != long int"),
+            ],
+            definitions: vec![vec![source("a.c", 1)], vec![source("b.c", 1)]],
+        },
+        OdrViolation {
+            name: String::from("counter"),
+            differences: vec![String::from("type: long int != int")],
+            definitions: vec![vec![source("b.c", 5)], vec![source("a.c", 5)]],
+        },
+    ];
+    check_test(defined_types, variable_decs, global_variables, expected);
+}
@@ -0,0 +1,178 @@
+extern crate troll;
+
+use troll::domain::global_variable::*;
+use troll::domain::global_variable_view::*;
+use troll::domain::global_variable_view_factory::*;
+use troll::domain::pointer_follower::*;
+use troll::domain::type_entry::*;
+use troll::domain::type_entry_repository::TypeEntryRepository;
+use troll::domain::value::Value;
+use troll::domain::value_decoder::ValueDecoder;
+use troll::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use troll::library::dwarf::{BaseTypeEncoding, Location, Offset};
+use troll::library::memory::{Memory, RawImage};
+
+// struct node { int value; struct node *next; };
+fn node_types() -> TypeEntryRepository {
+    let mut type_entry_repository = TypeEntryRepository::new();
+    type_entry_repository.save(TypeEntry::new_structure_type_entry(
+        TypeEntryId::new(Offset::new(10)),
+        Some(String::from("node")),
+        16,
+        vec![
+            StructureTypeMemberEntry::from(
+                MemberEntryBuilder::new_structure()
+                    .name("value")
+                    .location(0)
+                    .type_ref(TypeEntryId::new(Offset::new(30)))
+                    .build(),
+            ),
+            StructureTypeMemberEntry::from(
+                MemberEntryBuilder::new_structure()
+                    .name("next")
+                    .location(8)
+                    .type_ref(TypeEntryId::new(Offset::new(20)))
+                    .build(),
+            ),
+        ],
+    ));
+    type_entry_repository.save(TypeEntry::new_pointer_type_entry(
+        TypeEntryId::new(Offset::new(20)),
+        8,
+        Some(TypeEntryId::new(Offset::new(10))),
+    ));
+    type_entry_repository.save(
+        TypeEntry::new_base_type_entry(TypeEntryId::new(Offset::new(30)), String::from("int"), 4)
+            .with_encoding(Some(BaseTypeEncoding::Signed)),
+    );
+    type_entry_repository
+}
+
+// Nodes at 0x1000, 0x1010 and 0x1020 with the values 1, 2 and 3, linked by the pointers
+fn nodes(nexts: [u64; 3]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for (value, next) in nexts.iter().enumerate() {
+        data.extend_from_slice(&(value as u32 + 1).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&next.to_le_bytes());
+    }
+    data
+}
+
+fn follow(memory: &dyn Memory, depth: usize) -> GlobalVariableView {
+    let type_entry_repository = node_types();
+    let variable_declaration_repository = VariableDeclarationEntryRepository::new();
+    let factory =
+        GlobalVariableViewFactory::new(&type_entry_repository, &variable_declaration_repository);
+    let mut variable_view = factory
+        .from_global_variable(GlobalVariable::new_variable(
+            Some(Address::new(Location::new(0x1000))),
+            String::from("head"),
+            TypeEntryId::new(Offset::new(10)),
+        ))
+        .unwrap();
+    ValueDecoder::new(memory, true).decode(&mut variable_view);
+    PointerFollower::new(factory, memory, true, depth).follow(&mut variable_view);
+    variable_view
+}
+
+// The values of the nodes reached through `next`
+fn values(variable_view: &GlobalVariableView) -> Vec<Option<Value>> {
+    let mut values = vec![variable_view.children[0].value.clone()];
+    if let Some(pointee) = variable_view.children[1].children.first() {
+        assert_eq!(pointee.name, "*");
+        values.extend(self::values(pointee));
+    }
+    values
+}
+
+#[test]
+fn follow_linked_list() {
+    let image = RawImage::new(0x1000, nodes([0x1010, 0x1020, 0]));
+    let variable_view = follow(&image, 8);
+    assert_eq!(
+        values(&variable_view),
+        vec![
+            Some(Value::Signed(1)),
+            Some(Value::Signed(2)),
+            Some(Value::Signed(3))
+        ]
+    );
+    let pointee = &variable_view.children[1].children[0];
+    assert_eq!(pointee.address, Some(Address::new(Location::new(0x1010))));
+    assert_eq!(
+        pointee.type_view,
+        TypeView::new_structure_type_view(Some("node"))
+    );
+}
+
+#[test]
+fn follow_up_to_depth() {
+    let image = RawImage::new(0x1000, nodes([0x1010, 0x1020, 0]));
+    assert_eq!(
+        values(&follow(&image, 1)),
+        vec![Some(Value::Signed(1)), Some(Value::Signed(2))]
+    );
+    assert_eq!(values(&follow(&image, 0)), vec![Some(Value::Signed(1))]);
+}
+
+#[test]
+fn follow_stops_at_cycle() {
+    let image = RawImage::new(0x1000, nodes([0x1010, 0x1020, 0x1000]));
+    let variable_view = follow(&image, 8);
+    assert_eq!(
+        values(&variable_view),
+        vec![
+            Some(Value::Signed(1)),
+            Some(Value::Signed(2)),
+            Some(Value::Signed(3))
+        ]
+    );
+}
+
+#[test]
+fn follow_not_beyond_memory() {
+    let image = RawImage::new(0x1000, nodes([0x1010, 0x8000, 0]));
+    let variable_view = follow(&image, 8);
+    assert_eq!(
+        values(&variable_view),
+        vec![Some(Value::Signed(1)), Some(Value::Signed(2))]
+    );
+}
+
+#[test]
+fn follow_not_beyond_address_space() {
+    let image = RawImage::new(0x1000, nodes([0x1010, 0xffff_ffff_ffff_fffc, 0]));
+    let variable_view = follow(&image, 8);
+    assert_eq!(
+        values(&variable_view),
+        vec![Some(Value::Signed(1)), Some(Value::Signed(2))]
+    );
+}
+
+// Memory of a position independent executable loaded at 0x5000
+struct LoadedImage(RawImage);
+
+impl Memory for LoadedImage {
+    fn read(&self, address: usize, size: usize) -> Option<Vec<u8>> {
+        self.0.read(address + self.load_bias(), size)
+    }
+
+    fn load_bias(&self) -> usize {
+        0x5000
+    }
+}
+
+#[test]
+fn follow_loaded_pointers() {
+    let image = LoadedImage(RawImage::new(0x6000, nodes([0x6010, 0x6020, 0])));
+    let variable_view = follow(&image, 8);
+    assert_eq!(
+        values(&variable_view),
+        vec![
+            Some(Value::Signed(1)),
+            Some(Value::Signed(2)),
+            Some(Value::Signed(3))
+        ]
+    );
+}
@@ -0,0 +1,66 @@
+extern crate troll;
+
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::sampler::Sampler;
+use troll::domain::value::Value;
+use troll::library::dwarf::{BaseTypeEncoding, Location};
+use troll::library::memory::RawImage;
+
+fn int_view(name: &str, address: usize) -> GlobalVariableView {
+    GlobalVariableViewBuilder::new()
+        .name(name)
+        .address(Some(Address::new(Location::new(address))))
+        .size(4)
+        .type_view(TypeView::new_encoded_base_type_view(
+            "int",
+            Some(BaseTypeEncoding::Signed),
+        ))
+        .build()
+}
+
+#[test]
+fn sample_leaves_with_paths() {
+    let int = TypeView::new_encoded_base_type_view("int", Some(BaseTypeEncoding::Signed));
+    let array = GlobalVariableViewBuilder::new()
+        .name("array")
+        .address(Some(Address::new(Location::new(0x1004))))
+        .size(8)
+        .type_view(TypeView::new_typedef_type_view(
+            "pair",
+            TypeView::new_array_type_view(int, Some(1)),
+        ))
+        .children(vec![int_view("0", 0x1004), int_view("1", 0x1008)])
+        .build();
+    let hoge = GlobalVariableViewBuilder::new()
+        .name("hoge")
+        .address(Some(Address::new(Location::new(0x1000))))
+        .size(12)
+        .type_view(TypeView::new_structure_type_view(Some("hoge")))
+        .children(vec![int_view("count", 0x1000), array])
+        .build();
+    let sampler = Sampler::new(vec![hoge, int_view("missing", 0x2000)], true);
+    assert_eq!(
+        sampler.names(),
+        vec!["hoge.count", "hoge.array[0]", "hoge.array[1]", "missing"]
+    );
+
+    let mut data = Vec::new();
+    for value in &[3i32, -1, 7] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    let image = RawImage::new(0x1000, data);
+    assert_eq!(
+        sampler.sample(&image),
+        vec![
+            Some(Value::Signed(3)),
+            Some(Value::Signed(-1)),
+            Some(Value::Signed(7)),
+            None
+        ]
+    );
+
+    // samples are read again from the memory
+    let image = RawImage::new(0x1000, vec![0; 12]);
+    assert_eq!(sampler.sample(&image)[0], Some(Value::Signed(0)));
+}
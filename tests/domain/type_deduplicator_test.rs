@@ -0,0 +1,105 @@
+extern crate troll;
+
+use troll::domain::global_variables_extractor::GlobalVariablesExtractor;
+use troll::domain::type_deduplicator::*;
+use troll::domain::type_entry::*;
+use troll::domain::type_entry_repository::TypeEntryRepository;
+use troll::domain::variable_declaration_entry_repository::VariableDeclarationEntryRepository;
+use troll::library::dwarf::{DwarfInfoIntoIterator, Offset};
+
+fn id(offset: usize) -> TypeEntryId {
+    TypeEntryId::new(Offset::new(offset))
+}
+
+// struct node { int value; struct node *next; };
+fn node_types(base: usize, value_name: &str) -> Vec<TypeEntry> {
+    vec![
+        TypeEntry::new_base_type_entry(id(base), String::from("int"), 4),
+        TypeEntry::new_structure_type_entry(
+            id(base + 10),
+            Some(String::from("node")),
+            16,
+            vec![
+                StructureTypeMemberEntry::new(String::from(value_name), 0, id(base), None, None),
+                StructureTypeMemberEntry::new(String::from("next"), 8, id(base + 20), None, None),
+            ],
+        ),
+        TypeEntry::new_pointer_type_entry(id(base + 20), 8, Some(id(base + 10))),
+    ]
+}
+
+fn deduplicate_test(defined_types: Vec<TypeEntry>, expected: Vec<Vec<TypeEntryId>>) {
+    let mut type_entry_repository = TypeEntryRepository::new();
+    for defined_type in defined_types {
+        type_entry_repository.save(defined_type);
+    }
+
+    let got: Vec<CanonicalType> = TypeDeduplicator::new(&type_entry_repository).deduplicate();
+    for canonical_type in got.iter() {
+        assert_eq!(canonical_type.offsets[0], canonical_type.entry.id());
+    }
+    let got: Vec<Vec<TypeEntryId>> = got
+        .into_iter()
+        .map(|canonical_type| canonical_type.offsets)
+        .collect();
+    assert_eq!(expected, got);
+}
+
+#[test]
+fn deduplicate_recursive_types() {
+    let defined_types = [node_types(100, "value"), node_types(200, "value")].concat();
+
+    deduplicate_test(
+        defined_types,
+        vec![
+            vec![id(100), id(200)],
+            vec![id(110), id(210)],
+            vec![id(120), id(220)],
+        ],
+    );
+}
+
+#[test]
+fn deduplicate_different_types() {
+    // the same name with different members, and a pointer to the different one
+    let mut defined_types = [node_types(100, "value"), node_types(200, "data")].concat();
+    defined_types.push(TypeEntry::new_pointer_type_entry(id(300), 8, Some(id(999))));
+    defined_types.push(TypeEntry::new_pointer_type_entry(id(310), 8, Some(id(998))));
+
+    deduplicate_test(
+        defined_types,
+        vec![
+            vec![id(100), id(200)],
+            vec![id(110)],
+            vec![id(120)],
+            vec![id(210)],
+            vec![id(220)],
+            vec![id(300)],
+            vec![id(310)],
+        ],
+    );
+}
+
+#[test]
+#[ignore]
+fn deduplicate_static() {
+    let mut type_entry_repository = TypeEntryRepository::new();
+    let mut variable_declaration_repository = VariableDeclarationEntryRepository::new();
+    let units = DwarfInfoIntoIterator::new(String::from("examples/static")).into_units();
+    GlobalVariablesExtractor::new(
+        &mut type_entry_repository,
+        &mut variable_declaration_repository,
+    )
+    .extract_units(units);
+
+    // `int` is declared in both units
+    let got = TypeDeduplicator::new(&type_entry_repository).deduplicate();
+    let ints: Vec<&CanonicalType> = got
+        .iter()
+        .filter(|canonical_type| {
+            matches!(&canonical_type.entry.kind, TypeEntryKind::BaseType { name, .. } if name == "int")
+        })
+        .collect();
+    assert_eq!(1, ints.len());
+    assert_eq!(2, ints[0].offsets.len());
+}
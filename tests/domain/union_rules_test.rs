@@ -0,0 +1,198 @@
+extern crate troll;
+
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::union_rules::*;
+use troll::domain::value::Value;
+use troll::library::dwarf::{BaseTypeEncoding, Location};
+
+fn view(name: &str, type_view: TypeView, value: Option<Value>) -> GlobalVariableView {
+    GlobalVariableView {
+        value,
+        ..GlobalVariableViewBuilder::new()
+            .name(name)
+            .address(Some(Address::new(Location::new(0x1000))))
+            .size(4)
+            .type_view(type_view)
+            .build()
+    }
+}
+
+fn int(name: &str, value: i128) -> GlobalVariableView {
+    view(
+        name,
+        TypeView::new_encoded_base_type_view("int", Some(BaseTypeEncoding::Signed)),
+        Some(Value::Signed(value)),
+    )
+}
+
+// struct { enum kind type; union { int a; int b; } u; } msg;
+fn message(kind: &str, value: i128) -> GlobalVariableView {
+    let kind = view(
+        "type",
+        TypeView::new_enum_type_view::<String>(None, TypeView::new_base_type_view("int"), vec![]),
+        Some(Value::Enumerator {
+            name: Some(String::from(kind)),
+            value,
+        }),
+    );
+    let mut union = view("u", TypeView::new_union_type_view::<String>(None), None);
+    union.children = vec![int("a", 1), int("b", 1)];
+    let mut message = view(
+        "msg",
+        TypeView::new_structure_type_view(Some("message")),
+        None,
+    );
+    message.children = vec![kind, union];
+    message
+}
+
+// struct message msgs[2];
+fn messages(messages: Vec<GlobalVariableView>) -> GlobalVariableView {
+    let mut array = view(
+        "msgs",
+        TypeView::new_array_type_view(TypeView::new_structure_type_view(Some("message")), Some(1)),
+        None,
+    );
+    array.children = messages
+        .into_iter()
+        .enumerate()
+        .map(|(i, message)| GlobalVariableView {
+            name: i.to_string(),
+            ..message
+        })
+        .collect();
+    array
+}
+
+fn members(message: &GlobalVariableView) -> Vec<&str> {
+    message.children[1]
+        .children
+        .iter()
+        .map(|member| member.name.as_str())
+        .collect()
+}
+
+fn rules() -> UnionRules {
+    "\
+# tagged unions
+msg.type == MSG_A -> msg.u.a
+msg.type == 1 -> msg.u.b  # by the integer
+"
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn parse_union_rules() {
+    let union_rules = rules();
+    let rules: Vec<(String, &str, String)> = union_rules
+        .rules
+        .iter()
+        .map(|rule| {
+            (
+                rule.discriminant.to_string(),
+                rule.value.as_str(),
+                rule.member.to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            (String::from("msg.type"), "MSG_A", String::from("msg.u.a")),
+            (String::from("msg.type"), "1", String::from("msg.u.b")),
+        ]
+    );
+}
+
+#[test]
+fn parse_invalid_union_rules() {
+    assert_eq!(
+        "msg.type == A -> msg.u.a\nmsg.type = B -> msg.u.b\n".parse::<UnionRules>(),
+        Err(String::from(
+            "line 2: no `==` in the rule: msg.type = B -> msg.u.b"
+        ))
+    );
+    assert_eq!(
+        "msg.type == A\n".parse::<UnionRules>(),
+        Err(String::from("line 1: no `->` in the rule: msg.type == A"))
+    );
+    assert_eq!(
+        "msg.type == -> msg.u.a\n".parse::<UnionRules>(),
+        Err(String::from(
+            "line 1: no value in the rule: msg.type == -> msg.u.a"
+        ))
+    );
+    assert_eq!(
+        "msg.type == A -> other.u.a\n".parse::<UnionRules>(),
+        Err(String::from(
+            "line 1: msg.type and other.u.a are not in the same variable"
+        ))
+    );
+    assert_eq!(
+        "msg.type == A -> msg.u[0]\n".parse::<UnionRules>(),
+        Err(String::from("line 1: msg.u[0] is not a member of a union"))
+    );
+    assert_eq!(
+        "msgs[0].type == A -> msgs[*].u.a\n".parse::<UnionRules>(),
+        Err(String::from(
+            "line 1: msgs[0].type and msgs[*].u.a do not have the same number of [*]"
+        ))
+    );
+}
+
+#[test]
+fn apply_union_rules() {
+    let mut message_a = message("MSG_A", 0);
+    rules().apply(&mut message_a);
+    assert_eq!(members(&message_a), vec!["a"]);
+
+    let mut message_b = message("MSG_B", 1);
+    rules().apply(&mut message_b);
+    assert_eq!(members(&message_b), vec!["b"]);
+}
+
+#[test]
+fn apply_union_rules_to_each_element() {
+    let rules: UnionRules = "\
+msgs[*].type == MSG_A -> msgs[*].u.a
+msgs[*].type == MSG_B -> msgs[*].u.b
+"
+    .parse()
+    .unwrap();
+    let mut msgs = messages(vec![
+        message("MSG_B", 1),
+        message("MSG_A", 0),
+        message("MSG_C", 2),
+    ]);
+    rules.apply(&mut msgs);
+    let actual: Vec<Vec<&str>> = msgs.children.iter().map(members).collect();
+    assert_eq!(actual, vec![vec!["b"], vec!["a"], vec!["a", "b"]]);
+
+    // only the element in the rule
+    let rules: UnionRules = "msgs[1].type == MSG_B -> msgs[1].u.b\n".parse().unwrap();
+    let mut msgs = messages(vec![message("MSG_B", 1), message("MSG_B", 1)]);
+    rules.apply(&mut msgs);
+    let actual: Vec<Vec<&str>> = msgs.children.iter().map(members).collect();
+    assert_eq!(actual, vec![vec!["a", "b"], vec!["b"]]);
+}
+
+#[test]
+fn show_all_members_without_matching_rules() {
+    let mut message = message("MSG_C", 2);
+    rules().apply(&mut message);
+    assert_eq!(members(&message), vec!["a", "b"]);
+
+    let mut message = self::message("MSG_A", 0);
+    UnionRules::default().apply(&mut message);
+    assert_eq!(members(&message), vec!["a", "b"]);
+
+    // rules of other variables and unknown members are ignored
+    let mut message = self::message("MSG_A", 0);
+    let rules: UnionRules = "other.type == MSG_A -> other.u.a\nmsg.type == MSG_A -> msg.u.c\n"
+        .parse()
+        .unwrap();
+    rules.apply(&mut message);
+    assert_eq!(members(&message), vec!["a", "b"]);
+}
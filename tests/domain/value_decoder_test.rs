@@ -0,0 +1,219 @@
+extern crate troll;
+
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::value::Value;
+use troll::domain::value_decoder::ValueDecoder;
+use troll::library::dwarf::{BaseTypeEncoding, Location};
+use troll::library::memory::RawImage;
+
+fn view(name: &str, address: usize, size: usize, type_view: TypeView) -> GlobalVariableView {
+    GlobalVariableViewBuilder::new()
+        .name(name)
+        .address(Some(Address::new(Location::new(address))))
+        .size(size)
+        .type_view(type_view)
+        .build()
+}
+
+fn base(name: &str, encoding: BaseTypeEncoding) -> TypeView {
+    TypeView::new_encoded_base_type_view(name, Some(encoding))
+}
+
+fn bit_field(view: GlobalVariableView, bit_offset: usize, bit_size: usize) -> GlobalVariableView {
+    GlobalVariableView {
+        bit_offset: Some(bit_offset),
+        bit_size: Some(bit_size),
+        ..view
+    }
+}
+
+fn decode(image: &RawImage, little_endian: bool, mut view: GlobalVariableView) -> Option<Value> {
+    ValueDecoder::new(image, little_endian).decode(&mut view);
+    view.value
+}
+
+#[test]
+fn decode_base_types() {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(-42i32).to_le_bytes());
+    data.extend_from_slice(&3_000_000_000u32.to_le_bytes());
+    data.extend_from_slice(&1.5f32.to_le_bytes());
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    data.extend_from_slice(&(-2.25f64).to_le_bytes());
+    let image = RawImage::new(0x1000, data);
+
+    let int = base("int", BaseTypeEncoding::Signed);
+    let unsigned = base("unsigned int", BaseTypeEncoding::Unsigned);
+    let float = base("float", BaseTypeEncoding::Float);
+    let boolean = base("_Bool", BaseTypeEncoding::Boolean);
+    let double = TypeView::new_const_type_view(base("double", BaseTypeEncoding::Float));
+    assert_eq!(
+        decode(&image, true, view("i", 0x1000, 4, int)),
+        Some(Value::Signed(-42))
+    );
+    assert_eq!(
+        decode(&image, true, view("u", 0x1004, 4, unsigned)),
+        Some(Value::Unsigned(3_000_000_000))
+    );
+    assert_eq!(
+        decode(&image, true, view("f", 0x1008, 4, float)),
+        Some(Value::Float(1.5))
+    );
+    assert_eq!(
+        decode(&image, true, view("b", 0x100c, 1, boolean)),
+        Some(Value::Boolean(true))
+    );
+    assert_eq!(
+        decode(&image, true, view("d", 0x1010, 8, double.clone())),
+        Some(Value::Float(-2.25))
+    );
+    // partially out of the image
+    assert_eq!(decode(&image, true, view("d", 0x1014, 8, double)), None);
+}
+
+#[test]
+fn decode_big_endian() {
+    let image = RawImage::new(0, vec![0x12, 0x34, 0x56, 0x78, 0xff, 0xfe]);
+
+    let unsigned = base("unsigned int", BaseTypeEncoding::Unsigned);
+    let short = base("short int", BaseTypeEncoding::Signed);
+    assert_eq!(
+        decode(&image, false, view("u", 0, 4, unsigned)),
+        Some(Value::Unsigned(0x1234_5678))
+    );
+    assert_eq!(
+        decode(&image, false, view("s", 4, 2, short)),
+        Some(Value::Signed(-2))
+    );
+}
+
+#[test]
+fn decode_bit_fields() {
+    // a: 3 = 5, b: 5 = -3, c: 40 = 0x12_3456_789a at bit 10
+    let bits: u64 = 5 | (0b11101 << 3) | (0x12_3456_789a << 10);
+    let image = RawImage::new(0x100, bits.to_le_bytes().to_vec());
+
+    let unsigned = base("unsigned int", BaseTypeEncoding::Unsigned);
+    let int = base("int", BaseTypeEncoding::Signed);
+    let long = base("long long unsigned int", BaseTypeEncoding::Unsigned);
+    assert_eq!(
+        decode(&image, true, bit_field(view("a", 0x100, 4, unsigned), 0, 3)),
+        Some(Value::Unsigned(5))
+    );
+    assert_eq!(
+        decode(&image, true, bit_field(view("b", 0x100, 4, int), 3, 5)),
+        Some(Value::Signed(-3))
+    );
+    // the bits are in the image even though the size is beyond it
+    assert_eq!(
+        decode(
+            &image,
+            true,
+            bit_field(view("c", 0x101, 8, long.clone()), 2, 40)
+        ),
+        Some(Value::Unsigned(0x12_3456_789a))
+    );
+
+    // struct bit_field of examples/bit-field-big-endian.ll with the bit fields normalized from DWARF
+    let image = RawImage::new(
+        0,
+        vec![
+            0x78, 0xb5, 0x40, 0x00, 0xff, 0xff, 0xff, 0xfe, 0x84, 0x8d, 0x15, 0x9e, 0x26, 0x80,
+        ],
+    );
+    let unsigned = base("unsigned int", BaseTypeEncoding::Unsigned);
+    let char = base("unsigned char", BaseTypeEncoding::Unsigned);
+    assert_eq!(
+        decode(
+            &image,
+            false,
+            bit_field(view("a", 1, 4, unsigned.clone()), 5, 3)
+        ),
+        Some(Value::Unsigned(5))
+    );
+    assert_eq!(
+        decode(&image, false, bit_field(view("b", 1, 4, unsigned), 6, 7)),
+        Some(Value::Unsigned(85))
+    );
+    assert_eq!(
+        decode(&image, false, bit_field(view("e", 8, 1, char), 6, 2)),
+        Some(Value::Unsigned(2))
+    );
+    // the last bit field at the end of the image is read even though the size is beyond it
+    assert_eq!(
+        decode(&image, false, bit_field(view("f", 8, 8, long), 6, 40)),
+        Some(Value::Unsigned(0x12_3456_789a))
+    );
+}
+
+#[test]
+fn decode_enums_and_pointers() {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(-1i32).to_le_bytes());
+    data.extend_from_slice(&7i32.to_le_bytes());
+    data.extend_from_slice(&0x4010u64.to_le_bytes());
+    let image = RawImage::new(0, data);
+
+    let color = TypeView::new_enum_type_view(
+        Some("color"),
+        base("int", BaseTypeEncoding::Signed),
+        vec![
+            Enumerator {
+                name: String::from("RED"),
+                value: 0,
+            },
+            Enumerator {
+                name: String::from("BLUE"),
+                value: -1,
+            },
+        ],
+    );
+    assert_eq!(
+        decode(&image, true, view("c", 0, 4, color.clone())),
+        Some(Value::Enumerator {
+            name: Some(String::from("BLUE")),
+            value: -1
+        })
+    );
+    assert_eq!(
+        decode(&image, true, view("c", 4, 4, color)),
+        Some(Value::Enumerator {
+            name: None,
+            value: 7
+        })
+    );
+    assert_eq!(
+        decode(
+            &image,
+            true,
+            view("p", 8, 8, TypeView::new_void_pointer_type_view())
+        ),
+        Some(Value::Pointer(0x4010))
+    );
+}
+
+#[test]
+fn decode_members() {
+    let image = RawImage::new(0x10, vec![1, 0, 0, 0]);
+    let int = base("int", BaseTypeEncoding::Signed);
+    let structure = GlobalVariableViewBuilder::new()
+        .name("s")
+        .address(Some(Address::new(Location::new(0x10))))
+        .size(8)
+        .type_view(TypeView::new_structure_type_view(Some("s")))
+        .children(vec![
+            view("x", 0x10, 4, int.clone()),
+            view("y", 0x14, 4, int),
+        ])
+        .build();
+
+    let mut decoded = structure.clone();
+    assert!(ValueDecoder::new(&image, true).decode(&mut decoded));
+    assert_eq!(decoded.value, None);
+    assert_eq!(decoded.children[0].value, Some(Value::Signed(1)));
+    assert_eq!(decoded.children[1].value, None);
+
+    let mut outside = structure;
+    assert!(!ValueDecoder::new(&RawImage::new(0x100, vec![0; 8]), true).decode(&mut outside));
+}
@@ -0,0 +1,195 @@
+extern crate troll;
+
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::value::Value;
+use troll::domain::value_decoder::ValueDecoder;
+use troll::domain::value_encoder::{MemoryWrite, ValueEncoder};
+use troll::library::dwarf::{BaseTypeEncoding, Location};
+use troll::library::memory::{RawImage, WritableMemory};
+
+fn view(name: &str, address: usize, size: usize, type_view: TypeView) -> GlobalVariableView {
+    GlobalVariableViewBuilder::new()
+        .name(name)
+        .address(Some(Address::new(Location::new(address))))
+        .size(size)
+        .type_view(type_view)
+        .build()
+}
+
+fn base(name: &str, encoding: BaseTypeEncoding) -> TypeView {
+    TypeView::new_encoded_base_type_view(name, Some(encoding))
+}
+
+fn bit_field(view: GlobalVariableView, bit_offset: usize, bit_size: usize) -> GlobalVariableView {
+    GlobalVariableView {
+        bit_offset: Some(bit_offset),
+        bit_size: Some(bit_size),
+        ..view
+    }
+}
+
+fn write(address: usize, bytes: &[u8]) -> Result<MemoryWrite, String> {
+    Ok(MemoryWrite {
+        address,
+        bytes: bytes.to_vec(),
+    })
+}
+
+#[test]
+fn encode_base_types() {
+    let image = RawImage::new(0x1000, vec![0; 16]);
+    let encoder = ValueEncoder::new(&image, true);
+
+    let int = view("i", 0x1000, 4, base("int", BaseTypeEncoding::Signed));
+    let unsigned = view(
+        "u",
+        0x1004,
+        2,
+        base("unsigned short", BaseTypeEncoding::Unsigned),
+    );
+    let float = view("f", 0x1008, 4, base("float", BaseTypeEncoding::Float));
+    let boolean = view("b", 0x100c, 1, base("_Bool", BaseTypeEncoding::Boolean));
+    let double = view("d", 0x1008, 8, base("double", BaseTypeEncoding::Float));
+    assert_eq!(
+        encoder.encode(&int, "-2"),
+        write(0x1000, &[0xfe, 0xff, 0xff, 0xff])
+    );
+    assert_eq!(
+        encoder.encode(&unsigned, "0x1234"),
+        write(0x1004, &[0x34, 0x12])
+    );
+    assert_eq!(encoder.encode(&unsigned, "+3"), write(0x1004, &[3, 0]));
+    assert_eq!(
+        encoder.encode(&float, "1.5"),
+        write(0x1008, &1.5f32.to_le_bytes())
+    );
+    assert_eq!(encoder.encode(&boolean, "true"), write(0x100c, &[1]));
+    assert_eq!(
+        encoder.encode(&double, "-2.25"),
+        write(0x1008, &(-2.25f64).to_le_bytes())
+    );
+
+    assert!(encoder.encode(&unsigned, "65536").is_err());
+    assert!(encoder.encode(&unsigned, "-1").is_err());
+    assert!(encoder.encode(&int, "2147483648").is_err());
+    assert!(encoder.encode(&int, "1.5").is_err());
+    // a second sign or a sign after the radix prefix
+    assert!(encoder.encode(&int, "--3").is_err());
+    assert!(encoder.encode(&int, "-+3").is_err());
+    assert!(encoder.encode(&int, "+-3").is_err());
+    assert!(encoder.encode(&int, "0x-5").is_err());
+    assert!(encoder.encode(&unsigned, "0x+5").is_err());
+    assert!(encoder.encode(&boolean, "yes").is_err());
+    assert!(encoder.encode(&float, "1e39").is_err());
+}
+
+#[test]
+fn encode_enum_and_pointer() {
+    let image = RawImage::new(0x1000, vec![0; 16]);
+    let encoder = ValueEncoder::new(&image, false);
+
+    let enumerators = vec![
+        Enumerator {
+            name: String::from("A"),
+            value: 0,
+        },
+        Enumerator {
+            name: String::from("B"),
+            value: -1,
+        },
+    ];
+    let enum_view = view(
+        "ab",
+        0x1000,
+        4,
+        TypeView::new_enum_type_view(
+            Some("AB"),
+            base("int", BaseTypeEncoding::Signed),
+            enumerators,
+        ),
+    );
+    let pointer = view("p", 0x1004, 4, TypeView::new_void_pointer_type_view());
+    let structure = view("s", 0x1000, 8, TypeView::new_structure_type_view(Some("s")));
+    assert_eq!(encoder.encode(&enum_view, "B"), write(0x1000, &[0xff; 4]));
+    assert_eq!(
+        encoder.encode(&enum_view, "5"),
+        write(0x1000, &[0, 0, 0, 5])
+    );
+    assert!(encoder.encode(&enum_view, "C").is_err());
+    assert_eq!(
+        encoder.encode(&pointer, "0x20000010"),
+        write(0x1004, &[0x20, 0, 0, 0x10])
+    );
+    assert!(encoder.encode(&structure, "0").is_err());
+}
+
+// Encoded bit fields keep the other bits, and are decoded to the same values
+#[test]
+fn encode_bit_fields() {
+    let mut image = RawImage::new(0x1000, vec![0xff, 0xff, 0xff, 0xff]);
+    let int = base("int", BaseTypeEncoding::Signed);
+    let unsigned = base("unsigned int", BaseTypeEncoding::Unsigned);
+    let a = bit_field(view("a", 0x1000, 4, unsigned), 3, 7);
+    let b = bit_field(view("b", 0x1000, 4, int), 10, 4);
+
+    for (variable_view, text) in [(&a, "0x15"), (&b, "-3")] {
+        let write = ValueEncoder::new(&image, true)
+            .encode(variable_view, text)
+            .unwrap();
+        image.write(write.address, &write.bytes).unwrap();
+    }
+    let decode = |mut variable_view: GlobalVariableView| {
+        ValueDecoder::new(&image, true).decode(&mut variable_view);
+        variable_view.value
+    };
+    assert_eq!(decode(a.clone()), Some(Value::Unsigned(0x15)));
+    assert_eq!(decode(b.clone()), Some(Value::Signed(-3)));
+    // the bits out of the fields are kept
+    let rest = bit_field(a.clone(), 14, 18);
+    assert_eq!(decode(rest), Some(Value::Unsigned(0x3ffff)));
+    let low = bit_field(a.clone(), 0, 3);
+    assert_eq!(decode(low), Some(Value::Unsigned(0x7)));
+
+    let encoder = ValueEncoder::new(&image, true);
+    assert!(encoder.encode(&b, "8").is_err());
+    assert!(encoder.encode(&a, "128").is_err());
+}
+
+// Only the bytes of big endian bit fields are written, where the bit fields are
+// of struct bit_field of examples/bit-field-big-endian.ll normalized from DWARF
+#[test]
+fn encode_big_endian_bit_fields() {
+    let image = RawImage::new(
+        0,
+        vec![
+            0x78, 0xb5, 0x40, 0x00, 0xff, 0xff, 0xff, 0xfe, 0x84, 0x8d, 0x15, 0x9e, 0x26, 0x80,
+        ],
+    );
+    let unsigned = base("unsigned int", BaseTypeEncoding::Unsigned);
+    let char = base("unsigned char", BaseTypeEncoding::Unsigned);
+    let long = base("long long unsigned int", BaseTypeEncoding::Unsigned);
+    let a = bit_field(view("a", 1, 4, unsigned.clone()), 5, 3);
+    let b = bit_field(view("b", 1, 4, unsigned), 6, 7);
+    let e = bit_field(view("e", 8, 1, char), 6, 2);
+    let f = bit_field(view("f", 8, 8, long), 6, 40);
+
+    let encoder = ValueEncoder::new(&image, false);
+    assert_eq!(encoder.encode(&a, "2"), write(1, &[0x55]));
+    assert_eq!(encoder.encode(&b, "0x2a"), write(1, &[0xaa, 0x80]));
+    assert_eq!(encoder.encode(&e, "1"), write(8, &[0x44]));
+    // the last bit field at the end of the image keeps the bits of e
+    assert_eq!(
+        encoder.encode(&f, "0xabcdef0123"),
+        write(8, &[0xaa, 0xf3, 0x7b, 0xc0, 0x48, 0xc0])
+    );
+}
+
+#[test]
+fn write_raw_image() {
+    let mut image = RawImage::new(0x1000, vec![0; 4]);
+    assert!(image.write(0x1002, &[1, 2]).is_ok());
+    assert!(image.write(0x1003, &[1, 2]).is_err());
+    assert!(image.write(0xfff, &[1]).is_err());
+    assert_eq!(image.data(), &[0, 0, 1, 2]);
+}
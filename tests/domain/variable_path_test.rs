@@ -0,0 +1,205 @@
+extern crate troll;
+
+use std::sync::Arc;
+use troll::domain::global_variable::Address;
+use troll::domain::global_variable_view::*;
+use troll::domain::source_location::SourceLocation;
+use troll::domain::variable_path::*;
+use troll::library::dwarf::{CompilationUnit, Location};
+
+#[test]
+fn parse_variable_path() {
+    let expected = VariablePath {
+        name: String::from("g_config"),
+        elements: vec![
+            VariablePathElement::Member(String::from("timeouts")),
+            VariablePathElement::Index(2),
+            VariablePathElement::Member(String::from("_value1")),
+        ],
+    };
+
+    let got = "g_config.timeouts[2]._value1".parse::<VariablePath>();
+    assert_eq!(Ok(expected), got);
+    assert_eq!(
+        "g_config.timeouts[2]._value1",
+        got.unwrap().to_string().as_str()
+    );
+}
+
+#[test]
+fn parse_qualified_variable_path() {
+    let expected = VariablePath {
+        name: String::from("src/file.c:counter"),
+        elements: vec![VariablePathElement::Index(1)],
+    };
+
+    let got = "src/file.c:counter[1]".parse::<VariablePath>();
+    assert_eq!(Ok(expected), got);
+    let got = got.unwrap();
+    assert_eq!("counter", got.unqualified_name());
+    assert_eq!("src/file.c:counter[1]", got.to_string().as_str());
+
+    // the unit ends at the last `:`
+    let got = "C:\\src\\file.c:counter".parse::<VariablePath>().unwrap();
+    assert_eq!("C:\\src\\file.c:counter", got.name);
+    assert_eq!("counter", got.unqualified_name());
+    assert_eq!(
+        (Some("C:\\src\\file.c"), "counter"),
+        split_qualified_name(&got.name)
+    );
+}
+
+#[test]
+fn parse_invalid_variable_path() {
+    for path in &[
+        "", "1abc", "a.", "a[", "a[x]", "a..b", "a-b", "a[1]b", ":a", "a.c:", "a.c:1b", "a[*]",
+    ] {
+        assert!(path.parse::<VariablePath>().is_err(), "{}", path);
+    }
+}
+
+#[test]
+fn parse_variable_path_pattern() {
+    let expected = VariablePath {
+        name: String::from("msgs"),
+        elements: vec![
+            VariablePathElement::AnyIndex,
+            VariablePathElement::Member(String::from("type")),
+        ],
+    };
+
+    let got = VariablePath::parse_pattern("msgs[*].type");
+    assert_eq!(Ok(expected), got);
+    assert_eq!("msgs[*].type", got.unwrap().to_string().as_str());
+    assert!(VariablePath::parse_pattern("msgs[*").is_err());
+}
+
+#[test]
+fn find_by_path() {
+    let element = |n: usize| {
+        GlobalVariableViewBuilder::new()
+            .name(n.to_string())
+            .address(Some(Address::new(Location::new(0x1004 + 4 * n))))
+            .size(4)
+            .type_view(TypeView::new_base_type_view("int"))
+            .build()
+    };
+    let variable_view = GlobalVariableViewBuilder::new()
+        .name("g_config")
+        .address(Some(Address::new(Location::new(0x1000))))
+        .size(12)
+        .type_view(TypeView::new_typedef_type_view(
+            "config",
+            TypeView::new_structure_type_view(Some("config")),
+        ))
+        .children(vec![
+            GlobalVariableViewBuilder::new()
+                .name("mode")
+                .address(Some(Address::new(Location::new(0x1000))))
+                .size(4)
+                .type_view(TypeView::new_base_type_view("int"))
+                .build(),
+            GlobalVariableViewBuilder::new()
+                .name("timeouts")
+                .address(Some(Address::new(Location::new(0x1004))))
+                .size(8)
+                .type_view(TypeView::new_array_type_view(
+                    TypeView::new_base_type_view("int"),
+                    Some(1),
+                ))
+                .children(vec![element(0), element(1)])
+                .build(),
+        ])
+        .build();
+
+    let path: VariablePath = "g_config.timeouts[1]".parse().unwrap();
+    assert_eq!(
+        Some(element(1)),
+        variable_view.clone().find_by_path(&path.elements)
+    );
+    let path: VariablePath = "g_config.mode[0]".parse().unwrap();
+    assert_eq!(None, variable_view.clone().find_by_path(&path.elements));
+    let path: VariablePath = "g_config.timeouts[2]".parse().unwrap();
+    assert_eq!(None, variable_view.clone().find_by_path(&path.elements));
+    let path: VariablePath = "g_config[0]".parse().unwrap();
+    assert_eq!(None, variable_view.find_by_path(&path.elements));
+}
+
+#[test]
+fn qualify_names() {
+    let variable_view = |name: &str, unit: &str, external: bool| {
+        let (comp_dir, unit) = match unit.rsplit_once('/') {
+            Some((comp_dir, unit)) => (Some(String::from(comp_dir)), unit),
+            None => (None, unit),
+        };
+        let unit = Arc::new(CompilationUnit {
+            name: Some(String::from(unit)),
+            comp_dir,
+            producer: None,
+        });
+        GlobalVariableViewBuilder::new()
+            .name(name)
+            .address(Some(Address::new(Location::new(0x1000))))
+            .size(4)
+            .type_view(TypeView::new_base_type_view("int"))
+            .external(external)
+            .source(SourceLocation::new(Some(unit), None, None))
+            .build()
+    };
+    let mut variable_views = vec![
+        variable_view("counter", "a.c", false),
+        variable_view("counter", "b.c", false),
+        variable_view("counter", "c.c", true),
+        variable_view("only", "a.c", false),
+    ];
+
+    GlobalVariableView::qualify_names(&mut variable_views);
+    let got: Vec<&str> = variable_views
+        .iter()
+        .map(|variable_view| variable_view.name.as_str())
+        .collect();
+    assert_eq!(vec!["a.c:counter", "b.c:counter", "counter", "only"], got);
+    assert_eq!(
+        Some(String::from("a.c:only")),
+        variable_views[3].qualified_name()
+    );
+    assert_eq!(None, variable_views[2].qualified_name());
+    assert!(variable_views[0].is_local_to("a.c"));
+    assert!(!variable_views[2].is_local_to("c.c"));
+
+    // the units with the same name in different directories are told apart by their paths
+    let mut variable_views = vec![
+        variable_view("counter", "/x/a.c", false),
+        variable_view("counter", "/y/a.c", false),
+        variable_view("counter", "/y/b.c", false),
+    ];
+    GlobalVariableView::qualify_names(&mut variable_views);
+    let got: Vec<&str> = variable_views
+        .iter()
+        .map(|variable_view| variable_view.name.as_str())
+        .collect();
+    assert_eq!(vec!["/x/a.c:counter", "/y/a.c:counter", "b.c:counter"], got);
+    assert_eq!("counter", variable_views[0].unqualified_name());
+    assert!(variable_views[0].is_local_to("/x/a.c"));
+    assert!(!variable_views[0].is_local_to("/y/a.c"));
+}
+
+#[test]
+fn compilation_unit_key() {
+    let unit = |name: &str, comp_dir: Option<&str>| CompilationUnit {
+        name: Some(String::from(name)),
+        comp_dir: comp_dir.map(String::from),
+        producer: None,
+    };
+    let absolute = unit("/work/src/file.c", Some("/work"));
+    assert_eq!(Some(String::from("src/file.c")), absolute.key());
+    assert_eq!(Some(String::from("/work/src/file.c")), absolute.path());
+    let relative = unit("src/file.c", Some("/work"));
+    assert_eq!(Some(String::from("src/file.c")), relative.key());
+    assert_eq!(Some(String::from("/work/src/file.c")), relative.path());
+    assert!(relative.is_named("src/file.c"));
+    assert!(relative.is_named("/work/src/file.c"));
+    assert!(!relative.is_named("file.c"));
+    let outside = unit("/other/file.c", Some("/work"));
+    assert_eq!(Some(String::from("/other/file.c")), outside.key());
+}
@@ -0,0 +1,133 @@
+extern crate troll;
+
+use object::Object;
+use std::env;
+use std::fs;
+
+use troll::library::core_file::*;
+use troll::library::memory::Memory;
+
+const BIAS: u64 = 0x5555_0000_0000;
+
+// An ELF64 little endian core with NT_AUXV, and a PT_LOAD segment at 0x4000 + BIAS
+// whose last 4 bytes are not dumped
+fn write_core(path: &str, entry: u64) {
+    let mut core = vec![0; 64];
+    core[..7].copy_from_slice(b"\x7fELF\x02\x01\x01");
+    core[0x10..0x12].copy_from_slice(&4u16.to_le_bytes());
+    core[0x20..0x28].copy_from_slice(&64u64.to_le_bytes());
+    core[0x36..0x38].copy_from_slice(&56u16.to_le_bytes());
+    core[0x38..0x3a].copy_from_slice(&2u16.to_le_bytes());
+
+    let note_offset = 64 + 2 * 56;
+    let mut note = Vec::new();
+    for word in &[5u32, 32, 6] {
+        note.extend_from_slice(&word.to_le_bytes());
+    }
+    note.extend_from_slice(b"CORE\0\0\0\0");
+    for word in &[9, entry + BIAS, 0, 0] {
+        note.extend_from_slice(&word.to_le_bytes());
+    }
+    let load_offset = note_offset + note.len() as u64;
+    let data = [0x78u8, 0x56, 0x34, 0x12, 0xef, 0xcd, 0xab, 0x89];
+
+    let program_header = |p_type: u32, offset: u64, address: u64, file_size: u64, size: u64| {
+        let mut header = Vec::new();
+        header.extend_from_slice(&p_type.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        for word in &[offset, address, 0, file_size, size, 1] {
+            header.extend_from_slice(&word.to_le_bytes());
+        }
+        header
+    };
+    core.extend(program_header(4, note_offset, 0, note.len() as u64, 0));
+    core.extend(program_header(1, load_offset, 0x4000 + BIAS, 8, 12));
+    core.extend(note);
+    core.extend_from_slice(&data);
+    fs::write(path, core).unwrap();
+}
+
+#[test]
+fn read_core_file() {
+    let elf_path = env::current_exe().unwrap();
+    let entry = {
+        let data = fs::read(&elf_path).unwrap();
+        object::File::parse(&data).unwrap().entry()
+    };
+    let core_path = env::temp_dir().join(format!("troll-core-{}", std::process::id()));
+    write_core(core_path.to_str().unwrap(), entry);
+
+    let core = CoreFile::open(core_path.to_str().unwrap(), elf_path.to_str().unwrap());
+    fs::remove_file(&core_path).unwrap();
+    let core = core.unwrap();
+
+    assert_eq!(core.load_bias(), BIAS as usize);
+    assert_eq!(core.read(0x4000, 4), Some(vec![0x78, 0x56, 0x34, 0x12]));
+    assert_eq!(core.read(0x4006, 2), Some(vec![0xab, 0x89]));
+    // not dumped
+    assert_eq!(core.read(0x4008, 4), None);
+    // out of the segment
+    assert_eq!(core.read(0x3ffc, 4), None);
+    assert_eq!(core.read(0x400a, 4), None);
+    assert_eq!(core.read(usize::MAX - BIAS as usize - 1, 4), None);
+}
+
+#[test]
+fn open_broken_core_file() {
+    let elf_path = env::current_exe().unwrap();
+    let elf_path = elf_path.to_str().unwrap();
+    let core_path = env::temp_dir().join(format!("troll-broken-core-{}", std::process::id()));
+    let core_path = core_path.to_str().unwrap();
+    write_core(core_path, 0);
+    let core = fs::read(core_path).unwrap();
+    let broken = |patch: &dyn Fn(&mut Vec<u8>)| {
+        let mut core = core.clone();
+        patch(&mut core);
+        fs::write(core_path, core).unwrap();
+        CoreFile::open(core_path, elf_path)
+    };
+
+    // the program headers at the end of the address space
+    let headers = broken(&|core| core[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes()));
+    // the notes and the segment at the end of the file offsets
+    let note = broken(&|core| core[0x48..0x50].copy_from_slice(&u64::MAX.to_le_bytes()));
+    let segment = broken(&|core| core[0x80..0x88].copy_from_slice(&(u64::MAX - 2).to_le_bytes()));
+    fs::remove_file(core_path).unwrap();
+
+    assert!(headers.is_err());
+    let note = note.unwrap();
+    assert_eq!(note.load_bias(), 0);
+    assert_eq!(
+        note.read(BIAS as usize + 0x4000, 4),
+        Some(vec![0x78, 0x56, 0x34, 0x12])
+    );
+    assert_eq!(segment.unwrap().read(0x4000, 4), None);
+}
+
+#[test]
+fn open_non_core_file() {
+    let elf_path = env::current_exe().unwrap();
+    let elf_path = elf_path.to_str().unwrap();
+    assert!(CoreFile::open(elf_path, elf_path).is_err());
+}
+
+#[test]
+fn open_empty_or_non_elf_files() {
+    let elf_path = env::current_exe().unwrap();
+    let elf_path = elf_path.to_str().unwrap();
+    let path = env::temp_dir().join(format!("troll-empty-{}", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    fs::write(path, b"").unwrap();
+    let empty_core = CoreFile::open(path, elf_path).is_err();
+    let empty_elf = CoreFile::open(elf_path, path).is_err();
+    fs::write(path, b"not an ELF file").unwrap();
+    let non_elf_core = CoreFile::open(path, elf_path).is_err();
+    let non_elf = CoreFile::open(elf_path, path).is_err();
+    fs::remove_file(path).unwrap();
+
+    assert!(empty_core);
+    assert!(empty_elf);
+    assert!(non_elf_core);
+    assert!(non_elf);
+}
@@ -1,5 +1,8 @@
 extern crate troll;
 
+use std::path::Path;
+use std::process::Command;
+use std::{env, fs};
 use troll::library::dwarf::*;
 
 fn init() {
@@ -15,6 +18,105 @@ fn dwarf_info_intoiterator_test<S: Into<String>>(elf_path: S, expected: Vec<Dwar
     assert_eq!(expected, got);
 }
 
+// The offsets and the addresses of the entries depend on the compiler which built the examples,
+// so the variables are compared by their names and the structures of their types instead
+#[derive(Debug, PartialEq)]
+struct VariableStructure {
+    name: Option<String>,
+    declaration: Option<bool>,
+    has_location: bool,
+    type_structure: Option<TypeStructure>,
+}
+
+#[derive(Debug, PartialEq)]
+struct TypeStructure {
+    tag: DwarfTag,
+    name: Option<String>,
+    byte_size: Option<usize>,
+    encoding: Option<BaseTypeEncoding>,
+    data_member_location: Option<usize>,
+    type_structure: Option<Box<TypeStructure>>,
+    children: Vec<TypeStructure>,
+}
+
+fn find_info<'a>(infos: &'a [DwarfInfo], offset: Offset) -> &'a DwarfInfo<'a> {
+    infos
+        .iter()
+        .find(|info| info.offset() == offset)
+        .unwrap_or_else(|| panic!("no entry at {:?}", offset))
+}
+
+fn type_structure(infos: &[DwarfInfo], info: &DwarfInfo) -> TypeStructure {
+    TypeStructure {
+        tag: info.tag(),
+        name: info.name(),
+        byte_size: info.byte_size(),
+        encoding: info.encoding(),
+        data_member_location: info.data_member_location(),
+        type_structure: info
+            .type_offset()
+            .map(|offset| Box::new(type_structure(infos, find_info(infos, offset)))),
+        children: info
+            .children()
+            .iter()
+            .map(|child| type_structure(infos, child))
+            .collect(),
+    }
+}
+
+// Definitions which refer to their declarations by DW_AT_specification take the names and the types from them,
+// and the declarations are merged into the definitions as compilers may or may not write them separately
+fn variable_structures(infos: &[DwarfInfo]) -> Vec<VariableStructure> {
+    let specifications: Vec<Offset> = infos.iter().filter_map(DwarfInfo::specification).collect();
+    infos
+        .iter()
+        .filter(|info| {
+            info.tag() == DwarfTag::DW_TAG_variable && !specifications.contains(&info.offset())
+        })
+        .map(|info| {
+            let declaration = info
+                .specification()
+                .map(|offset| find_info(infos, offset))
+                .unwrap_or(info);
+            VariableStructure {
+                name: declaration.name(),
+                declaration: info.declaration(),
+                has_location: info.location().is_some(),
+                type_structure: declaration
+                    .type_offset()
+                    .map(|offset| type_structure(infos, find_info(infos, offset))),
+            }
+        })
+        .collect()
+}
+
+fn assert_variable_structures(expected: &[DwarfInfo], got: &[DwarfInfo]) {
+    assert!(!got.is_empty());
+    assert_eq!(variable_structures(expected), variable_structures(got));
+}
+
+fn dwarf_info_structure_test<S: Into<String>>(elf_path: S, expected: Vec<DwarfInfo>) {
+    init();
+
+    let got: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(elf_path.into())
+        .into_iter()
+        .collect();
+    assert_variable_structures(&expected, &got);
+}
+
+// Examples which need tools besides the compiler are not built without them
+fn example_built(elf_path: &str, tool: &str) -> bool {
+    if Command::new(tool).arg("--version").output().is_err() {
+        eprintln!("skipped: {} is not installed to build {}", tool, elf_path);
+        return false;
+    }
+    if !Path::new(elf_path).exists() {
+        eprintln!("skipped: {} is not built", elf_path);
+        return false;
+    }
+    true
+}
+
 #[test]
 #[ignore]
 fn dwarf_info_const() {
@@ -24,6 +126,7 @@ fn dwarf_info_const() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(72))
+            .external(true)
             .location(Location::new(8196))
             .build(),
         DwarfInfoBuilder::new()
@@ -31,6 +134,7 @@ fn dwarf_info_const() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(72))
@@ -57,6 +161,7 @@ fn dwarf_info_pointer() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("p")
             .type_offset(Offset::new(65))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -70,6 +175,7 @@ fn dwarf_info_pointer() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(78))
@@ -97,12 +203,14 @@ fn dwarf_info_typedef() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("unsigned int")
+            .encoding(BaseTypeEncoding::Unsigned)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(64))
             .tag(DwarfTag::DW_TAG_variable)
             .name("a")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -116,6 +224,7 @@ fn dwarf_info_typedef() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
     ];
 
@@ -142,18 +251,21 @@ fn dwarf_info_array() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(8)
             .name("long unsigned int")
+            .encoding(BaseTypeEncoding::Unsigned)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(68))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(75))
             .tag(DwarfTag::DW_TAG_variable)
             .name("hoges")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -203,12 +315,14 @@ fn dwarf_info_enum() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(85))
             .tag(DwarfTag::DW_TAG_variable)
             .name("ab")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -251,12 +365,14 @@ fn dwarf_info_anonymous_enum() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("unsigned int")
+            .encoding(BaseTypeEncoding::Unsigned)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(75))
             .tag(DwarfTag::DW_TAG_variable)
             .name("ab")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -270,6 +386,7 @@ fn dwarf_info_anonymous_enum() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
     ];
 
@@ -307,8 +424,8 @@ fn dwarf_info_structure() {
                     .type_offset(Offset::new(115))
                     .byte_size(4)
                     .bit_size(1)
-                    .bit_offset(23)
-                    .data_member_location(4)
+                    .bit_offset(0)
+                    .data_member_location(5)
                     .build(),
             ])
             .build(),
@@ -317,24 +434,28 @@ fn dwarf_info_structure() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(108))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(1)
             .name("char")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(115))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("unsigned int")
+            .encoding(BaseTypeEncoding::Unsigned)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(122))
             .tag(DwarfTag::DW_TAG_variable)
             .name("hoge")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -348,6 +469,108 @@ fn dwarf_info_structure() {
     dwarf_info_intoiterator_test("examples/structure", expected);
 }
 
+#[test]
+#[ignore]
+fn dwarf_info_bit_field() {
+    // (name, data_member_location, bit_offset, bit_size)
+    let expected = vec![
+        (String::from("c"), Some(0), None, None),
+        (String::from("a"), Some(1), Some(0), Some(3)),
+        (String::from("b"), Some(1), Some(3), Some(7)),
+        (String::from("d"), Some(4), None, None),
+        (String::from("e"), Some(8), Some(0), Some(2)),
+        (String::from("f"), Some(8), Some(2), Some(40)),
+    ];
+
+    // DW_AT_data_bit_offset and DW_AT_bit_offset are normalized to the same bit fields
+    for elf_path in &["examples/bit-field", "examples/bit-field-dwarf4"] {
+        let structure = DwarfInfoIntoIterator::new(String::from(*elf_path))
+            .into_iter()
+            .find(|info| info.tag() == DwarfTag::DW_TAG_structure_type)
+            .unwrap();
+        let got: Vec<_> = structure
+            .children()
+            .iter()
+            .map(|member| {
+                (
+                    member.name().unwrap(),
+                    member.data_member_location(),
+                    member.bit_offset(),
+                    member.bit_size(),
+                )
+            })
+            .collect();
+        assert_eq!(expected, got, "{}", elf_path);
+    }
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_bit_field_big_endian() {
+    // (name, data_member_location, bit_offset, bit_size)
+    // where bit offsets are counted from the LSB of the bytes the bit fields occupy
+    let expected = vec![
+        (String::from("c"), Some(0), None, None),
+        (String::from("a"), Some(1), Some(5), Some(3)),
+        (String::from("b"), Some(1), Some(6), Some(7)),
+        (String::from("d"), Some(4), None, None),
+        (String::from("e"), Some(8), Some(6), Some(2)),
+        (String::from("f"), Some(8), Some(6), Some(40)),
+    ];
+
+    for elf_path in &[
+        "examples/bit-field-big-endian",
+        "examples/bit-field-big-endian-dwarf4",
+    ] {
+        let structure = DwarfInfoIntoIterator::new(String::from(*elf_path))
+            .into_iter()
+            .find(|info| info.tag() == DwarfTag::DW_TAG_structure_type)
+            .unwrap();
+        let got: Vec<_> = structure
+            .children()
+            .iter()
+            .map(|member| {
+                (
+                    member.name().unwrap(),
+                    member.data_member_location(),
+                    member.bit_offset(),
+                    member.bit_size(),
+                )
+            })
+            .collect();
+        assert_eq!(expected, got, "{}", elf_path);
+    }
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_data_member_location_expression() {
+    // (name, data_member_location, bit_offset, bit_size)
+    let expected = vec![
+        (String::from("hoge"), Some(0), None, None),
+        (String::from("fuga"), Some(4), None, None),
+        (String::from("pohe"), Some(5), Some(0), Some(1)),
+    ];
+
+    let structure = DwarfInfoIntoIterator::new(String::from("examples/structure-dwarf2"))
+        .into_iter()
+        .find(|info| info.tag() == DwarfTag::DW_TAG_structure_type)
+        .unwrap();
+    let got: Vec<_> = structure
+        .children()
+        .iter()
+        .map(|member| {
+            (
+                member.name().unwrap(),
+                member.data_member_location(),
+                member.bit_offset(),
+                member.bit_size(),
+            )
+        })
+        .collect();
+    assert_eq!(expected, got);
+}
+
 #[test]
 #[ignore]
 fn dwarf_info_union() {
@@ -371,7 +594,8 @@ fn dwarf_info_union() {
                     .type_offset(Offset::new(93))
                     .byte_size(4)
                     .bit_size(8)
-                    .bit_offset(24)
+                    .bit_offset(0)
+                    .data_member_location(0)
                     .build(),
             ])
             .build(),
@@ -380,18 +604,21 @@ fn dwarf_info_union() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(1)
             .name("char")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(93))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(100))
             .tag(DwarfTag::DW_TAG_variable)
             .name("book")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -426,12 +653,14 @@ fn dwarf_info_anonymous_union_structure() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(73))
             .tag(DwarfTag::DW_TAG_variable)
             .name("a")
             .type_offset(Offset::new(45))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -458,12 +687,14 @@ fn dwarf_info_anonymous_union_structure() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(1)
             .name("char")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(130))
             .tag(DwarfTag::DW_TAG_variable)
             .name("ab")
             .type_offset(Offset::new(93))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -503,18 +734,21 @@ fn dwarf_info_function_pointer() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(72))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(1)
             .name("char")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(79))
             .tag(DwarfTag::DW_TAG_variable)
             .name("sub2")
             .type_offset(Offset::new(101))
+            .external(true)
             .location(Location::new(16424))
             .build(),
         DwarfInfoBuilder::new()
@@ -534,20 +768,6 @@ fn dwarf_info_function_pointer() {
             .tag(DwarfTag::DW_TAG_unimplemented)
             .name("sub1")
             .type_offset(Offset::new(65))
-            .children(vec![
-                DwarfInfoBuilder::new()
-                    .offset(Offset::new(167))
-                    .tag(DwarfTag::DW_TAG_formal_parameter)
-                    .name("i")
-                    .type_offset(Offset::new(65))
-                    .build(),
-                DwarfInfoBuilder::new()
-                    .offset(Offset::new(179))
-                    .tag(DwarfTag::DW_TAG_formal_parameter)
-                    .name("c")
-                    .type_offset(Offset::new(72))
-                    .build(),
-            ])
             .build(),
     ];
 
@@ -567,6 +787,7 @@ fn dwarf_info_void_function_pointer() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("callback")
             .type_offset(Offset::new(68))
+            .external(true)
             .location(Location::new(16432))
             .build(),
         DwarfInfoBuilder::new()
@@ -586,6 +807,7 @@ fn dwarf_info_void_function_pointer() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
     ];
 
@@ -625,12 +847,14 @@ fn dwarf_info_complex_structure() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(8)
             .name("long unsigned int")
+            .encoding(BaseTypeEncoding::Unsigned)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(95))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(1)
             .name("char")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(102))
@@ -672,6 +896,7 @@ fn dwarf_info_complex_structure() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(168))
@@ -700,6 +925,7 @@ fn dwarf_info_complex_structure() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("hoge")
             .type_offset(Offset::new(184))
+            .external(true)
             .location(Location::new(16480))
             .build(),
         DwarfInfoBuilder::new()
@@ -721,59 +947,53 @@ fn dwarf_info_many_compilation_units() {
             .offset(Offset::new(45))
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
-            .type_offset(Offset::new(65))
-            .location(Location::new(16424))
+            .type_offset(Offset::new(55))
+            .external(true)
+            .declaration(true)
             .build(),
         DwarfInfoBuilder::new()
-            .offset(Offset::new(65))
+            .offset(Offset::new(55))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
-            .offset(Offset::new(72))
+            .offset(Offset::new(62))
             .tag(DwarfTag::DW_TAG_unimplemented)
             .name("main")
-            .type_offset(Offset::new(65))
-            .children(vec![DwarfInfoBuilder::new()
-                .offset(Offset::new(106))
-                .tag(DwarfTag::DW_TAG_unimplemented)
-                .build()])
+            .type_offset(Offset::new(55))
             .build(),
         DwarfInfoBuilder::new()
-            .offset(Offset::new(120))
+            .offset(Offset::new(110))
             .tag(DwarfTag::DW_TAG_unimplemented)
             .name("sub1")
             .declaration(true)
             .build(),
         DwarfInfoBuilder::new()
-            .offset(Offset::new(182))
+            .offset(Offset::new(172))
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
-            .type_offset(Offset::new(202))
+            .type_offset(Offset::new(192))
+            .external(true)
             .location(Location::new(16424))
             .build(),
         DwarfInfoBuilder::new()
-            .offset(Offset::new(202))
+            .offset(Offset::new(192))
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
-            .offset(Offset::new(209))
+            .offset(Offset::new(199))
             .tag(DwarfTag::DW_TAG_unimplemented)
             .name("sub1")
-            .type_offset(Offset::new(202))
-            .children(vec![DwarfInfoBuilder::new()
-                .offset(Offset::new(239))
-                .tag(DwarfTag::DW_TAG_formal_parameter)
-                .name("i")
-                .type_offset(Offset::new(202))
-                .build()])
+            .type_offset(Offset::new(192))
             .build(),
     ];
 
-    dwarf_info_intoiterator_test("examples/many-compilation-units", expected);
+    dwarf_info_structure_test("examples/many-compilation-units", expected);
 }
 
 #[test]
@@ -785,6 +1005,7 @@ fn dwarf_info_extern() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(55))
+            .external(true)
             .declaration(true)
             .build(),
         DwarfInfoBuilder::new()
@@ -792,6 +1013,7 @@ fn dwarf_info_extern() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(62))
@@ -804,6 +1026,7 @@ fn dwarf_info_extern() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(136))
+            .external(true)
             .declaration(true)
             .build(),
         DwarfInfoBuilder::new()
@@ -811,6 +1034,7 @@ fn dwarf_info_extern() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(143))
@@ -832,6 +1056,7 @@ fn dwarf_info_volatile() {
             .tag(DwarfTag::DW_TAG_variable)
             .name("c")
             .type_offset(Offset::new(72))
+            .external(true)
             .location(Location::new(16428))
             .build(),
         DwarfInfoBuilder::new()
@@ -839,6 +1064,7 @@ fn dwarf_info_volatile() {
             .tag(DwarfTag::DW_TAG_base_type)
             .byte_size(4)
             .name("int")
+            .encoding(BaseTypeEncoding::Signed)
             .build(),
         DwarfInfoBuilder::new()
             .offset(Offset::new(72))
@@ -855,3 +1081,402 @@ fn dwarf_info_volatile() {
 
     dwarf_info_intoiterator_test("examples/volatile", expected);
 }
+
+fn split_dwarf_expected() -> Vec<DwarfInfo<'static>> {
+    let dwo_offset = |offset| Offset::new_in_section(Section::DebugInfoDwo(0), offset);
+    vec![
+        DwarfInfoBuilder::new()
+            .offset(dwo_offset(31))
+            .tag(DwarfTag::DW_TAG_structure_type)
+            .name("hoge")
+            .byte_size(8)
+            .children(vec![
+                DwarfInfoBuilder::new()
+                    .offset(dwo_offset(42))
+                    .tag(DwarfTag::DW_TAG_unimplemented)
+                    .name("hoge")
+                    .type_offset(dwo_offset(65))
+                    .data_member_location(0)
+                    .build(),
+                DwarfInfoBuilder::new()
+                    .offset(dwo_offset(53))
+                    .tag(DwarfTag::DW_TAG_unimplemented)
+                    .name("fuga")
+                    .type_offset(dwo_offset(72))
+                    .data_member_location(4)
+                    .build(),
+            ])
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(dwo_offset(65))
+            .tag(DwarfTag::DW_TAG_base_type)
+            .byte_size(4)
+            .name("int")
+            .encoding(BaseTypeEncoding::Signed)
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(dwo_offset(72))
+            .tag(DwarfTag::DW_TAG_base_type)
+            .byte_size(1)
+            .name("char")
+            .encoding(BaseTypeEncoding::Signed)
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(dwo_offset(77))
+            .tag(DwarfTag::DW_TAG_variable)
+            .name("hoge")
+            .type_offset(dwo_offset(31))
+            .external(true)
+            .location(Location::new(16408))
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(dwo_offset(90))
+            .tag(DwarfTag::DW_TAG_unimplemented)
+            .name("main")
+            .type_offset(dwo_offset(65))
+            .build(),
+    ]
+}
+
+// The entries are read from the split DWARF objects rather than the skeleton units
+fn split_dwarf_test(got: &[DwarfInfo]) {
+    assert_variable_structures(&split_dwarf_expected(), got);
+    assert!(got
+        .iter()
+        .all(|info| matches!(info.offset().section(), Section::DebugInfoDwo(_))));
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_split_dwarf() {
+    let elf_path = "examples/split-dwarf";
+    if !example_built("examples/split-dwarf.dwo", "gcc") {
+        return;
+    }
+    init();
+
+    let got: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(String::from(elf_path))
+        .into_iter()
+        .collect();
+    split_dwarf_test(&got);
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_split_dwarf_with_empty_package() {
+    if !example_built("examples/split-dwarf.dwo", "gcc") {
+        return;
+    }
+    init();
+
+    // an unreadable package is skipped, and the split DWARF objects are read instead
+    let dir = env::temp_dir().join(format!("troll-dwp-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let elf_path = dir.join("split-dwarf");
+    fs::copy("examples/split-dwarf", &elf_path).unwrap();
+    fs::write(dir.join("split-dwarf.dwp"), []).unwrap();
+    let elf_path = elf_path.to_str().unwrap().to_string();
+    let got: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(elf_path).into_iter().collect();
+    fs::remove_dir_all(&dir).unwrap();
+
+    split_dwarf_test(&got);
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_split_dwarf_package() {
+    let elf_path = "examples/split-dwarf-package";
+    if !example_built("examples/split-dwarf-package.dwp", "dwp") {
+        return;
+    }
+    init();
+
+    let got: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(String::from(elf_path))
+        .into_iter()
+        .collect();
+    split_dwarf_test(&got);
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_separate_debug_file() {
+    let expected = vec![
+        DwarfInfoBuilder::new()
+            .offset(Offset::new(46))
+            .tag(DwarfTag::DW_TAG_structure_type)
+            .name("hoge")
+            .byte_size(8)
+            .children(vec![
+                DwarfInfoBuilder::new()
+                    .offset(Offset::new(59))
+                    .tag(DwarfTag::DW_TAG_unimplemented)
+                    .name("hoge")
+                    .type_offset(Offset::new(84))
+                    .data_member_location(0)
+                    .build(),
+                DwarfInfoBuilder::new()
+                    .offset(Offset::new(71))
+                    .tag(DwarfTag::DW_TAG_unimplemented)
+                    .name("fuga")
+                    .type_offset(Offset::new(91))
+                    .data_member_location(4)
+                    .build(),
+            ])
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(Offset::new(84))
+            .tag(DwarfTag::DW_TAG_base_type)
+            .byte_size(4)
+            .name("int")
+            .encoding(BaseTypeEncoding::Signed)
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(Offset::new(91))
+            .tag(DwarfTag::DW_TAG_base_type)
+            .byte_size(1)
+            .name("char")
+            .encoding(BaseTypeEncoding::Signed)
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(Offset::new(98))
+            .tag(DwarfTag::DW_TAG_variable)
+            .name("hoge")
+            .type_offset(Offset::new(46))
+            .external(true)
+            .location(Location::new(16408))
+            .build(),
+        DwarfInfoBuilder::new()
+            .offset(Offset::new(120))
+            .tag(DwarfTag::DW_TAG_unimplemented)
+            .name("main")
+            .type_offset(Offset::new(84))
+            .build(),
+    ];
+
+    dwarf_info_structure_test("examples/separate-debug-file", expected);
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_supplementary_object() {
+    if !example_built("examples/supplementary-object.sup", "dwz") {
+        return;
+    }
+    init();
+
+    let got: Vec<DwarfInfo> =
+        DwarfInfoIntoIterator::new(String::from("examples/supplementary-object"))
+            .into_iter()
+            .collect();
+    let variable = got
+        .iter()
+        .find(|info| info.tag() == DwarfTag::DW_TAG_variable)
+        .unwrap();
+    let type_offset = variable.type_offset().unwrap();
+    assert_eq!(Some(String::from("hoge")), variable.name());
+    assert_eq!(Section::DebugInfoSup, type_offset.section());
+    assert!(got.iter().any(|info| info.offset() == type_offset));
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_malformed_supplementary_object() {
+    if !example_built("examples/supplementary-object.sup", "dwz") {
+        return;
+    }
+    init();
+
+    // a malformed supplementary object file is skipped, and the variables are still read
+    let dir = env::temp_dir().join(format!("troll-dwz-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let elf_path = dir.join("supplementary-object");
+    fs::copy("examples/supplementary-object", &elf_path).unwrap();
+    fs::write(dir.join("supplementary-object.sup"), b"not an ELF").unwrap();
+    let elf_path = elf_path.to_str().unwrap().to_string();
+    let got: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(elf_path).into_iter().collect();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let variable = got
+        .iter()
+        .find(|info| info.tag() == DwarfTag::DW_TAG_variable)
+        .unwrap();
+    assert_eq!(Some(String::from("hoge")), variable.name());
+}
+
+fn type_unit_test<S: Into<String>>(elf_path: S, type_section: Section) {
+    init();
+
+    let got: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(elf_path.into())
+        .into_iter()
+        .collect();
+    let typedef = got
+        .iter()
+        .find(|info| info.tag() == DwarfTag::DW_TAG_typedef)
+        .unwrap();
+    let type_offset = typedef.type_offset().unwrap();
+    let structure = got
+        .iter()
+        .find(|info| info.offset() == type_offset)
+        .unwrap();
+    assert_eq!(Some(String::from("Hoge")), typedef.name());
+    assert_eq!(type_section, type_offset.section());
+    assert_eq!(DwarfTag::DW_TAG_structure_type, structure.tag());
+    assert_eq!(Some(String::from("hoge")), structure.name());
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_type_units() {
+    type_unit_test("examples/type-units", Section::DebugTypes);
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_type_units_dwarf5() {
+    type_unit_test("examples/type-units-dwarf5", Section::DebugInfo);
+}
+
+fn compressed_debug_sections_test<S: Into<String>>(elf_path: S) {
+    init();
+
+    let expected: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(String::from("examples/structure"))
+        .into_iter()
+        .collect();
+    let got: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(elf_path.into())
+        .into_iter()
+        .collect();
+    assert!(!got.is_empty());
+    assert_eq!(expected, got);
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_compressed_debug_sections_zlib() {
+    compressed_debug_sections_test("examples/structure-zlib");
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_compressed_debug_sections_zlib_gnu() {
+    compressed_debug_sections_test("examples/structure-zlib-gnu");
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_compressed_debug_sections_zstd() {
+    compressed_debug_sections_test("examples/structure-zstd");
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_map_units() {
+    init();
+
+    let elf_path = "examples/many-compilation-units";
+    let expected: Vec<usize> = DwarfInfoIntoIterator::new(String::from(elf_path))
+        .into_units()
+        .iter()
+        .map(Vec::len)
+        .collect();
+    let got = DwarfInfoIntoIterator::new(String::from(elf_path)).map_units(|infos| infos.count());
+    assert_eq!(2, got.len());
+    assert_eq!(expected, got);
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_lookup_variable() {
+    init();
+
+    let elf_path = "examples/complex-structure";
+    let got = DwarfInfoIntoIterator::new(String::from(elf_path)).lookup_variable("hoge");
+    let all: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(String::from(elf_path))
+        .into_iter()
+        .collect();
+    assert!(got
+        .iter()
+        .any(|info| info.tag() == DwarfTag::DW_TAG_variable
+            && info.name() == Some(String::from("hoge"))));
+    // only the variable and its types are read
+    assert!(got.len() < all.len());
+    for info in got {
+        assert!(all.contains(&info));
+    }
+    assert!(DwarfInfoIntoIterator::new(String::from(elf_path))
+        .lookup_variable("fuga")
+        .is_empty());
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_lookup_variable_by_name_index() {
+    init();
+
+    let iter = DwarfInfoIntoIterator::new(String::from("examples/structure-pubnames"));
+    assert!(iter.has_name_index());
+    assert!(iter
+        .lookup_variable("hoge")
+        .iter()
+        .any(|info| info.tag() == DwarfTag::DW_TAG_variable
+            && info.name() == Some(String::from("hoge"))));
+    assert!(iter.lookup_variable("fuga").is_empty());
+    assert!(!DwarfInfoIntoIterator::new(String::from("examples/structure")).has_name_index());
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_source() {
+    init();
+
+    let elf_path = "examples/structure";
+    let infos: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(String::from(elf_path))
+        .source(true)
+        .into_iter()
+        .collect();
+    let hoge = infos
+        .iter()
+        .find(|info| {
+            info.tag() == DwarfTag::DW_TAG_variable && info.name() == Some(String::from("hoge"))
+        })
+        .unwrap();
+    let unit = hoge.compilation_unit().unwrap();
+    assert_eq!(Some(String::from("structure.c")), unit.name);
+    assert!(unit.comp_dir.is_some());
+    assert!(unit.producer.is_some());
+    assert_eq!(Some(String::from("structure.c")), hoge.decl_file());
+    assert_eq!(Some(5), hoge.decl_line());
+
+    // sources are not read by default
+    assert!(DwarfInfoIntoIterator::new(String::from(elf_path))
+        .into_iter()
+        .all(|info| info.compilation_unit().is_none() && info.decl_file().is_none()));
+}
+
+#[test]
+#[ignore]
+fn dwarf_info_static_variables() {
+    init();
+
+    let elf_path = "examples/static";
+    let counters: Vec<DwarfInfo> = DwarfInfoIntoIterator::new(String::from(elf_path))
+        .into_iter()
+        .filter(|info| {
+            info.tag() == DwarfTag::DW_TAG_variable && info.name() == Some(String::from("counter"))
+        })
+        .collect();
+    // variables local to the units are not external, and have their units
+    let units: Vec<Option<String>> = counters
+        .iter()
+        .map(|info| {
+            assert_eq!(None, info.external());
+            info.compilation_unit().and_then(|unit| unit.name.clone())
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            Some(String::from("static.c")),
+            Some(String::from("static-sub.c"))
+        ],
+        units
+    );
+}
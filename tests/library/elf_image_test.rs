@@ -0,0 +1,40 @@
+extern crate troll;
+
+use object::{Object, ObjectSection};
+use std::env;
+use std::fs;
+
+use troll::library::elf_image::*;
+use troll::library::memory::{Memory, WritableMemory};
+
+// The address and the file offset of the section in this test executable
+fn section(name: &str) -> (usize, usize) {
+    let data = fs::read(env::current_exe().unwrap()).unwrap();
+    let object = object::File::parse(&data).unwrap();
+    let section = object.section_by_name(name).unwrap();
+    let offset = section
+        .file_range()
+        .map_or(0, |(offset, _)| offset as usize);
+    (section.address() as usize, offset)
+}
+
+#[test]
+fn patch_elf_image() {
+    let elf_path = env::current_exe().unwrap();
+    let data = fs::read(&elf_path).unwrap();
+    let mut image = ElfImage::from_file(elf_path.to_str().unwrap()).unwrap();
+
+    let (address, offset) = section(".data");
+    assert_eq!(
+        image.read(address, 4),
+        Some(data[offset..offset + 4].to_vec())
+    );
+    assert!(image.write(address, &[1, 2, 3, 4]).is_ok());
+    assert_eq!(image.read(address, 4), Some(vec![1, 2, 3, 4]));
+
+    // .bss has no bytes in the file
+    let (address, _) = section(".bss");
+    assert_eq!(image.read(address, 1), None);
+    let message = image.write(address, &[1]).unwrap_err();
+    assert!(message.contains(".bss"), "{}", message);
+}
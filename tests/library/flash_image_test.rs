@@ -0,0 +1,93 @@
+extern crate troll;
+
+use std::env;
+use std::fs;
+
+use troll::library::flash_image::*;
+use troll::library::memory::Memory;
+
+#[test]
+fn merge_records() {
+    let image = FlashImage::new(vec![
+        (0x1004, vec![4, 5, 6, 7]),
+        (0x1000, vec![0, 1, 2, 3]),
+        (0x1006, vec![0x16, 0x17, 8]),
+        (0x2000, vec![0x20]),
+    ]);
+    assert_eq!(
+        image.read(0x1000, 9),
+        Some(vec![0, 1, 2, 3, 4, 5, 0x16, 0x17, 8])
+    );
+    assert_eq!(image.read(0x2000, 1), Some(vec![0x20]));
+    assert_eq!(image.read(0x1008, 2), None);
+    assert_eq!(image.read(0xfff, 1), None);
+
+    // later records overwrite earlier ones even at lower addresses
+    let image = FlashImage::new(vec![
+        (0x1002, vec![2, 3]),
+        (0x1006, vec![6]),
+        (0x1001, vec![0x11, 0x12, 0x13, 0x14, 0x15]),
+    ]);
+    assert_eq!(
+        image.read(0x1001, 6),
+        Some(vec![0x11, 0x12, 0x13, 0x14, 0x15, 6])
+    );
+    assert_eq!(image.read(0x1000, 1), None);
+}
+
+#[test]
+fn parse_flash_image() {
+    let hex: FlashImage = ":02000000AABB99\n:00000001FF\n".parse().unwrap();
+    let srecord: FlashImage = "S1050000AABB95\nS9030000FC\n".parse().unwrap();
+    assert_eq!(hex, srecord);
+    assert!("\x7fELF".parse::<FlashImage>().is_err());
+}
+
+// An ELF32 little endian executable with .rodata at 0x08000000 and .data at 0x20000000,
+// whose initial values are loaded from 0x08001000
+fn write_elf(path: &str) {
+    let mut elf = vec![0; 52];
+    elf[..7].copy_from_slice(b"\x7fELF\x01\x01\x01");
+    elf[0x10..0x12].copy_from_slice(&2u16.to_le_bytes());
+    elf[0x1c..0x20].copy_from_slice(&52u32.to_le_bytes());
+    elf[0x2a..0x2c].copy_from_slice(&32u16.to_le_bytes());
+    elf[0x2c..0x2e].copy_from_slice(&2u16.to_le_bytes());
+    let segments = [
+        (0x0800_0000u32, 0x0800_0000u32, 0x100u32),
+        (0x2000_0000, 0x0800_1000, 0x10),
+    ];
+    for (virtual_address, physical_address, size) in segments.iter() {
+        for word in &[
+            1,
+            0,
+            *virtual_address,
+            *physical_address,
+            *size,
+            *size,
+            0,
+            4,
+        ] {
+            elf.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    fs::write(path, elf).unwrap();
+}
+
+#[test]
+fn read_at_load_addresses() {
+    let elf_path = env::temp_dir().join(format!("troll-flash-{}", std::process::id()));
+    let elf_path = elf_path.to_str().unwrap();
+    write_elf(elf_path);
+    let image = FlashImage::new(vec![
+        (0x0800_0000, vec![1, 2, 3, 4]),
+        (0x0800_1000, vec![5, 6, 7, 8]),
+    ]);
+    let memory = LoadAddressMemory::new(image, elf_path);
+    fs::remove_file(elf_path).unwrap();
+    let memory = memory.unwrap();
+
+    assert_eq!(memory.read(0x0800_0000, 4), Some(vec![1, 2, 3, 4]));
+    assert_eq!(memory.read(0x2000_0002, 2), Some(vec![7, 8]));
+    // beyond the initial values in the image
+    assert_eq!(memory.read(0x2000_0004, 4), None);
+}
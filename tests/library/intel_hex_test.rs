@@ -0,0 +1,61 @@
+extern crate troll;
+
+use troll::library::intel_hex::*;
+
+#[test]
+fn write_intel_hex() {
+    let data: Vec<u8> = (0..20).collect();
+    let hex = to_intel_hex(&[(0x0800_0000, &data), (0x2000_fffe, &[0xaa, 0xbb, 0xcc])]).unwrap();
+    assert_eq!(
+        hex,
+        "\
+:020000040800F2
+:10000000000102030405060708090A0B0C0D0E0F78
+:0400100010111213A6
+:020000042000DA
+:02FFFE00AABB9C
+:020000042001D9
+:01000000CC33
+:00000001FF
+"
+    );
+}
+
+#[test]
+fn write_intel_hex_beyond_4gib() {
+    assert!(to_intel_hex(&[(0xffff_fffe, &[0, 0, 0])]).is_err());
+}
+
+#[test]
+fn read_intel_hex() {
+    let hex = "\
+:020000040800F2
+:10000000000102030405060708090A0B0C0D0E0F78
+:0400100010111213A6
+:020000021000EC
+:02000000AABB99
+:0400000508000000EF
+:00000001FF
+";
+    let data: Vec<u8> = (0..16).collect();
+    assert_eq!(
+        from_intel_hex(hex),
+        Ok(vec![
+            (0x0800_0000, data),
+            (0x0800_0010, vec![0x10, 0x11, 0x12, 0x13]),
+            (0x10000, vec![0xaa, 0xbb]),
+        ])
+    );
+}
+
+#[test]
+fn read_invalid_intel_hex() {
+    assert_eq!(
+        from_intel_hex(":020000040800F2\n:0400100010111213A7\n"),
+        Err(String::from(
+            "line 2: invalid checksum of the record: :0400100010111213A7"
+        ))
+    );
+    assert!(from_intel_hex(":0400100010111213\n").is_err());
+    assert!(from_intel_hex("S1130000\n").is_err());
+}
@@ -0,0 +1,193 @@
+extern crate troll;
+
+use troll::library::linker_map::*;
+
+const GNU_MAP: &str = "\
+Archive member included to satisfy reference by file (symbol)
+
+libb.a(b.o)                   a.o (shared)
+
+Memory Configuration
+
+Name             Origin             Length             Attributes
+*default*        0x0000000000000000 0xffffffffffffffff
+
+Linker script and memory map
+
+LOAD a.o
+LOAD libb.a
+.data           0x0000000000004000       0x14
+ *(.data .data.* .gnu.linkonce.d.*)
+ .data          0x0000000000004000        0x4 /usr/lib/x86_64-linux-gnu/Scrt1.o
+                0x0000000000004000                data_start
+ *fill*         0x0000000000004004        0x4 
+ .data.rel.local
+                0x0000000000004008        0x8 /usr/lib/gcc/x86_64-linux-gnu/12/crtbeginS.o
+                0x0000000000004008                __dso_handle
+ .data          0x0000000000004010        0x4 a.o
+                0x0000000000004010                value
+
+.tm_clone_table
+                0x0000000000004018        0x0
+
+.bss            0x0000000000004020       0x40
+ *(.bss .bss.* .gnu.linkonce.b.*)
+ .bss           0x0000000000004040       0x20 a.o
+                0x0000000000004040                big
+ .bss           0x0000000000004060       0x14 libb.a(b.o)
+                0x0000000000004060                shared
+                0x0000000000004068                other
+ *(COMMON)
+                0x0000000000004078                . = ALIGN ((. != 0x0)?0x8:0x1)
+                0x0000000000004078                _end = .
+
+.debug_info     0x0000000000000000      0x100
+ .debug_info    0x0000000000000000       0x80 a.o
+OUTPUT(app elf64-x86-64)
+";
+
+const LLD_MAP: &str = "             VMA              LMA     Size Align Out     In      Symbol
+          201120           201120        8     4 .data
+          201120           201120        4     4         a.o:(.data)
+          201120           201120        4     1                 value
+          201128           201128       18     8 .bss
+          201128           201128        8     8         libb.a(b.o):(.bss)
+          201128           201128        4     1                 shared
+          201130           201130       10     8         <internal>:(.bss)
+          201140           201140        0     1         . = ALIGN(0x8)
+               0                0       40     1 .debug_info
+               0                0       40     1         a.o:(.debug_info)
+";
+
+fn section(
+    output_section: &str,
+    name: &str,
+    address: usize,
+    size: usize,
+    object: &str,
+) -> InputSection {
+    InputSection {
+        output_section: String::from(output_section),
+        name: String::from(name),
+        address,
+        size,
+        object: String::from(object),
+    }
+}
+
+fn symbol(name: &str, address: usize, size: MapSymbolSize, object: &str) -> MapSymbol {
+    MapSymbol {
+        name: String::from(name),
+        address,
+        size,
+        object: String::from(object),
+    }
+}
+
+#[test]
+fn parse_gnu_map() {
+    let map = GNU_MAP.parse::<LinkerMap>().unwrap();
+
+    assert_eq!(
+        map.sections,
+        vec![
+            section(
+                ".data",
+                ".data",
+                0x4000,
+                0x4,
+                "/usr/lib/x86_64-linux-gnu/Scrt1.o"
+            ),
+            section(
+                ".data",
+                ".data.rel.local",
+                0x4008,
+                0x8,
+                "/usr/lib/gcc/x86_64-linux-gnu/12/crtbeginS.o"
+            ),
+            section(".data", ".data", 0x4010, 0x4, "a.o"),
+            section(".bss", ".bss", 0x4040, 0x20, "a.o"),
+            section(".bss", ".bss", 0x4060, 0x14, "libb.a(b.o)"),
+        ]
+    );
+    assert_eq!(
+        map.symbols,
+        vec![
+            symbol(
+                "data_start",
+                0x4000,
+                MapSymbolSize::AtMost(0x4),
+                "/usr/lib/x86_64-linux-gnu/Scrt1.o"
+            ),
+            symbol(
+                "__dso_handle",
+                0x4008,
+                MapSymbolSize::AtMost(0x8),
+                "/usr/lib/gcc/x86_64-linux-gnu/12/crtbeginS.o"
+            ),
+            symbol("value", 0x4010, MapSymbolSize::AtMost(0x4), "a.o"),
+            symbol("big", 0x4040, MapSymbolSize::AtMost(0x20), "a.o"),
+            symbol("shared", 0x4060, MapSymbolSize::AtMost(0x8), "libb.a(b.o)"),
+            symbol("other", 0x4068, MapSymbolSize::AtMost(0xc), "libb.a(b.o)"),
+        ]
+    );
+}
+
+#[test]
+fn parse_lld_map() {
+    let map = LLD_MAP.parse::<LinkerMap>().unwrap();
+
+    assert_eq!(
+        map.sections,
+        vec![
+            section(".data", ".data", 0x201120, 0x4, "a.o"),
+            section(".bss", ".bss", 0x201128, 0x8, "libb.a(b.o)"),
+            section(".bss", ".bss", 0x201130, 0x10, "<internal>"),
+        ]
+    );
+    assert_eq!(
+        map.symbols,
+        vec![
+            symbol("value", 0x201120, MapSymbolSize::Exact(4), "a.o"),
+            symbol("shared", 0x201128, MapSymbolSize::Exact(4), "libb.a(b.o)"),
+        ]
+    );
+}
+
+// older versions of lld without LMA
+#[test]
+fn parse_old_lld_map() {
+    let map = "\
+ Address     Size Align Out     In      Symbol
+  201120        8     4 .data
+  201120        4     4         a.o:(.data)
+  201120        4     1                 value
+"
+    .parse::<LinkerMap>()
+    .unwrap();
+
+    assert_eq!(
+        map.sections,
+        vec![section(".data", ".data", 0x201120, 0x4, "a.o")]
+    );
+    assert_eq!(
+        map.symbols,
+        vec![symbol("value", 0x201120, MapSymbolSize::Exact(4), "a.o")]
+    );
+}
+
+#[test]
+fn parse_unknown_map() {
+    assert!("not a map\n".parse::<LinkerMap>().is_err());
+}
+
+#[test]
+fn find_section() {
+    let map = GNU_MAP.parse::<LinkerMap>().unwrap();
+
+    let section = map.find_section(0x4064).unwrap();
+    assert_eq!(section.object, "libb.a(b.o)");
+    assert_eq!(section.archive(), "libb.a");
+    assert_eq!(map.find_section(0x4010).unwrap().archive(), "a.o");
+    assert_eq!(map.find_section(0x4020), None);
+}
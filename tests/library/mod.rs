@@ -1 +1,8 @@
+pub mod core_file_test;
 pub mod dwarf_test;
+pub mod elf_image_test;
+pub mod flash_image_test;
+pub mod intel_hex_test;
+pub mod linker_map_test;
+pub mod process_memory_test;
+pub mod srecord_test;
@@ -0,0 +1,64 @@
+extern crate troll;
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use troll::library::memory::{Memory, WritableMemory};
+use troll::library::process_memory::*;
+use troll::usecase::lookup_variable::LookupVariableUsecase;
+
+const ELF_PATH: &str = "examples/live";
+
+// Spawns the example and waits until the values are set
+fn spawn_live() -> Child {
+    let mut child = Command::new(ELF_PATH)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    child
+}
+
+fn address_of(path: &str) -> usize {
+    let variable_view = LookupVariableUsecase::new()
+        .lookup_variable(String::from(ELF_PATH), &path.parse().unwrap())
+        .unwrap();
+    variable_view.address.unwrap().into()
+}
+
+#[test]
+#[ignore]
+fn read_process_memory() {
+    let mut child = spawn_live();
+    let address = address_of("counter.ticks");
+    let process = ProcessMemory::attach(child.id(), ELF_PATH);
+    let value = process.map(|process| process.read(address, 4));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert_eq!(value, Ok(Some(42u32.to_le_bytes().to_vec())));
+}
+
+#[test]
+#[ignore]
+fn write_process_memory() {
+    let mut child = spawn_live();
+    let address = address_of("counter.ticks");
+    let value = ProcessMemory::attach_writable(child.id(), ELF_PATH).and_then(|mut process| {
+        process.write(address, &7u32.to_le_bytes())?;
+        Ok(process.read(address, 4))
+    });
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert_eq!(value, Ok(Some(7u32.to_le_bytes().to_vec())));
+}
+
+#[test]
+fn attach_with_non_elf_file() {
+    let process = ProcessMemory::attach(std::process::id(), "Cargo.toml");
+    assert!(process.is_err());
+}
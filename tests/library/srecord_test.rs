@@ -0,0 +1,35 @@
+extern crate troll;
+
+use troll::library::srecord::*;
+
+#[test]
+fn read_srecord() {
+    let srecord = "\
+S00600004844521B
+S1070000AABBCCDDEA
+S20801000001020304EC
+S30900002000DEADBEEF9E
+S5030003F9
+S9030000FC
+";
+    assert_eq!(
+        from_srecord(srecord),
+        Ok(vec![
+            (0x0000, vec![0xaa, 0xbb, 0xcc, 0xdd]),
+            (0x010000, vec![0x01, 0x02, 0x03, 0x04]),
+            (0x2000, vec![0xde, 0xad, 0xbe, 0xef]),
+        ])
+    );
+}
+
+#[test]
+fn read_invalid_srecord() {
+    assert_eq!(
+        from_srecord("S1070000AABBCCDDEB\n"),
+        Err(String::from(
+            "line 1: invalid checksum of the record: S1070000AABBCCDDEB"
+        ))
+    );
+    assert!(from_srecord("S10700\n").is_err());
+    assert!(from_srecord(":00000001FF\n").is_err());
+}